@@ -0,0 +1,195 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use crate::client::RancherClient;
+use crate::prtb::ProjectRoleTemplateBinding;
+use crate::reconcile::{
+    reconcile_project_role_template_bindings, reconcile_role_templates, ReconcileOptions, ReconcileReport,
+};
+use crate::rt::RoleTemplate;
+use crate::ResourceKind;
+
+/// The resources read from a directory, grouped by kind
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DirectoryResources {
+    pub role_templates: Vec<RoleTemplate>,
+    /// The bindings by project (namespace) they belong to
+    pub bindings: BTreeMap<String, Vec<ProjectRoleTemplateBinding>>,
+}
+
+/// The outcome of applying a directory
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ApplySummary {
+    /// `None` when the directory contains no role templates
+    pub role_templates: Option<ReconcileReport>,
+    /// The outcome for every project with bindings in the directory
+    pub bindings: BTreeMap<String, ReconcileReport>,
+}
+
+fn is_yaml(path: &Path) -> bool {
+    matches!(
+        path.extension().and_then(|ext| ext.to_str()),
+        Some("yaml") | Some("yml")
+    )
+}
+
+/// Collect the YAML files below a directory and its sub directories
+fn yaml_files(dir: &Path, files: &mut Vec<PathBuf>) -> Result<(), String> {
+    let entries = std::fs::read_dir(dir).map_err(|e| format!("Failed to read directory {}: {}", dir.display(), e))?;
+    for entry in entries {
+        let path = entry
+            .map_err(|e| format!("Failed to read directory {}: {}", dir.display(), e))?
+            .path();
+        if path.is_dir() {
+            yaml_files(&path, files)?;
+        } else if is_yaml(&path) {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// Read every `.yaml` and `.yml` file below a directory into its domain type
+///
+/// Each file holds a single object whose `kind` field selects the type, the other fields are
+/// those of the domain type (`RoleTemplate` or `ProjectRoleTemplateBinding`).
+///
+/// # Arguments
+///
+/// * `path` - The directory to read, sub directories are read as well
+///
+/// # Returns
+///
+/// * `DirectoryResources` - The objects found, in path order
+///
+/// # Errors
+///
+/// * `String` - A message naming the file that could not be read or parsed, or that has a missing or unknown `kind`
+pub fn load_directory(path: &Path) -> Result<DirectoryResources, String> {
+    let mut files = Vec::new();
+    yaml_files(path, &mut files)?;
+    files.sort();
+
+    let mut resources = DirectoryResources::default();
+    for file in files {
+        let content = std::fs::read_to_string(&file).map_err(|e| format!("Failed to read {}: {}", file.display(), e))?;
+        let value: serde_yaml::Value =
+            serde_yaml::from_str(&content).map_err(|e| format!("Failed to parse {}: {}", file.display(), e))?;
+        let kind = value
+            .get("kind")
+            .and_then(|kind| kind.as_str())
+            .ok_or_else(|| format!("Missing kind in {}", file.display()))?;
+
+        match kind {
+            kind if kind == ResourceKind::RoleTemplate.as_str() => {
+                let rt: RoleTemplate = serde_yaml::from_value(value.clone())
+                    .map_err(|e| format!("Invalid {} in {}: {}", kind, file.display(), e))?;
+                resources.role_templates.push(rt);
+            }
+            kind if kind == ResourceKind::ProjectRoleTemplateBinding.as_str() => {
+                let binding: ProjectRoleTemplateBinding = serde_yaml::from_value(value.clone())
+                    .map_err(|e| format!("Invalid {} in {}: {}", kind, file.display(), e))?;
+                resources
+                    .bindings
+                    .entry(binding.resolved_namespace())
+                    .or_default()
+                    .push(binding);
+            }
+            other => return Err(format!("Unknown kind {} in {}", other, file.display())),
+        }
+    }
+    Ok(resources)
+}
+
+/// Apply a directory of YAML resources to Rancher
+///
+/// The files are read with `load_directory`, then role templates are reconciled with
+/// `reconcile_role_templates` and the bindings of every project with
+/// `reconcile_project_role_template_bindings`. A kind without any file in the directory is not
+/// reconciled. Nothing is written when a file fails to load.
+///
+/// Orphans are only deleted when `options` prunes them.
+///
+/// # Arguments
+///
+/// * `client` - The client to send the requests with
+/// * `path` - The directory to apply
+/// * `options` - Whether to delete owned objects missing from the directory
+///
+/// # Returns
+///
+/// * `ApplySummary` - The outcome of every reconciliation
+///
+/// # Errors
+///
+/// * `String` - A message naming the file that failed to load, or the error of the first failed reconciliation
+#[async_backtrace::framed]
+pub async fn apply_directory(
+    client: &RancherClient,
+    path: &Path,
+    options: &ReconcileOptions,
+) -> Result<ApplySummary, String> {
+    let resources = load_directory(path)?;
+
+    let mut summary = ApplySummary::default();
+    if !resources.role_templates.is_empty() {
+        let report = reconcile_role_templates(client, &resources.role_templates, options)
+            .await
+            .map_err(|e| format!("Failed to reconcile the role templates: {}", e))?;
+        summary.role_templates = Some(report);
+    }
+    for (project_id, bindings) in &resources.bindings {
+        let project_report = reconcile_project_role_template_bindings(client, project_id, bindings, options)
+            .await
+            .map_err(|e| format!("Failed to reconcile the bindings of project {}: {}", project_id, e))?;
+        summary.bindings.insert(project_id.clone(), project_report);
+    }
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TestDir;
+
+    const ROLE_TEMPLATE: &str = "kind: RoleTemplate\nid: rt-1\ncontext: project\ndisplay_name: Role One\n";
+    const BINDING: &str = "kind: ProjectRoleTemplateBinding\nid: prtb-1\nnamespace: ''\nproject_name: c-1:p-1\nrole_template_name: rt-1\nuser_name: u-alice\n";
+
+    #[test]
+    fn test_load_directory_dispatches_by_kind() {
+        let dir = TestDir::new("dispatch");
+        dir.write("roles/rt-1.yaml", ROLE_TEMPLATE);
+        dir.write("projects/p-1/prtb-1.yml", BINDING);
+        dir.write("README.md", "not a resource");
+
+        let resources = load_directory(&dir.0).unwrap();
+
+        assert_eq!(resources.role_templates.len(), 1);
+        assert_eq!(resources.role_templates[0].id, "rt-1");
+        assert_eq!(resources.bindings.len(), 1);
+        assert_eq!(resources.bindings["p-1"][0].id, "prtb-1");
+    }
+
+    #[test]
+    fn test_load_directory_names_file_with_unknown_kind() {
+        let dir = TestDir::new("unknown-kind");
+        dir.write("roles/rt-1.yaml", ROLE_TEMPLATE);
+        dir.write("other/secret.yaml", "kind: Secret\nid: s-1\n");
+
+        let err = load_directory(&dir.0).unwrap_err();
+
+        assert!(err.contains("Unknown kind Secret"));
+        assert!(err.contains("secret.yaml"));
+    }
+
+    #[test]
+    fn test_load_directory_names_file_without_kind() {
+        let dir = TestDir::new("missing-kind");
+        dir.write("rt-1.yaml", "id: rt-1\n");
+
+        let err = load_directory(&dir.0).unwrap_err();
+
+        assert!(err.contains("Missing kind"));
+        assert!(err.contains("rt-1.yaml"));
+    }
+}
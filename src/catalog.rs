@@ -0,0 +1,492 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize, Serializer};
+
+use rancher_client::apis::{configuration::Configuration, Error, ResponseContent};
+use reqwest::StatusCode;
+
+use rancher_client::{
+    apis::management_cattle_io_v3_api::{
+        create_management_cattle_io_v3_catalog, delete_management_cattle_io_v3_catalog,
+        read_management_cattle_io_v3_catalog, CreateManagementCattleIoV3CatalogError,
+        DeleteManagementCattleIoV3CatalogError, ReadManagementCattleIoV3CatalogError,
+    },
+    models::{
+        IoCattleManagementv3Catalog, IoCattleManagementv3CatalogList,
+        IoCattleManagementv3CatalogSpec, IoK8sApimachineryPkgApisMetaV1ObjectMeta,
+    },
+};
+
+use crate::client::RequestOptions;
+use crate::metadata::validate_name;
+use crate::paginate::ContinueToken;
+use crate::request::{
+    get_list, is_write_success, list_query, optional_object, parse_response, send_request,
+    written_object, JSON_CONTENT_TYPE,
+};
+use crate::retry::RetryPolicy;
+use crate::{checked_limit, continue_token, ApiStatus, RancherError};
+
+/// The API path of the catalog list
+const CATALOGS_PATH: &str = "/apis/management.cattle.io/v3/catalogs";
+
+/// What a catalog password is serialized as, so it never ends up in an export
+pub const REDACTED: &str = "<redacted>";
+
+pub const CATALOG_EXCLUDE_PATHS: &[&str] = &[
+    "metadata.creationTimestamp",
+    "metadata.finalizers",
+    "metadata.generateName",
+    "metadata.generation",
+    "metadata.managedFields",
+    "metadata.resourceVersion",
+    "metadata.selfLink",
+    "metadata.uid",
+    "status",
+];
+
+/// Get a page of catalogs
+///
+/// A page rate limited by the server is retried with the default `RetryPolicy`, and the request
+/// times out after the timeout of `options`.
+///
+/// # Arguments
+///
+/// * `configuration` - The configuration to use for the request
+/// * `field_selector` - A selector to restrict the catalogs by their fields
+/// * `label_selector` - A selector to restrict the catalogs by their labels
+/// * `limit` - The largest number of catalogs to return, clamped to the largest page size of `options`
+/// * `continue_` - The token returned with the previous page, `None` for the first page
+/// * `options` - The timeout and largest page size of the request
+///
+/// # Returns
+///
+/// * `IoCattleManagementv3CatalogList` - The page of catalogs
+/// * `Option<ContinueToken>` - The token to fetch the next page, `None` on the last page
+///
+/// # Errors
+///
+/// * `RancherError` - The error that occurred while trying to get the catalogs
+///
+#[async_backtrace::framed]
+pub async fn get_catalogs(
+    configuration: &Configuration,
+    field_selector: Option<&str>,
+    label_selector: Option<&str>,
+    limit: Option<i32>,
+    continue_: Option<&ContinueToken>,
+    options: &RequestOptions,
+) -> Result<(IoCattleManagementv3CatalogList, Option<ContinueToken>), RancherError> {
+    let limit = checked_limit(limit, options)?;
+    let query = list_query(field_selector, label_selector, limit, continue_.map(ContinueToken::as_str));
+    let list: IoCattleManagementv3CatalogList = get_list(
+        configuration,
+        CATALOGS_PATH,
+        &query,
+        &RetryPolicy::default(),
+        options,
+    )
+    .await?;
+    let token = continue_token(list.metadata.as_deref()).map(ContinueToken::new);
+    Ok((list, token))
+}
+
+/// Get a catalog by name
+///
+/// # Arguments
+///
+/// * `configuration` - The configuration to use for the request
+/// * `name` - The name of the catalog
+///
+/// # Returns
+///
+/// * `Option<Catalog>` - The catalog, or `None` when it does not exist
+///
+/// # Errors
+///
+/// * `RancherError` - The error that occurred while reading or converting the catalog
+///
+#[async_backtrace::framed]
+pub async fn get_catalog(
+    configuration: &Configuration,
+    name: &str,
+) -> Result<Option<Catalog>, RancherError> {
+    let result = read_management_cattle_io_v3_catalog(configuration, name, None, None).await;
+    optional_object::<IoCattleManagementv3Catalog, _>(result, ReadManagementCattleIoV3CatalogError::UnknownValue)?
+        .map(Catalog::try_from)
+        .transpose()
+        .map_err(RancherError::conversion)
+}
+
+/// Create a catalog
+///
+/// # Arguments
+///
+/// * `configuration` - The configuration to use for the request
+/// * `catalog` - The catalog to create
+///
+/// # Returns
+///
+/// * `Catalog` - The catalog as created by the server
+///
+/// # Errors
+///
+/// * `RancherError` - The error that occurred while converting or creating the catalog
+///
+#[async_backtrace::framed]
+pub async fn create_catalog(
+    configuration: &Configuration,
+    catalog: &Catalog,
+) -> Result<Catalog, RancherError> {
+    let body = IoCattleManagementv3Catalog::try_from(catalog.clone()).map_err(RancherError::conversion)?;
+
+    let result = create_management_cattle_io_v3_catalog(configuration, body, None, None, None, None).await;
+    match result {
+        Err(e) => Err(e.into()),
+        Ok(response_content) => {
+            // Match on the status code and deserialize accordingly
+            match response_content.status {
+                status if is_write_success(status) => {
+                    // Deserialize the created IoCattleManagementv3Catalog when the body holds it
+                    match written_object::<IoCattleManagementv3Catalog>(&response_content.content) {
+                        Ok(Some(data)) => Catalog::try_from(data).map_err(RancherError::conversion),
+                        // accepted without the object, it is created as sent
+                        Ok(None) => Ok(catalog.clone()),
+                        Err(deserialize_err) => Err(RancherError::Serde(deserialize_err)),
+                    }
+                }
+                _ => {
+                    // If not a success, treat as UnknownValue
+                    match serde_json::from_str::<serde_json::Value>(&response_content.content) {
+                        Ok(unknown_data) => {
+                            // Handle the unknown response
+                            Err(Error::ResponseError(ResponseContent {
+                                status: response_content.status,
+                                content: response_content.content,
+                                entity: Some(CreateManagementCattleIoV3CatalogError::UnknownValue(
+                                    unknown_data,
+                                )),
+                            })
+                            .into())
+                        }
+                        Err(deserialize_err) => Err(RancherError::Serde(deserialize_err)),
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Delete a catalog
+///
+/// # Arguments
+///
+/// * `configuration` - The configuration to use for the request
+/// * `name` - The name of the catalog to delete
+///
+/// # Errors
+///
+/// * `RancherError` - `NotFound` when the catalog does not exist, so callers can treat it as already deleted, `Forbidden` when the server refuses the deletion, or the error that occurred while deleting the catalog
+///
+#[async_backtrace::framed]
+pub async fn delete_catalog(
+    configuration: &Configuration,
+    name: &str,
+) -> Result<(), RancherError> {
+    let result = delete_management_cattle_io_v3_catalog(
+        configuration,
+        name,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+    )
+    .await;
+    match result {
+        Err(e) => Err(e.into()),
+        Ok(response_content) => {
+            // Match on the status code
+            match response_content.status {
+                status if is_write_success(status) => Ok(()),
+                StatusCode::NOT_FOUND => Err(RancherError::NotFound),
+                StatusCode::FORBIDDEN => Err(RancherError::Forbidden(ApiStatus::parse(&response_content.content))),
+                _ => {
+                    // Otherwise treat as UnknownValue
+                    match serde_json::from_str::<serde_json::Value>(&response_content.content) {
+                        Ok(unknown_data) => {
+                            // Handle the unknown response
+                            Err(Error::ResponseError(ResponseContent {
+                                status: response_content.status,
+                                content: response_content.content,
+                                entity: Some(DeleteManagementCattleIoV3CatalogError::UnknownValue(
+                                    unknown_data,
+                                )),
+                            })
+                            .into())
+                        }
+                        Err(deserialize_err) => Err(RancherError::Serde(deserialize_err)),
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Ask Rancher to fetch the charts of a catalog again
+///
+/// Refreshing is not part of the Kubernetes API, it goes through the `refresh` action of the
+/// Rancher v3 API.
+///
+/// # Arguments
+///
+/// * `configuration` - The configuration to use for the request
+/// * `name` - The name of the catalog to refresh
+///
+/// # Errors
+///
+/// * `RancherError` - The error that occurred while triggering the refresh
+///
+#[async_backtrace::framed]
+pub async fn refresh_catalog(
+    configuration: &Configuration,
+    name: &str,
+) -> Result<(), RancherError> {
+    let path = format!("/v3/catalogs/{}?action=refresh", name);
+    let response_content = send_request(
+        configuration,
+        reqwest::Method::POST,
+        &path,
+        JSON_CONTENT_TYPE,
+        None,
+    )
+    .await?;
+    if response_content.content.trim().is_empty() && response_content.status.is_success() {
+        return Ok(());
+    }
+    parse_response(response_content)?;
+    Ok(())
+}
+
+/// Serialize a password as `REDACTED`
+fn redact<S: Serializer>(password: &Option<String>, serializer: S) -> Result<S::Ok, S::Error> {
+    match password {
+        Some(_) => serializer.serialize_some(REDACTED),
+        None => serializer.serialize_none(),
+    }
+}
+
+/// The credentials of a private catalog repository
+#[derive(Serialize, Deserialize, JsonSchema, Clone, PartialEq)]
+pub struct CatalogCredentials {
+    pub username: String,
+    /// The password or access token, always serialized as `REDACTED`
+    #[serde(skip_serializing_if = "Option::is_none", serialize_with = "redact")]
+    pub password: Option<String>,
+}
+
+impl std::fmt::Debug for CatalogCredentials {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CatalogCredentials")
+            .field("username", &self.username)
+            .field("password", &self.password.as_ref().map(|_| REDACTED))
+            .finish()
+    }
+}
+
+/// A Helm chart repository known to Rancher
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq)]
+pub struct Catalog {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub branch: Option<String>,
+    /// `helm` for a Helm repository, `git` for a git repository of charts
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub catalog_kind: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub credentials: Option<CatalogCredentials>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub helm_version: Option<String>,
+    pub id: String,
+    pub url: String,
+}
+
+impl TryFrom<IoCattleManagementv3Catalog> for Catalog {
+    type Error = &'static str;
+
+    fn try_from(value: IoCattleManagementv3Catalog) -> Result<Self, Self::Error> {
+        let metadata: IoK8sApimachineryPkgApisMetaV1ObjectMeta = value.metadata.ok_or("missing metadata")?;
+        let spec: IoCattleManagementv3CatalogSpec = *value.spec;
+
+        let credentials = spec.username.map(|username| CatalogCredentials {
+            username,
+            password: spec.password,
+        });
+
+        Ok(Catalog {
+            branch: spec.branch,
+            catalog_kind: spec.catalog_kind,
+            credentials,
+            description: spec.description,
+            helm_version: spec.helm_version,
+            id: metadata.name.ok_or("missing metadata.name")?,
+            url: spec.url,
+        })
+    }
+}
+
+impl TryFrom<Catalog> for IoCattleManagementv3Catalog {
+    type Error = &'static str;
+
+    fn try_from(value: Catalog) -> Result<Self, Self::Error> {
+        validate_name(&value.id)?;
+        let metadata = IoK8sApimachineryPkgApisMetaV1ObjectMeta {
+            name: Some(value.id),
+            ..Default::default()
+        };
+        let (username, password) = match value.credentials {
+            // a password read back from an export is a placeholder, not the secret
+            Some(credentials) if credentials.password.as_deref() == Some(REDACTED) => {
+                return Err("the catalog password is redacted");
+            }
+            Some(credentials) => (Some(credentials.username), credentials.password),
+            None => (None, None),
+        };
+        let spec = IoCattleManagementv3CatalogSpec {
+            branch: value.branch,
+            catalog_kind: value.catalog_kind,
+            description: value.description,
+            helm_version: value.helm_version,
+            password,
+            url: value.url,
+            username,
+            ..Default::default()
+        };
+
+        Ok(IoCattleManagementv3Catalog {
+            api_version: Some("management.cattle.io/v3".to_string()),
+            kind: Some("Catalog".to_string()),
+            metadata: Some(metadata),
+            spec: Box::new(spec),
+            ..Default::default()
+        })
+    }
+}
+
+impl PartialEq<Catalog> for IoCattleManagementv3Catalog {
+    /// The password is not compared, Rancher does not return it
+    fn eq(&self, other: &Catalog) -> bool {
+        self.metadata.as_ref().and_then(|m| m.name.as_deref()) == Some(other.id.as_str())
+            && self.spec.branch == other.branch
+            && self.spec.catalog_kind == other.catalog_kind
+            && self.spec.description == other.description
+            && self.spec.helm_version == other.helm_version
+            && self.spec.url == other.url
+            && self.spec.username.as_ref() == other.credentials.as_ref().map(|c| &c.username)
+    }
+}
+
+impl PartialEq<IoCattleManagementv3Catalog> for Catalog {
+    fn eq(&self, other: &IoCattleManagementv3Catalog) -> bool {
+        other == self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_catalog() -> Catalog {
+        Catalog {
+            branch: Some("main".to_string()),
+            catalog_kind: Some("helm".to_string()),
+            credentials: Some(CatalogCredentials {
+                username: "ci".to_string(),
+                password: Some("hunter2".to_string()),
+            }),
+            description: Some("Charts of the platform team".to_string()),
+            helm_version: Some("helm_v3".to_string()),
+            id: "platform".to_string(),
+            url: "https://charts.example.com".to_string(),
+        }
+    }
+
+    fn sample_iocattle_catalog() -> IoCattleManagementv3Catalog {
+        IoCattleManagementv3Catalog {
+            api_version: Some("management.cattle.io/v3".to_string()),
+            kind: Some("Catalog".to_string()),
+            metadata: Some(IoK8sApimachineryPkgApisMetaV1ObjectMeta {
+                name: Some("platform".to_string()),
+                ..Default::default()
+            }),
+            spec: Box::new(IoCattleManagementv3CatalogSpec {
+                branch: Some("main".to_string()),
+                catalog_kind: Some("helm".to_string()),
+                description: Some("Charts of the platform team".to_string()),
+                helm_version: Some("helm_v3".to_string()),
+                url: "https://charts.example.com".to_string(),
+                username: Some("ci".to_string()),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_inequality_on_url() {
+        let catalog = sample_catalog();
+        let mut iocatalog = sample_iocattle_catalog();
+        iocatalog.spec.url = "https://charts.example.org".to_string();
+
+        assert_ne!(catalog, iocatalog);
+        assert_ne!(iocatalog, catalog);
+    }
+
+    #[test]
+    fn test_try_from_iocattle_to_catalog() {
+        let catalog = Catalog::try_from(sample_iocattle_catalog()).unwrap();
+
+        assert_eq!(catalog.id, "platform");
+        assert_eq!(catalog.credentials.unwrap().username, "ci");
+    }
+
+    #[test]
+    fn test_try_from_catalog_to_iocattle_sends_password() {
+        let iocatalog = IoCattleManagementv3Catalog::try_from(sample_catalog()).unwrap();
+
+        assert_eq!(iocatalog.spec.password.as_deref(), Some("hunter2"));
+        assert_eq!(iocatalog.spec.branch.as_deref(), Some("main"));
+    }
+
+    #[test]
+    fn test_password_is_redacted_on_serialize() {
+        let catalog = sample_catalog();
+
+        let yaml = serde_yaml::to_string(&catalog).unwrap();
+        assert!(!yaml.contains("hunter2"));
+        assert!(yaml.contains(REDACTED));
+        assert!(!format!("{:?}", catalog).contains("hunter2"));
+    }
+
+    #[test]
+    fn test_redacted_password_is_not_sent() {
+        let mut catalog = sample_catalog();
+        catalog.credentials.as_mut().unwrap().password = Some(REDACTED.to_string());
+
+        assert!(IoCattleManagementv3Catalog::try_from(catalog).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_refresh_catalog_posts_refresh_action() {
+        let rancher = crate::test_support::MockRancher::start().await;
+        rancher
+            .expect("POST", "/v3/catalogs/platform")
+            .with_query("action", "refresh")
+            .expect_calls(1)
+            .returning_json(200, serde_json::json!({}))
+            .await;
+
+        refresh_catalog(&rancher.configuration(), "platform").await.unwrap();
+    }
+}
@@ -0,0 +1,416 @@
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Duration;
+
+use futures::future::BoxFuture;
+use tokio::sync::RwLock;
+
+use rancher_client::apis::configuration::{ApiKey, Configuration};
+
+use crate::{RancherError, MAX_PAGE_SIZE};
+
+/// The timeout of a request when none is configured
+pub const DEFAULT_REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Options applied to every request sent by the helpers
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RequestOptions {
+    /// How long to wait for a response before failing with `RancherError::Timeout`, `None` waits
+    /// forever
+    pub timeout: Option<Duration>,
+    /// Largest page size the list helpers send, larger limits are clamped to it
+    pub max_page_size: i32,
+}
+
+impl Default for RequestOptions {
+    /// A timeout of `DEFAULT_REQUEST_TIMEOUT` and pages of at most `MAX_PAGE_SIZE` items
+    fn default() -> Self {
+        RequestOptions {
+            timeout: Some(DEFAULT_REQUEST_TIMEOUT),
+            max_page_size: MAX_PAGE_SIZE,
+        }
+    }
+}
+
+/// Await a request, failing with `RancherError::Timeout` when it outlasts the timeout of `options`
+///
+/// # Arguments
+///
+/// * `options` - The timeout to apply
+/// * `request` - The request to await
+///
+/// # Returns
+///
+/// * `T` - The result of the request
+///
+/// # Errors
+///
+/// * `RancherError` - `Timeout` on expiry, or the error of the request
+pub async fn with_timeout<T, E, Fut>(options: &RequestOptions, request: Fut) -> Result<T, RancherError>
+where
+    E: Into<RancherError>,
+    Fut: Future<Output = Result<T, E>>,
+{
+    match options.timeout {
+        Some(timeout) => tokio::time::timeout(timeout, request)
+            .await
+            .map_err(|_| RancherError::Timeout(timeout))?
+            .map_err(Into::into),
+        None => request.await.map_err(Into::into),
+    }
+}
+
+/// Replace the HTTP client of a configuration with one trusting `certificate_authority`
+///
+/// The client is left untouched when there is no certificate authority and certificates are
+/// verified.
+///
+/// # Arguments
+///
+/// * `configuration` - The configuration to update
+/// * `certificate_authority` - A PEM certificate to trust on top of the system roots
+/// * `insecure` - Whether to accept any certificate, for development instances only
+///
+/// # Errors
+///
+/// * `RancherError` - `Invalid` when the certificate cannot be parsed, `Transport` when the client cannot be built
+pub(crate) fn configure_tls(
+    configuration: &mut Configuration,
+    certificate_authority: Option<&[u8]>,
+    insecure: bool,
+) -> Result<(), RancherError> {
+    if certificate_authority.is_none() && !insecure {
+        return Ok(());
+    }
+    let mut builder = reqwest::Client::builder().danger_accept_invalid_certs(insecure);
+    if let Some(pem) = certificate_authority {
+        let certificate = reqwest::Certificate::from_pem(pem)
+            .map_err(|e| RancherError::Invalid(format!("invalid certificate authority: {}", e)))?;
+        builder = builder.add_root_certificate(certificate);
+    }
+    let client = builder.build().map_err(RancherError::Transport)?;
+    configuration.client = reqwest_middleware::ClientBuilder::new(client).build();
+    Ok(())
+}
+
+/// Build a configuration from the environment, the entry point of CI jobs
+///
+/// | Variable | |
+/// |---|---|
+/// | `RANCHER_URL` | The URL of Rancher, e.g. `https://rancher.example.com` |
+/// | `RANCHER_TOKEN` | The bearer token, e.g. `token-abc12:secret` |
+/// | `RANCHER_INSECURE` | Optional, `true` or `1` to accept any certificate |
+/// | `RANCHER_CA_FILE` | Optional, the path of a PEM certificate authority to trust |
+///
+/// # Returns
+///
+/// * `Configuration` - The configuration to use for the requests
+///
+/// # Errors
+///
+/// * `RancherError` - `Invalid` naming the variable that is missing or invalid, or the error that
+///   occurred while reading the certificate authority
+pub fn configuration_from_env() -> Result<Configuration, RancherError> {
+    configuration_from_vars(|name| std::env::var(name).ok())
+}
+
+/// Build a configuration from variables looked up by `var`, see `configuration_from_env`
+fn configuration_from_vars(var: impl Fn(&str) -> Option<String>) -> Result<Configuration, RancherError> {
+    let required = |name: &str| {
+        var(name)
+            .filter(|value| !value.is_empty())
+            .ok_or_else(|| RancherError::Invalid(format!("{} is not set", name)))
+    };
+    let url = required("RANCHER_URL")?;
+    let token = required("RANCHER_TOKEN")?;
+    let insecure = match var("RANCHER_INSECURE").as_deref() {
+        None | Some("") | Some("false") | Some("0") => false,
+        Some("true") | Some("1") => true,
+        Some(other) => {
+            return Err(RancherError::Invalid(format!(
+                "RANCHER_INSECURE must be true or false, got {}",
+                other
+            )))
+        }
+    };
+    let certificate_authority = match var("RANCHER_CA_FILE").filter(|path| !path.is_empty()) {
+        Some(path) => Some(std::fs::read(&path).map_err(|e| {
+            RancherError::Invalid(format!("failed to read RANCHER_CA_FILE {}: {}", path, e))
+        })?),
+        None => None,
+    };
+
+    let mut configuration = crate::rancher_config_init(url.trim_end_matches('/'), &token);
+    configure_tls(&mut configuration, certificate_authority.as_deref(), insecure)?;
+    Ok(configuration)
+}
+
+/// A callback returning a fresh bearer token, typically read from a token vault
+pub type TokenRefresh = Arc<dyn Fn() -> BoxFuture<'static, String> + Send + Sync>;
+
+/// A `Configuration` shared by the requests of a long-running process, whose token can be
+/// refreshed when it expires
+///
+/// Requests are sent through `call`, which hands them the current configuration and fails them
+/// when they outlast the timeout of the request options. When one fails with a 401 and
+/// `on_unauthorized` is set, the callback is asked for a new token, the configuration is updated
+/// and the request is sent once more.
+pub struct RancherClient {
+    configuration: RwLock<Configuration>,
+    on_unauthorized: Option<TokenRefresh>,
+    options: RequestOptions,
+}
+
+impl RancherClient {
+    pub fn new(configuration: Configuration) -> Self {
+        RancherClient {
+            configuration: RwLock::new(configuration),
+            on_unauthorized: None,
+            options: RequestOptions::default(),
+        }
+    }
+
+    /// Apply `options` to every request sent through `call`
+    pub fn with_request_options(mut self, options: RequestOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Trust a private certificate authority, on top of the system roots
+    ///
+    /// The HTTP client of the configuration is rebuilt with the certificate as a root, so a
+    /// Rancher served with a certificate of an on-premise CA is reached without disabling TLS
+    /// verification.
+    ///
+    /// # Arguments
+    ///
+    /// * `pem` - The PEM encoded certificate of the certificate authority
+    ///
+    /// # Errors
+    ///
+    /// * `RancherError` - `Invalid` when the certificate cannot be parsed, `Transport` when the client cannot be built
+    pub fn with_ca_cert(mut self, pem: &[u8]) -> Result<Self, RancherError> {
+        configure_tls(self.configuration.get_mut(), Some(pem), false)?;
+        Ok(self)
+    }
+
+    /// Refresh the token with `on_unauthorized` when a request fails with a 401
+    pub fn with_token_refresh(mut self, on_unauthorized: TokenRefresh) -> Self {
+        self.on_unauthorized = Some(on_unauthorized);
+        self
+    }
+
+    /// A copy of the current configuration, carrying the latest token
+    pub async fn configuration(&self) -> Configuration {
+        self.configuration.read().await.clone()
+    }
+
+    /// The options applied to every request sent through `call`
+    pub fn request_options(&self) -> &RequestOptions {
+        &self.options
+    }
+
+    /// Replace the token of the configuration
+    ///
+    /// The bearer access token is replaced when the configuration uses one, otherwise the API key
+    /// is set to `Bearer <token>` as `rancher_config_init` does.
+    pub async fn set_token(&self, token: String) {
+        let mut configuration = self.configuration.write().await;
+        if configuration.bearer_access_token.is_some() {
+            configuration.bearer_access_token = Some(token);
+        } else {
+            configuration.api_key = Some(ApiKey {
+                prefix: Some("Bearer".to_string()),
+                key: token,
+            });
+        }
+    }
+
+    /// Send a request with the current configuration, refreshing the token and retrying once on a 401
+    ///
+    /// # Arguments
+    ///
+    /// * `op` - The request to send, given the configuration to send it with
+    ///
+    /// # Returns
+    ///
+    /// * `T` - The result of the request
+    ///
+    /// # Errors
+    ///
+    /// * `RancherError` - The error of the request, `Timeout` when it outlasts the timeout, `Unauthorized` when the refreshed token is rejected as well
+    pub async fn call<T, E, F, Fut>(&self, op: F) -> Result<T, RancherError>
+    where
+        E: Into<RancherError>,
+        F: Fn(Configuration) -> Fut,
+        Fut: Future<Output = Result<T, E>>,
+    {
+        match with_timeout(&self.options, op(self.configuration().await)).await {
+            Err(RancherError::Unauthorized(status)) => match &self.on_unauthorized {
+                Some(on_unauthorized) => {
+                    self.set_token(on_unauthorized().await).await;
+                    with_timeout(&self.options, op(self.configuration().await)).await
+                }
+                None => Err(RancherError::Unauthorized(status)),
+            },
+            result => result,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rt::get_role_template;
+    use crate::test_support::{role_template_json, MockRancher};
+    use wiremock::ResponseTemplate;
+
+    #[tokio::test]
+    async fn test_unauthorized_request_succeeds_after_refresh() {
+        let rancher = MockRancher::start().await;
+        rancher
+            .expect_get_role_template("rt-1")
+            .times(1)
+            .expect_calls(1)
+            .returning_status(401, "Unauthorized", "token expired")
+            .await;
+        rancher
+            .expect_get_role_template("rt-1")
+            .with_header("authorization", "Bearer token-fresh:secret")
+            .expect_calls(1)
+            .returning_json(200, role_template_json("rt-1"))
+            .await;
+
+        let client = RancherClient::new(rancher.configuration()).with_token_refresh(Arc::new(|| {
+            Box::pin(async { "token-fresh:secret".to_string() })
+        }));
+        let role_template = client
+            .call(|configuration| async move { get_role_template(&configuration, "rt-1").await })
+            .await
+            .unwrap();
+
+        assert_eq!(role_template.unwrap().id, "rt-1");
+    }
+
+    #[tokio::test]
+    async fn test_unauthorized_without_refresh_fails() {
+        let rancher = MockRancher::start().await;
+        rancher
+            .expect_get_role_template("rt-1")
+            .expect_calls(1)
+            .returning_status(401, "Unauthorized", "token expired")
+            .await;
+
+        let client = RancherClient::new(rancher.configuration());
+        let result = client
+            .call(|configuration| async move { get_role_template(&configuration, "rt-1").await })
+            .await;
+
+        assert!(matches!(
+            result,
+            Err(RancherError::Unauthorized(status)) if status.message == "token expired"
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_delayed_response_times_out() {
+        let rancher = MockRancher::start().await;
+        rancher
+            .expect_get_role_template("rt-1")
+            .returning_template(
+                ResponseTemplate::new(200)
+                    .set_body_json(role_template_json("rt-1"))
+                    .set_delay(Duration::from_secs(5)),
+            )
+            .await;
+
+        let client = RancherClient::new(rancher.configuration()).with_request_options(RequestOptions {
+            timeout: Some(Duration::from_millis(50)),
+            ..RequestOptions::default()
+        });
+        let result = client
+            .call(|configuration| async move { get_role_template(&configuration, "rt-1").await })
+            .await;
+
+        assert!(matches!(
+            result,
+            Err(RancherError::Timeout(timeout)) if timeout == Duration::from_millis(50)
+        ));
+    }
+
+    fn vars(entries: &[(&str, &str)]) -> impl Fn(&str) -> Option<String> {
+        let entries: std::collections::HashMap<String, String> = entries
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect();
+        move |name| entries.get(name).cloned()
+    }
+
+    #[test]
+    fn test_configuration_from_vars_names_missing_token() {
+        let result = configuration_from_vars(vars(&[("RANCHER_URL", "https://rancher.example.com")]));
+
+        match result {
+            Err(RancherError::Invalid(message)) => assert_eq!(message, "RANCHER_TOKEN is not set"),
+            other => panic!("expected a missing variable, got {:?}", other.map(|c| c.base_path)),
+        }
+    }
+
+    #[test]
+    fn test_configuration_from_vars() {
+        let configuration = configuration_from_vars(vars(&[
+            ("RANCHER_URL", "https://rancher.example.com/"),
+            ("RANCHER_TOKEN", "token-abc12:secret"),
+            ("RANCHER_INSECURE", "true"),
+        ]))
+        .unwrap();
+
+        assert_eq!(configuration.base_path, "https://rancher.example.com");
+        let api_key = configuration.api_key.unwrap();
+        assert_eq!(api_key.prefix.as_deref(), Some("Bearer"));
+        assert_eq!(api_key.key, "token-abc12:secret");
+
+        let result = configuration_from_vars(vars(&[
+            ("RANCHER_URL", "https://rancher.example.com"),
+            ("RANCHER_TOKEN", "token-abc12:secret"),
+            ("RANCHER_INSECURE", "maybe"),
+        ]));
+        assert!(matches!(result, Err(RancherError::Invalid(_))));
+    }
+
+    /// A self-signed certificate, parseable but trusted by nothing
+    const TEST_CA: &str = "-----BEGIN CERTIFICATE-----
+MIIBkzCCATmgAwIBAgIUSdWcsqk0CmowSnzM+IeH8EPR22swCgYIKoZIzj0EAwIw
+HjEcMBoGA1UEAwwTcmFuY2hlci1jYWMgdGVzdCBDQTAgFw0yNjEwMTYwODUwMzBa
+GA8yMTI2MDkyMjA4NTAzMFowHjEcMBoGA1UEAwwTcmFuY2hlci1jYWMgdGVzdCBD
+QTBZMBMGByqGSM49AgEGCCqGSM49AwEHA0IABN54cPBdFd9hrcAz4p6vUJUCNeEp
+oZ+oMVNSPqOtyXpZTZaHan+lkfnHze6MqVzvNl0pVRfEg4WfH+7kPLinYMejUzBR
+MB0GA1UdDgQWBBSsJfjUbmJfMPPbVB1RLmNsVJuxljAfBgNVHSMEGDAWgBSsJfjU
+bmJfMPPbVB1RLmNsVJuxljAPBgNVHRMBAf8EBTADAQH/MAoGCCqGSM49BAMCA0gA
+MEUCICXNJJEM7RSyiOqfP9+V5+ibdxfw33KFhH7zNHO0kD61AiEA7YiD/GiitxUB
+ozqfeB5QjvcUbHLItzPwTe/TMEictvY=
+-----END CERTIFICATE-----
+";
+
+    #[tokio::test]
+    async fn test_with_ca_cert_keeps_configuration() {
+        let configuration = crate::rancher_config_init("https://rancher.example.com", "token-abc12:secret");
+
+        let client = RancherClient::new(configuration)
+            .with_ca_cert(TEST_CA.as_bytes())
+            .unwrap();
+
+        let configuration = client.configuration().await;
+        assert_eq!(configuration.base_path, "https://rancher.example.com");
+        assert_eq!(configuration.api_key.unwrap().key, "token-abc12:secret");
+    }
+
+    #[test]
+    fn test_with_ca_cert_rejects_invalid_pem() {
+        let pem = "-----BEGIN CERTIFICATE-----\nnot a certificate\n-----END CERTIFICATE-----\n";
+
+        let result = RancherClient::new(Configuration::new()).with_ca_cert(pem.as_bytes());
+
+        assert!(matches!(result, Err(RancherError::Invalid(_)) | Err(RancherError::Transport(_))));
+    }
+}
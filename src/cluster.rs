@@ -5,14 +5,20 @@ use reqwest::StatusCode;
 
 use rancher_client::{
     apis::management_cattle_io_v3_api::{
-        list_management_cattle_io_v3_clusters, ListManagementCattleIoV3ClustersError,
+        list_management_cattle_io_v3_clusters, read_management_cattle_io_v3_cluster,
+        ListManagementCattleIoV3ClustersError, ReadManagementCattleIoV3ClusterError,
     },
     models::{
         IoCattleManagementv3Cluster, IoCattleManagementv3ClusterList,
-        IoCattleManagementv3ClusterSpec, IoK8sApimachineryPkgApisMetaV1ObjectMeta,
+        IoCattleManagementv3ClusterSpec, IoCattleManagementv3ClusterSpecAgentEnvVarsInner,
+        IoK8sApimachineryPkgApisMetaV1ObjectMeta,
     },
 };
 
+use crate::metadata::validate_name;
+use crate::request::optional_object;
+use crate::RancherError;
+
 /// Get all clusters from an endpoint using the provided configuration
 ///
 /// # Arguments
@@ -80,12 +86,126 @@ pub async fn get_clusters(
     }
 }
 
+/// Get a cluster by its ID
+///
+/// # Arguments
+///
+/// * `configuration` - The configuration to use for the request
+/// * `name` - The ID of the cluster, e.g. `c-m-abcd1234`
+///
+/// # Returns
+///
+/// * `Option<Cluster>` - The cluster, or `None` when it does not exist
+///
+/// # Errors
+///
+/// * `RancherError` - The error that occurred while reading or converting the cluster
+///
+#[async_backtrace::framed]
+pub async fn get_cluster(
+    configuration: &Configuration,
+    name: &str,
+) -> Result<Option<Cluster>, RancherError> {
+    let result = read_management_cattle_io_v3_cluster(configuration, name, None, None).await;
+    optional_object::<IoCattleManagementv3Cluster, _>(
+        result,
+        ReadManagementCattleIoV3ClusterError::UnknownValue,
+    )?
+    .map(Cluster::try_from)
+    .transpose()
+    .map_err(RancherError::conversion)
+}
+
+/// Resolve a cluster given either its ID or its display name to its ID
+///
+/// An exact ID match wins over a display name, so a cluster displayed as the ID of another one
+/// cannot shadow it.
+///
+/// # Arguments
+///
+/// * `configuration` - The configuration to use for the request
+/// * `name_or_id` - The ID (`c-xxxxx`) or the display name of the cluster
+///
+/// # Returns
+///
+/// * `String` - The ID of the cluster
+///
+/// # Errors
+///
+/// * `RancherError` - `NotFound` when no cluster matches, `Invalid` when several clusters share the display name, or the error that occurred while listing the clusters
+///
+#[async_backtrace::framed]
+pub async fn resolve_cluster_id(
+    configuration: &Configuration,
+    name_or_id: &str,
+) -> Result<String, RancherError> {
+    let clusters = get_clusters(configuration).await?.items;
+    let id_of = |cluster: &IoCattleManagementv3Cluster| cluster.metadata.as_ref().and_then(|m| m.name.clone());
+
+    if clusters.iter().any(|cluster| id_of(cluster).as_deref() == Some(name_or_id)) {
+        return Ok(name_or_id.to_string());
+    }
+
+    let mut by_display_name: Vec<String> = clusters
+        .iter()
+        .filter(|cluster| cluster.spec.display_name == name_or_id)
+        .filter_map(id_of)
+        .collect();
+    match by_display_name.len() {
+        0 => Err(RancherError::NotFound),
+        1 => Ok(by_display_name.remove(0)),
+        _ => Err(RancherError::Invalid(format!(
+            "{} clusters are named {}: {}",
+            by_display_name.len(),
+            name_or_id,
+            by_display_name.join(", ")
+        ))),
+    }
+}
+
+/// An environment variable set on the cluster agent
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct AgentEnvVar {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value: Option<String>,
+}
+
+impl From<IoCattleManagementv3ClusterSpecAgentEnvVarsInner> for AgentEnvVar {
+    fn from(value: IoCattleManagementv3ClusterSpecAgentEnvVarsInner) -> Self {
+        AgentEnvVar {
+            name: value.name,
+            value: value.value,
+        }
+    }
+}
+
+impl From<AgentEnvVar> for IoCattleManagementv3ClusterSpecAgentEnvVarsInner {
+    fn from(value: AgentEnvVar) -> Self {
+        IoCattleManagementv3ClusterSpecAgentEnvVarsInner {
+            name: value.name,
+            value: value.value,
+            ..Default::default()
+        }
+    }
+}
+
+/// The managed subset of a cluster
+///
+/// Only the fields below are modeled, the rest of the cluster spec (provider config, node pools,
+/// etc.) is left to Rancher and is neither read nor written by this crate.
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct Cluster {
     pub id: String,
     pub display_name: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub description: Option<String>,
+    /// Whether project network isolation is enabled
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub enable_network_policy: Option<bool>,
+    /// Environment variables set on the cluster agent, in order
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub agent_env_vars: Option<Vec<AgentEnvVar>>,
 }
 
 impl Cluster {
@@ -94,7 +214,38 @@ impl Cluster {
             id,
             display_name: name,
             description,
+            enable_network_policy: None,
+            agent_env_vars: None,
+        }
+    }
+
+    /// List the fields that differ between two clusters
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - The cluster to compare against
+    ///
+    /// # Returns
+    ///
+    /// * `Vec<&'static str>` - The names of the differing fields, in declaration order
+    pub fn changed_fields(&self, other: &Cluster) -> Vec<&'static str> {
+        let mut fields = Vec::new();
+        if self.id != other.id {
+            fields.push("id");
+        }
+        if self.display_name != other.display_name {
+            fields.push("display_name");
+        }
+        if self.description != other.description {
+            fields.push("description");
+        }
+        if self.enable_network_policy != other.enable_network_policy {
+            fields.push("enable_network_policy");
+        }
+        if self.agent_env_vars != other.agent_env_vars {
+            fields.push("agent_env_vars");
         }
+        fields
     }
 }
 
@@ -120,6 +271,10 @@ impl TryFrom<IoCattleManagementv3Cluster> for Cluster {
             id: metadata.name.ok_or("missing name")?,
             display_name: spec.display_name,
             description: spec.description,
+            enable_network_policy: spec.enable_network_policy,
+            agent_env_vars: spec
+                .agent_env_vars
+                .map(|vars| vars.into_iter().map(AgentEnvVar::from).collect()),
         })
     }
 }
@@ -129,6 +284,7 @@ impl TryFrom<Cluster> for IoCattleManagementv3Cluster {
     type Error = &'static str;
 
     fn try_from(value: Cluster) -> Result<Self, Self::Error> {
+        validate_name(&value.id)?;
         let metadata = IoK8sApimachineryPkgApisMetaV1ObjectMeta {
             name: Some(value.id),
             ..Default::default()
@@ -136,6 +292,10 @@ impl TryFrom<Cluster> for IoCattleManagementv3Cluster {
         let spec = IoCattleManagementv3ClusterSpec {
             display_name: value.display_name,
             description: value.description,
+            enable_network_policy: value.enable_network_policy,
+            agent_env_vars: value
+                .agent_env_vars
+                .map(|vars| vars.into_iter().map(Into::into).collect()),
             ..Default::default()
         };
 
@@ -155,6 +315,13 @@ impl PartialEq<Cluster> for IoCattleManagementv3Cluster {
         lhs == rhs
             && self.spec.display_name == other.display_name
             && self.spec.description == other.description
+            && self.spec.enable_network_policy == other.enable_network_policy
+            && self
+                .spec
+                .agent_env_vars
+                .as_ref()
+                .map(|vars| vars.iter().cloned().map(AgentEnvVar::from).collect::<Vec<_>>())
+                == other.agent_env_vars
     }
 }
 
@@ -183,6 +350,8 @@ mod tests {
             id: "cluster-id".to_string(),
             display_name: "Test Cluster".to_string(),
             description: Some("A test cluster".to_string()),
+            enable_network_policy: None,
+            agent_env_vars: None,
         }
     }
 
@@ -337,7 +506,74 @@ mod tests {
         assert_eq!(ioc.spec.description, None);
     }
 
+    fn sample_cluster_with_agent_settings() -> Cluster {
+        Cluster {
+            enable_network_policy: Some(true),
+            agent_env_vars: Some(vec![AgentEnvVar {
+                name: "HTTP_PROXY".to_string(),
+                value: Some("http://proxy:3128".to_string()),
+            }]),
+            ..sample_cluster()
+        }
+    }
+
+    #[test]
+    fn test_round_trip_network_policy_and_agent_env_vars() {
+        let cluster = sample_cluster_with_agent_settings();
+
+        let ioc = IoCattleManagementv3Cluster::try_from(cluster.clone()).unwrap();
+        assert_eq!(ioc.spec.enable_network_policy, Some(true));
+        let env = ioc.spec.agent_env_vars.as_ref().unwrap();
+        assert_eq!(env.len(), 1);
+        assert_eq!(env[0].name, "HTTP_PROXY");
+        assert_eq!(env[0].value.as_deref(), Some("http://proxy:3128"));
+        assert_eq!(ioc, cluster);
 
+        let back = Cluster::try_from(ioc).unwrap();
+        assert_eq!(back, cluster);
+    }
+
+    #[test]
+    fn test_inequality_on_agent_env_var_value() {
+        let cluster = sample_cluster_with_agent_settings();
+        let mut ioc = IoCattleManagementv3Cluster::try_from(cluster.clone()).unwrap();
+        ioc.spec.agent_env_vars.as_mut().unwrap()[0].value = Some("http://other:3128".to_string());
 
+        assert_ne!(cluster, ioc);
+    }
 
+    #[test]
+    fn test_changed_fields() {
+        let current = sample_cluster();
+        let desired = sample_cluster_with_agent_settings();
+
+        assert!(current.changed_fields(&current).is_empty());
+        assert_eq!(
+            current.changed_fields(&desired),
+            vec!["enable_network_policy", "agent_env_vars"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_resolve_cluster_id_by_display_name() {
+        let rancher = crate::test_support::MockRancher::start().await;
+        let mut other = sample_iocattle_cluster();
+        other.metadata.as_mut().unwrap().name = Some("c-other".to_string());
+        other.spec.display_name = "Other Cluster".to_string();
+        rancher
+            .expect("GET", "/apis/management.cattle.io/v3/clusters")
+            .returning(vec![other, sample_iocattle_cluster()])
+            .await;
+        let configuration = rancher.configuration();
+
+        assert_eq!(
+            resolve_cluster_id(&configuration, "Test Cluster").await.unwrap(),
+            "cluster-id"
+        );
+        assert_eq!(resolve_cluster_id(&configuration, "c-other").await.unwrap(), "c-other");
+        assert!(matches!(
+            resolve_cluster_id(&configuration, "Missing").await,
+            Err(RancherError::NotFound)
+        ));
+    }
 }
@@ -0,0 +1,560 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use rancher_client::apis::{configuration::Configuration, Error, ResponseContent};
+use reqwest::StatusCode;
+
+use rancher_client::{
+    apis::management_cattle_io_v3_api::{
+        list_management_cattle_io_v3_cluster_role_template_binding_for_all_namespaces,
+        list_management_cattle_io_v3_namespaced_cluster_role_template_binding,
+        ListManagementCattleIoV3ClusterRoleTemplateBindingForAllNamespacesError,
+        ListManagementCattleIoV3NamespacedClusterRoleTemplateBindingError,
+    },
+    models::{
+        IoCattleManagementv3ClusterRoleTemplateBinding,
+        IoCattleManagementv3ClusterRoleTemplateBindingList,
+        IoK8sApimachineryPkgApisMetaV1ObjectMeta,
+    },
+};
+
+use crate::client::RequestOptions;
+use crate::metadata::{user_metadata_eq, validate_name};
+use crate::paginate::{ContinueToken, Paginator};
+use crate::request::{get_list, list_query};
+use crate::retry::RetryPolicy;
+use crate::rt::{check_context, BindingScope, RoleTemplate};
+use crate::{checked_limit, continue_token, RancherError, DEFAULT_PAGE_SIZE};
+
+/// The API path of the cluster role template binding list across all namespaces
+const CRTB_ALL_NAMESPACES_PATH: &str =
+    "/apis/management.cattle.io/v3/clusterroletemplatebindings";
+
+pub const CRTB_EXCLUDE_PATHS: &[&str] = &[
+    "metadata.creationTimestamp",
+    "metadata.finalizers",
+    "metadata.generateName",
+    "metadata.generation",
+    "metadata.managedFields",
+    "metadata.resourceVersion",
+    "metadata.selfLink",
+    "metadata.uid",
+];
+
+/// Get all cluster role template bindings from an endpoint using the provided configuration
+///
+/// # Arguments
+///
+/// * `configuration` - The configuration to use for the request
+///
+/// # Returns
+///
+/// * `IoCattleManagementv3ClusterRoleTemplateBindingList` - The list of cluster role template bindings
+/// * `Option<ContinueToken>` - The token to fetch the next page, `None` on the last page
+///
+/// # Errors
+///
+/// * `Error<ListManagementCattleIoV3ClusterRoleTemplateBindingForAllNamespacesError>` - The error that occurred while trying to get the bindings
+///
+#[async_backtrace::framed]
+pub async fn get_cluster_role_template_bindings(
+    configuration: &Configuration,
+    field_selector: Option<&str>,
+    label_selector: Option<&str>,
+    limit: Option<i32>,
+    resource_version: Option<&str>,
+    resource_version_match: Option<&str>,
+    continue_: Option<&str>,
+) -> Result<
+    (IoCattleManagementv3ClusterRoleTemplateBindingList, Option<ContinueToken>),
+    Error<ListManagementCattleIoV3ClusterRoleTemplateBindingForAllNamespacesError>,
+> {
+    let limit = checked_limit(limit, &RequestOptions::default())?;
+    let result = list_management_cattle_io_v3_cluster_role_template_binding_for_all_namespaces(
+        configuration,
+        None,
+        continue_,
+        field_selector,
+        label_selector,
+        limit,
+        None,
+        resource_version,
+        resource_version_match,
+        None,
+        None,
+        None,
+    )
+    .await;
+    match result {
+        Err(e) => Err(e),
+        Ok(response_content) => {
+            // Match on the status code and deserialize accordingly
+            match response_content.status {
+                StatusCode::OK => {
+                    // Try to deserialize the content into IoCattleManagementv3ClusterRoleTemplateBindingList (Status200 case)
+                    match serde_json::from_str::<IoCattleManagementv3ClusterRoleTemplateBindingList>(&response_content.content) {
+                        Ok(data) => {
+                            let token = continue_token(data.metadata.as_deref()).map(ContinueToken::new);
+                            Ok((data, token))
+                        }
+                        Err(deserialize_err) => Err(Error::Serde(deserialize_err)),
+                    }
+                }
+                _ => {
+                    // If not status 200, treat as UnknownValue
+                    match serde_json::from_str::<serde_json::Value>(&response_content.content) {
+                        Ok(unknown_data) => {
+                            // Handle the unknown response
+                            Err(Error::ResponseError(ResponseContent {
+                                status: response_content.status,
+                                content: response_content.content,
+                                entity: Some(ListManagementCattleIoV3ClusterRoleTemplateBindingForAllNamespacesError::UnknownValue(
+                                    unknown_data,
+                                )),
+                            }))
+                        }
+                        Err(deserialize_err) => Err(Error::Serde(deserialize_err)),
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Get every cluster role template binding across all namespaces, following the continue token
+///
+/// A page rate limited by the server is retried with the default `RetryPolicy`, one failing
+/// transiently with an exponential backoff, and every request times out after the timeout of
+/// `options`. Pages are `DEFAULT_PAGE_SIZE` items clamped to the largest page size of `options`.
+///
+/// # Arguments
+///
+/// * `configuration` - The configuration to use for the requests
+/// * `field_selector` - A selector to restrict the bindings by their fields
+/// * `label_selector` - A selector to restrict the bindings by their labels
+/// * `options` - The timeout and largest page size of the requests
+///
+/// # Returns
+///
+/// * `Vec<ClusterRoleTemplateBinding>` - The bindings of every page
+///
+/// # Errors
+///
+/// * `RancherError` - The first error that occurred while fetching a page or converting a binding
+///
+#[async_backtrace::framed]
+pub async fn get_all_cluster_role_template_bindings(
+    configuration: &Configuration,
+    field_selector: Option<&str>,
+    label_selector: Option<&str>,
+    options: &RequestOptions,
+) -> Result<Vec<ClusterRoleTemplateBinding>, RancherError> {
+    let limit = checked_limit(Some(DEFAULT_PAGE_SIZE), options)?;
+    let items = Paginator::new(move |continue_: Option<String>| async move {
+        let query = list_query(field_selector, label_selector, limit, continue_.as_deref());
+        let list: IoCattleManagementv3ClusterRoleTemplateBindingList = get_list(
+            configuration,
+            CRTB_ALL_NAMESPACES_PATH,
+            &query,
+            &RetryPolicy::default(),
+            options,
+        )
+        .await?;
+        Ok::<_, RancherError>((list.items, continue_token(list.metadata.as_deref())))
+    })
+    .collect_all()
+    .await?;
+
+    items
+        .into_iter()
+        .map(|item| {
+            ClusterRoleTemplateBinding::try_from(item).map_err(|e| RancherError::Invalid(e.to_string()))
+        })
+        .collect()
+}
+
+/// Get the cluster role template bindings of a cluster namespace
+///
+/// # Arguments
+///
+/// * `configuration` - The configuration to use for the request
+/// * `cluster_id` - The ID of the cluster (namespace) to get the bindings for
+/// * `field_selector` - A selector to restrict the bindings by their fields
+/// * `label_selector` - A selector to restrict the bindings by their labels
+/// * `limit` - The maximum number of bindings to return
+/// * `resource_version` - The resource version to list at
+/// * `resource_version_match` - How `resource_version` is matched
+/// * `continue_` - The continue token of the previous page
+///
+/// # Returns
+///
+/// * `IoCattleManagementv3ClusterRoleTemplateBindingList` - The list of cluster role template bindings
+///
+/// # Errors
+///
+/// * `Error<ListManagementCattleIoV3NamespacedClusterRoleTemplateBindingError>` - The error that occurred while trying to get the bindings
+#[async_backtrace::framed]
+pub async fn get_namespaced_cluster_role_template_bindings(
+    configuration: &Configuration,
+    cluster_id: &str,
+    field_selector: Option<&str>,
+    label_selector: Option<&str>,
+    limit: Option<i32>,
+    resource_version: Option<&str>,
+    resource_version_match: Option<&str>,
+    continue_: Option<&str>,
+) -> Result<
+    IoCattleManagementv3ClusterRoleTemplateBindingList,
+    Error<ListManagementCattleIoV3NamespacedClusterRoleTemplateBindingError>,
+> {
+    let limit = checked_limit(limit, &RequestOptions::default())?;
+    let result = list_management_cattle_io_v3_namespaced_cluster_role_template_binding(
+        configuration,
+        cluster_id,
+        None,
+        None,
+        continue_,
+        field_selector,
+        label_selector,
+        limit,
+        resource_version,
+        resource_version_match,
+        None,
+        None,
+        None,
+    )
+    .await;
+    match result {
+        Err(e) => Err(e),
+        Ok(response_content) => {
+            // Match on the status code and deserialize accordingly
+            match response_content.status {
+                StatusCode::OK => serde_json::from_str(&response_content.content).map_err(Error::Serde),
+                _ => {
+                    // If not status 200, treat as UnknownValue
+                    match serde_json::from_str::<serde_json::Value>(&response_content.content) {
+                        Ok(unknown_data) => {
+                            // Handle the unknown response
+                            Err(Error::ResponseError(ResponseContent {
+                                status: response_content.status,
+                                content: response_content.content,
+                                entity: Some(ListManagementCattleIoV3NamespacedClusterRoleTemplateBindingError::UnknownValue(
+                                    unknown_data,
+                                )),
+                            }))
+                        }
+                        Err(deserialize_err) => Err(Error::Serde(deserialize_err)),
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// A binding of a role template to a user or group on a whole cluster, the cluster analogue of
+/// `ProjectRoleTemplateBinding`
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq)]
+pub struct ClusterRoleTemplateBinding {
+    /// Annotations applied to the cluster role template binding.
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "crate::metadata::serialize_sorted"
+    )]
+    pub annotations: Option<std::collections::HashMap<String, String>>,
+
+    /// The ID of the cluster the role template is bound on
+    pub cluster_name: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub group_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub group_principal_name: Option<String>,
+
+    /// The name of the cluster role template binding (typically the Kubernetes metadata.name).
+    pub id: String,
+
+    /// Labels applied to the cluster role template binding
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "crate::metadata::serialize_sorted"
+    )]
+    pub labels: Option<std::collections::HashMap<String, String>>,
+
+    /// The namespace the binding exists in, the ID of its cluster
+    pub namespace: String,
+
+    /// The version of the object on the server, sent back unmodified on updates. Populated by the system. Read-only.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub resource_version: Option<String>,
+
+    pub role_template_name: String,
+
+    /// The UID of the binding. Rancher sets this value when the binding is created.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub uid: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user_principal_name: Option<String>,
+}
+
+impl ClusterRoleTemplateBinding {
+    /// Resolve the namespace the binding should be written to
+    ///
+    /// Returns `namespace` when it is set, otherwise the cluster ID, since cluster role template
+    /// bindings live in the namespace of their cluster.
+    pub fn resolved_namespace(&self) -> String {
+        if !self.namespace.is_empty() {
+            return self.namespace.clone();
+        }
+        self.cluster_name.clone()
+    }
+
+    /// Check that the role template this binding references can be bound to a cluster
+    ///
+    /// # Errors
+    ///
+    /// * `String` - The role template is a project role template, see `crate::rt::check_context`
+    pub fn check_role_template(&self, rt: &RoleTemplate) -> Result<(), String> {
+        check_context(rt, BindingScope::Cluster, &self.id)
+    }
+}
+
+impl TryFrom<IoCattleManagementv3ClusterRoleTemplateBinding> for ClusterRoleTemplateBinding {
+    type Error = &'static str;
+
+    fn try_from(value: IoCattleManagementv3ClusterRoleTemplateBinding) -> Result<Self, Self::Error> {
+        let metadata: IoK8sApimachineryPkgApisMetaV1ObjectMeta = value.metadata.ok_or("missing metadata")?;
+        let id = metadata.name.ok_or("missing name")?;
+
+        Ok(ClusterRoleTemplateBinding {
+            annotations: metadata.annotations,
+            cluster_name: value.cluster_name,
+            group_name: value.group_name,
+            group_principal_name: value.group_principal_name,
+            id,
+            labels: metadata.labels,
+            namespace: metadata.namespace.unwrap_or_default(),
+            resource_version: metadata.resource_version,
+            role_template_name: value.role_template_name,
+            uid: metadata.uid,
+            user_name: value.user_name,
+            user_principal_name: value.user_principal_name,
+        })
+    }
+}
+
+impl TryFrom<ClusterRoleTemplateBinding> for IoCattleManagementv3ClusterRoleTemplateBinding {
+    type Error = &'static str;
+
+    fn try_from(value: ClusterRoleTemplateBinding) -> Result<Self, Self::Error> {
+        validate_name(&value.id)?;
+        let namespace = value.resolved_namespace();
+        let metadata = IoK8sApimachineryPkgApisMetaV1ObjectMeta {
+            annotations: value.annotations,
+            labels: value.labels,
+            namespace: Some(namespace),
+            name: Some(value.id.clone()),
+            ..Default::default()
+        };
+
+        Ok(IoCattleManagementv3ClusterRoleTemplateBinding {
+            api_version: Some("management.cattle.io/v3".to_string()),
+            cluster_name: value.cluster_name,
+            group_name: value.group_name,
+            group_principal_name: value.group_principal_name,
+            kind: Some("ClusterRoleTemplateBinding".to_string()),
+            metadata: Some(metadata),
+            role_template_name: value.role_template_name,
+            user_name: value.user_name,
+            user_principal_name: value.user_principal_name,
+        })
+    }
+}
+
+impl PartialEq<ClusterRoleTemplateBinding> for IoCattleManagementv3ClusterRoleTemplateBinding {
+    fn eq(&self, other: &ClusterRoleTemplateBinding) -> bool {
+        let metadata = self.metadata.as_ref();
+
+        metadata.and_then(|m| m.name.as_deref()) == Some(other.id.as_str())
+            && metadata.and_then(|m| m.namespace.clone()) == Some(other.resolved_namespace())
+            && user_metadata_eq(metadata.and_then(|m| m.annotations.as_ref()), other.annotations.as_ref())
+            && user_metadata_eq(metadata.and_then(|m| m.labels.as_ref()), other.labels.as_ref())
+            && self.cluster_name == other.cluster_name
+            && self.group_name == other.group_name
+            && self.group_principal_name == other.group_principal_name
+            && self.role_template_name == other.role_template_name
+            && self.user_name == other.user_name
+            && self.user_principal_name == other.user_principal_name
+    }
+}
+
+impl PartialEq<IoCattleManagementv3ClusterRoleTemplateBinding> for ClusterRoleTemplateBinding {
+    fn eq(&self, other: &IoCattleManagementv3ClusterRoleTemplateBinding) -> bool {
+        other == self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_binding() -> ClusterRoleTemplateBinding {
+        ClusterRoleTemplateBinding {
+            annotations: Some(std::collections::HashMap::new()),
+            cluster_name: "c-1".to_string(),
+            group_name: None,
+            group_principal_name: None,
+            id: "crtb-1".to_string(),
+            labels: Some(std::collections::HashMap::new()),
+            namespace: "c-1".to_string(),
+            resource_version: Some("resource-version".to_string()),
+            role_template_name: "cluster-member".to_string(),
+            uid: Some("uid".to_string()),
+            user_name: Some("u-alice".to_string()),
+            user_principal_name: Some("local://u-alice".to_string()),
+        }
+    }
+
+    fn sample_iocattle_binding() -> IoCattleManagementv3ClusterRoleTemplateBinding {
+        IoCattleManagementv3ClusterRoleTemplateBinding {
+            api_version: Some("management.cattle.io/v3".to_string()),
+            cluster_name: "c-1".to_string(),
+            group_name: None,
+            group_principal_name: None,
+            kind: Some("ClusterRoleTemplateBinding".to_string()),
+            metadata: Some(IoK8sApimachineryPkgApisMetaV1ObjectMeta {
+                name: Some("crtb-1".to_string()),
+                namespace: Some("c-1".to_string()),
+                ..Default::default()
+            }),
+            role_template_name: "cluster-member".to_string(),
+            user_name: Some("u-alice".to_string()),
+            user_principal_name: Some("local://u-alice".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_equality_both_directions() {
+        let a = sample_binding();
+        let b = sample_iocattle_binding();
+
+        assert_eq!(a, b);
+        assert_eq!(b, a);
+    }
+
+    #[test]
+    fn test_try_from_iocattle_to_binding() {
+        let binding = ClusterRoleTemplateBinding::try_from(sample_iocattle_binding()).unwrap();
+
+        assert_eq!(binding.id, "crtb-1");
+        assert_eq!(binding.cluster_name, "c-1");
+        assert_eq!(binding.user_name.as_deref(), Some("u-alice"));
+    }
+
+    #[test]
+    fn test_try_from_binding_to_iocattle() {
+        let ioc = IoCattleManagementv3ClusterRoleTemplateBinding::try_from(sample_binding()).unwrap();
+
+        let metadata = ioc.metadata.unwrap();
+        assert_eq!(metadata.name.as_deref(), Some("crtb-1"));
+        assert_eq!(metadata.namespace.as_deref(), Some("c-1"));
+        assert_eq!(ioc.role_template_name, "cluster-member");
+    }
+
+    #[test]
+    fn test_round_trip_keeps_equality() {
+        let binding = sample_binding();
+        let ioc = IoCattleManagementv3ClusterRoleTemplateBinding::try_from(binding.clone()).unwrap();
+        let back = ClusterRoleTemplateBinding::try_from(ioc.clone()).unwrap();
+
+        assert_eq!(binding, ioc);
+        assert_eq!(back, ioc);
+    }
+
+    #[test]
+    fn test_try_from_binding_with_empty_namespace_uses_cluster_id() {
+        let mut binding = sample_binding();
+        binding.namespace = String::new();
+
+        let ioc = IoCattleManagementv3ClusterRoleTemplateBinding::try_from(binding).unwrap();
+
+        assert_eq!(ioc.metadata.unwrap().namespace.as_deref(), Some("c-1"));
+    }
+
+    #[test]
+    fn test_inequality_on_different_user() {
+        let a = sample_binding();
+        let mut b = sample_iocattle_binding();
+        b.user_name = Some("u-bob".to_string());
+
+        assert_ne!(a, b);
+        assert_ne!(b, a);
+    }
+
+    #[test]
+    fn test_inequality_on_different_cluster() {
+        let a = sample_binding();
+        let mut b = sample_iocattle_binding();
+        b.cluster_name = "c-2".to_string();
+
+        assert_ne!(a, b);
+        assert_ne!(b, a);
+    }
+
+    #[test]
+    fn test_inequality_on_different_labels() {
+        let a = sample_binding();
+        let mut b = sample_iocattle_binding();
+        b.metadata.as_mut().unwrap().labels = Some(std::collections::HashMap::from([(
+            "team".to_string(),
+            "platform".to_string(),
+        )]));
+
+        assert_ne!(a, b);
+        assert_ne!(b, a);
+    }
+
+    #[test]
+    fn test_missing_metadata_name() {
+        let mut b = sample_iocattle_binding();
+        b.metadata.as_mut().unwrap().name = None;
+
+        assert!(ClusterRoleTemplateBinding::try_from(b).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_get_all_bindings_follows_continue_token() {
+        let rancher = crate::test_support::MockRancher::start().await;
+        let named = |name: &str| {
+            let mut binding = sample_iocattle_binding();
+            binding.metadata.as_mut().unwrap().name = Some(name.to_string());
+            binding
+        };
+        rancher
+            .expect("GET", CRTB_ALL_NAMESPACES_PATH)
+            .without_query("continue")
+            .expect_calls(1)
+            .returning_page(vec![named("crtb-1"), named("crtb-2")], Some("page-2"))
+            .await;
+        rancher
+            .expect("GET", CRTB_ALL_NAMESPACES_PATH)
+            .with_query("continue", "page-2")
+            .expect_calls(1)
+            .returning_page(vec![named("crtb-3")], None)
+            .await;
+
+        let bindings = get_all_cluster_role_template_bindings(
+            &rancher.configuration(),
+            None,
+            None,
+            &RequestOptions::default(),
+        )
+        .await
+        .unwrap();
+
+        let ids: Vec<&str> = bindings.iter().map(|b| b.id.as_str()).collect();
+        assert_eq!(ids, vec!["crtb-1", "crtb-2", "crtb-3"]);
+    }
+}
@@ -0,0 +1,468 @@
+use std::io::IsTerminal;
+
+use serde_json::Value;
+
+/// Remove dot-separated paths (e.g. `metadata.creationTimestamp`) from a JSON value
+///
+/// Missing paths, and paths going through a value that is not an object, are ignored.
+///
+/// # Arguments
+///
+/// * `value` - The JSON value to prune
+/// * `paths` - The dot-separated paths to remove
+pub fn prune_paths(value: &mut Value, paths: &[&str]) {
+    for path in paths {
+        let parts: Vec<&str> = path.split('.').collect();
+        remove_path(value, &parts);
+    }
+}
+
+/// Remove a nested field, returning it if it existed
+fn remove_path(value: &mut Value, path: &[&str]) -> Option<Value> {
+    let (last_key, parents) = path.split_last()?;
+
+    let mut current = value;
+    // Traverse to the parent of the key to remove
+    for &key in parents {
+        current = current.as_object_mut()?.get_mut(key)?;
+    }
+
+    current.as_object_mut()?.remove(*last_key)
+}
+
+/// A single field that differs between two objects
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldChange {
+    /// Dotted path of the field, with array entries as `[index]` (e.g. `metadata.labels.team`, `rules[2].verbs`)
+    pub path: String,
+    /// The current value, `None` when the field is missing
+    pub from: Option<Value>,
+    /// The desired value, `None` when the field should be removed
+    pub to: Option<Value>,
+}
+
+/// The differences between a desired and an actual object, grouped by kind of change
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DiffReport {
+    /// Fields missing from the actual object (`from` is `None`)
+    pub added: Vec<FieldChange>,
+    /// Fields missing from the desired object (`to` is `None`)
+    pub removed: Vec<FieldChange>,
+    /// Fields present on both sides with different values
+    pub changed: Vec<FieldChange>,
+}
+
+impl DiffReport {
+    /// Group field changes into added, removed and changed fields, keeping their order
+    pub fn from_changes(changes: Vec<FieldChange>) -> Self {
+        let mut report = DiffReport::default();
+        for change in changes {
+            match (&change.from, &change.to) {
+                (None, _) => report.added.push(change),
+                (_, None) => report.removed.push(change),
+                _ => report.changed.push(change),
+            }
+        }
+        report
+    }
+
+    /// Compare two JSON values, see `diff_values`
+    pub fn between(actual: &Value, desired: &Value) -> Self {
+        DiffReport::from_changes(diff_values(actual, desired))
+    }
+
+    /// Whether the two objects are equivalent
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+
+    /// Total number of differing fields
+    pub fn len(&self) -> usize {
+        self.added.len() + self.removed.len() + self.changed.len()
+    }
+
+    /// Paths of every differing field, sorted
+    pub fn paths(&self) -> Vec<&str> {
+        let mut paths: Vec<&str> = self
+            .added
+            .iter()
+            .chain(&self.removed)
+            .chain(&self.changed)
+            .map(|change| change.path.as_str())
+            .collect();
+        paths.sort();
+        paths
+    }
+}
+
+/// Hash a JSON value (64-bit FNV-1a over its canonical form, stable across runs and platforms)
+///
+/// Object keys are hashed in sorted order, so the result does not depend on insertion order.
+///
+/// # Arguments
+///
+/// * `value` - The value to hash
+///
+/// # Returns
+///
+/// * `u64` - The hash
+pub fn hash_value(value: &Value) -> u64 {
+    let mut canonical = String::new();
+    write_canonical(value, &mut canonical);
+    fnv1a(canonical.as_bytes())
+}
+
+/// The 64-bit FNV-1a hash of some bytes, the one hash used for change detection
+///
+/// It is fast and stable but trivially reversible by guessing inputs, so it must not be used to
+/// hide identifiers; `report` uses a keyed HMAC for that.
+pub(crate) fn fnv1a(bytes: &[u8]) -> u64 {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for byte in bytes {
+        hash ^= u64::from(*byte);
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    hash
+}
+
+/// Write a value as compact JSON with sorted object keys
+fn write_canonical(value: &Value, out: &mut String) {
+    match value {
+        Value::Object(map) => {
+            let mut keys: Vec<&String> = map.keys().collect();
+            keys.sort();
+            out.push('{');
+            for (index, key) in keys.into_iter().enumerate() {
+                if index > 0 {
+                    out.push(',');
+                }
+                out.push_str(&Value::String(key.clone()).to_string());
+                out.push(':');
+                write_canonical(&map[key], out);
+            }
+            out.push('}');
+        }
+        Value::Array(items) => {
+            out.push('[');
+            for (index, item) in items.iter().enumerate() {
+                if index > 0 {
+                    out.push(',');
+                }
+                write_canonical(item, out);
+            }
+            out.push(']');
+        }
+        scalar => out.push_str(&scalar.to_string()),
+    }
+}
+
+/// Compute an RFC 6902 JSON patch turning `actual` into `desired`
+///
+/// # Arguments
+///
+/// * `actual` - The current value
+/// * `desired` - The desired value
+///
+/// # Returns
+///
+/// * `Value` - The patch as a JSON array of operations, empty when the values are equal
+pub fn json_patch_between(actual: &Value, desired: &Value) -> Value {
+    serde_json::to_value(json_patch::diff(actual, desired)).unwrap_or_else(|_| Value::Array(Vec::new()))
+}
+
+const ANSI_RED: &str = "\x1b[31m";
+const ANSI_GREEN: &str = "\x1b[32m";
+const ANSI_YELLOW: &str = "\x1b[33m";
+const ANSI_RESET: &str = "\x1b[0m";
+
+/// Render a diff report as text for the terminal, colored when stdout is a TTY
+///
+/// See `format_diff_with_color` for the layout.
+///
+/// # Arguments
+///
+/// * `report` - The report to render
+///
+/// # Returns
+///
+/// * `String` - One line per differing field, sorted by path
+pub fn format_diff(report: &DiffReport) -> String {
+    format_diff_with_color(report, std::io::stdout().is_terminal())
+}
+
+/// Render a diff report as text, one line per differing field sorted by path
+///
+/// Changed fields are rendered as `~ path: from -> to`, added fields as `+ path: to` and removed
+/// fields as `- path`, with values in compact JSON.
+///
+/// # Arguments
+///
+/// * `report` - The report to render
+/// * `color` - Whether to color the lines with ANSI escape codes
+///
+/// # Returns
+///
+/// * `String` - The rendered lines, each ending with a newline
+pub fn format_diff_with_color(report: &DiffReport, color: bool) -> String {
+    let mut lines: Vec<(&str, &str, String)> = Vec::with_capacity(report.len());
+    for change in &report.added {
+        lines.push((&change.path, ANSI_GREEN, format!("+ {}: {}", change.path, render_value(&change.to))));
+    }
+    for change in &report.removed {
+        lines.push((&change.path, ANSI_RED, format!("- {}", change.path)));
+    }
+    for change in &report.changed {
+        lines.push((
+            &change.path,
+            ANSI_YELLOW,
+            format!("~ {}: {} -> {}", change.path, render_value(&change.from), render_value(&change.to)),
+        ));
+    }
+    lines.sort_by(|a, b| a.0.cmp(b.0));
+
+    let mut out = String::new();
+    for (_, ansi, line) in lines {
+        if color {
+            out.push_str(&format!("{}{}{}\n", ansi, line, ANSI_RESET));
+        } else {
+            out.push_str(&line);
+            out.push('\n');
+        }
+    }
+    out
+}
+
+fn render_value(value: &Option<Value>) -> String {
+    value
+        .as_ref()
+        .map(Value::to_string)
+        .unwrap_or_else(|| "null".to_string())
+}
+
+/// List the fields that differ between two JSON values
+///
+/// Objects and arrays are compared entry by entry, `null` and missing fields are treated as equal.
+///
+/// # Arguments
+///
+/// * `actual` - The current value
+/// * `desired` - The desired value
+///
+/// # Returns
+///
+/// * `Vec<FieldChange>` - The differing leaf fields, in path order
+pub fn diff_values(actual: &Value, desired: &Value) -> Vec<FieldChange> {
+    let mut changes = Vec::new();
+    walk(String::new(), Some(actual), Some(desired), &mut changes);
+    changes
+}
+
+fn join(path: &str, key: &str) -> String {
+    if path.is_empty() {
+        key.to_string()
+    } else {
+        format!("{}.{}", path, key)
+    }
+}
+
+fn walk(path: String, from: Option<&Value>, to: Option<&Value>, changes: &mut Vec<FieldChange>) {
+    let from = from.filter(|v| !v.is_null());
+    let to = to.filter(|v| !v.is_null());
+
+    match (from, to) {
+        (Some(Value::Object(a)), Some(Value::Object(b))) => {
+            let mut keys: Vec<&String> = a.keys().chain(b.keys()).collect();
+            keys.sort();
+            keys.dedup();
+            for key in keys {
+                walk(join(&path, key), a.get(key), b.get(key), changes);
+            }
+        }
+        (Some(Value::Array(a)), Some(Value::Array(b))) => {
+            for index in 0..a.len().max(b.len()) {
+                walk(format!("{}[{}]", path, index), a.get(index), b.get(index), changes);
+            }
+        }
+        (from, to) if from != to => changes.push(FieldChange {
+            path,
+            from: from.cloned(),
+            to: to.cloned(),
+        }),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use rancher_client::models::{IoCattleManagementv3RoleTemplate, IoK8sApimachineryPkgApisMetaV1ObjectMeta};
+
+    use crate::rt::RT_EXCLUDE_PATHS;
+
+    fn sample_role_template_value() -> Value {
+        let rt = IoCattleManagementv3RoleTemplate {
+            metadata: Some(IoK8sApimachineryPkgApisMetaV1ObjectMeta {
+                name: Some("rt-1".to_string()),
+                creation_timestamp: Some("2024-01-01T00:00:00Z".to_string()),
+                resource_version: Some("42".to_string()),
+                uid: Some("uid-1".to_string()),
+                labels: Some([("team".to_string(), "a".to_string())].into_iter().collect()),
+                ..Default::default()
+            }),
+            description: Some("A role template".to_string()),
+            ..Default::default()
+        };
+        serde_json::to_value(rt).unwrap()
+    }
+
+    #[test]
+    fn test_prune_excluded_fields_keeps_siblings() {
+        let mut value = sample_role_template_value();
+
+        prune_paths(&mut value, RT_EXCLUDE_PATHS);
+
+        let metadata = &value["metadata"];
+        assert!(metadata.get("creationTimestamp").is_none());
+        assert!(metadata.get("resourceVersion").is_none());
+        assert!(metadata.get("uid").is_none());
+        assert_eq!(metadata["name"], "rt-1");
+        assert_eq!(metadata["labels"]["team"], "a");
+        assert_eq!(value["description"], "A role template");
+    }
+
+    #[test]
+    fn test_prune_ignores_missing_and_non_object_paths() {
+        let mut value = serde_json::json!({ "a": { "b": 1 }, "c": [1, 2] });
+
+        prune_paths(&mut value, &["a.x", "missing.path", "c.0", "a.b.deeper", ""]);
+
+        assert_eq!(value, serde_json::json!({ "a": { "b": 1 }, "c": [1, 2] }));
+
+        prune_paths(&mut value, &["a.b"]);
+        assert_eq!(value, serde_json::json!({ "a": {}, "c": [1, 2] }));
+    }
+
+    #[test]
+    fn test_diff_values_paths() {
+        let actual = serde_json::json!({
+            "metadata": { "labels": { "team": "a" }, "annotations": { "foo": "x" } },
+            "rules": [{ "verbs": ["get"] }],
+            "hidden": null
+        });
+        let desired = serde_json::json!({
+            "metadata": { "labels": { "team": "b" } },
+            "rules": [{ "verbs": ["get"] }, { "verbs": ["list"] }]
+        });
+
+        let changes = diff_values(&actual, &desired);
+
+        let paths: Vec<&str> = changes.iter().map(|c| c.path.as_str()).collect();
+        assert_eq!(paths, vec!["metadata.annotations", "metadata.labels.team", "rules[1]"]);
+        assert_eq!(changes[1].from, Some(serde_json::json!("a")));
+        assert_eq!(changes[1].to, Some(serde_json::json!("b")));
+        assert_eq!(changes[2].from, None);
+    }
+
+    #[test]
+    fn test_diff_report_groups_changes() {
+        let actual = serde_json::json!({ "description": "old", "metadata": { "annotations": { "foo": "x" } } });
+        let desired = serde_json::json!({ "description": "new", "metadata": { "labels": { "team": "a" } } });
+
+        let report = DiffReport::between(&actual, &desired);
+
+        assert_eq!(report.len(), 3);
+        assert_eq!(report.added[0].path, "metadata.labels");
+        assert_eq!(report.removed[0].path, "metadata.annotations");
+        assert_eq!(report.changed[0].path, "description");
+        assert_eq!(report.paths(), vec!["description", "metadata.annotations", "metadata.labels"]);
+        assert!(DiffReport::between(&actual, &actual).is_empty());
+    }
+
+    #[test]
+    fn test_diff_report_paths_match_exclude_path_form() {
+        let actual = sample_role_template_value();
+        let mut desired = actual.clone();
+        desired["metadata"]["resourceVersion"] = serde_json::json!("43");
+        desired["metadata"]["uid"] = Value::Null;
+        desired["metadata"]["generation"] = serde_json::json!(2);
+
+        let report = DiffReport::between(&actual, &desired);
+
+        assert_eq!(
+            report.paths(),
+            vec!["metadata.generation", "metadata.resourceVersion", "metadata.uid"]
+        );
+        assert!(report.paths().iter().all(|path| RT_EXCLUDE_PATHS.contains(path)));
+
+        let mut pruned_actual = actual;
+        let mut pruned_desired = desired;
+        prune_paths(&mut pruned_actual, RT_EXCLUDE_PATHS);
+        prune_paths(&mut pruned_desired, RT_EXCLUDE_PATHS);
+        assert!(DiffReport::between(&pruned_actual, &pruned_desired).is_empty());
+    }
+
+    fn sample_report() -> DiffReport {
+        DiffReport {
+            added: vec![FieldChange {
+                path: "rules[2]".to_string(),
+                from: None,
+                to: Some(serde_json::json!({ "verbs": ["get"] })),
+            }],
+            removed: vec![FieldChange {
+                path: "metadata.annotations.foo".to_string(),
+                from: Some(serde_json::json!("x")),
+                to: None,
+            }],
+            changed: vec![FieldChange {
+                path: "metadata.labels.team".to_string(),
+                from: Some(serde_json::json!("a")),
+                to: Some(serde_json::json!("b")),
+            }],
+        }
+    }
+
+    #[test]
+    fn test_format_diff_without_color() {
+        let rendered = format_diff_with_color(&sample_report(), false);
+
+        assert_eq!(
+            rendered,
+            "- metadata.annotations.foo\n\
+             ~ metadata.labels.team: \"a\" -> \"b\"\n\
+             + rules[2]: {\"verbs\":[\"get\"]}\n"
+        );
+        assert_eq!(format_diff_with_color(&DiffReport::default(), false), "");
+    }
+
+    #[test]
+    fn test_format_diff_with_color() {
+        let rendered = format_diff_with_color(&sample_report(), true);
+
+        assert_eq!(
+            rendered.lines().next(),
+            Some("\x1b[31m- metadata.annotations.foo\x1b[0m")
+        );
+        assert_eq!(
+            rendered.lines().nth(1),
+            Some("\x1b[33m~ metadata.labels.team: \"a\" -> \"b\"\x1b[0m")
+        );
+    }
+
+    #[test]
+    fn test_hash_value_ignores_key_order() {
+        let a: Value = serde_json::from_str(r#"{"b": [1, {"y": 2, "x": 1}], "a": "s"}"#).unwrap();
+        let b: Value = serde_json::from_str(r#"{"a": "s", "b": [1, {"x": 1, "y": 2}]}"#).unwrap();
+        let c: Value = serde_json::from_str(r#"{"a": "s", "b": [{"x": 1, "y": 2}, 1]}"#).unwrap();
+
+        assert_eq!(hash_value(&a), hash_value(&b));
+        assert_ne!(hash_value(&a), hash_value(&c));
+    }
+
+    #[test]
+    fn test_fnv1a_known_vectors() {
+        assert_eq!(fnv1a(b""), 0xcbf29ce484222325);
+        assert_eq!(fnv1a(b"a"), 0xaf63dc4c8601ec8c);
+        assert_eq!(fnv1a(b"foobar"), 0x85944171f73967e8);
+    }
+}
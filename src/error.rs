@@ -0,0 +1,433 @@
+use std::time::Duration;
+
+use reqwest::StatusCode;
+use serde_json::Value;
+
+use rancher_client::apis::Error;
+
+use crate::{status_message, ResourceKind};
+
+/// The fields of a Kubernetes `Status` failure body
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ApiStatus {
+    /// The human-readable description, or the raw body when it is not a `Status`
+    pub message: String,
+    /// The machine-readable reason, such as `Forbidden` or `AlreadyExists`
+    pub reason: Option<String>,
+    /// The HTTP status code repeated in the body
+    pub code: Option<u16>,
+}
+
+impl ApiStatus {
+    /// Read the fields of a response body, only `message` is set when it is not a `Status`
+    pub fn parse(content: &str) -> Self {
+        let status = serde_json::from_str::<Value>(content).ok();
+        let field = |key: &str| status.as_ref().and_then(|status| status.get(key).cloned());
+        ApiStatus {
+            message: status_message(content),
+            reason: field("reason").and_then(|reason| reason.as_str().map(String::from)),
+            code: field("code")
+                .and_then(|code| code.as_u64())
+                .and_then(|code| u16::try_from(code).ok()),
+        }
+    }
+
+    /// Write `<reason>: <message>`, with `fallback` standing in for a missing reason
+    fn describe(&self, f: &mut std::fmt::Formatter<'_>, fallback: &str) -> std::fmt::Result {
+        write!(f, "{}: {}", self.reason.as_deref().unwrap_or(fallback), self.message)
+    }
+}
+
+/// The `details` object of a Kubernetes `Status` body, `None` when it has none
+fn status_details(content: &str) -> Option<Value> {
+    serde_json::from_str::<Value>(content)
+        .ok()
+        .and_then(|status| status.get("details").cloned())
+        .filter(|details| !details.is_null())
+}
+
+/// An error of the Rancher API, independent of the generated per-endpoint error types
+///
+/// Every generated `Error<E>` converts into it, so callers can handle failures of any endpoint
+/// the same way. The helpers writing objects wrap it in `WithResource` to name the object.
+#[derive(Debug)]
+pub enum RancherError {
+    /// The object does not exist (404)
+    NotFound,
+    /// The credentials are missing, invalid or expired (401)
+    Unauthorized(ApiStatus),
+    /// The credentials do not allow the request (403)
+    Forbidden(ApiStatus),
+    /// The object changed on the server since it was read (409)
+    Conflict {
+        /// The server's message
+        message: String,
+        /// The `details` of the `Status` body (name, group, kind and causes of the conflict), for a
+        /// retry layer to tell which object to re-fetch before merging
+        details: Option<Value>,
+    },
+    /// The server is rate limiting the client (429)
+    RateLimited {
+        /// How long the server asked to wait before retrying, when it said so
+        retry_after: Option<Duration>,
+    },
+    /// The request was rejected before being sent, or the object could not be converted
+    Invalid(String),
+    /// Any other unexpected status, with the server's `Status`
+    Api(StatusCode, ApiStatus),
+    /// A body could not be serialized or deserialized
+    Serde(serde_json::Error),
+    /// The request could not be sent or its response not read
+    Transport(reqwest::Error),
+    /// No response was received within the timeout of the request
+    Timeout(Duration),
+}
+
+impl RancherError {
+    /// The HTTP status this error corresponds to, `None` when no response was received
+    pub fn status(&self) -> Option<StatusCode> {
+        match self {
+            RancherError::NotFound => Some(StatusCode::NOT_FOUND),
+            RancherError::Unauthorized(_) => Some(StatusCode::UNAUTHORIZED),
+            RancherError::Forbidden(_) => Some(StatusCode::FORBIDDEN),
+            RancherError::Conflict { .. } => Some(StatusCode::CONFLICT),
+            RancherError::RateLimited { .. } => Some(StatusCode::TOO_MANY_REQUESTS),
+            RancherError::Api(status, _) => Some(*status),
+            RancherError::Invalid(_)
+            | RancherError::Serde(_)
+            | RancherError::Transport(_)
+            | RancherError::Timeout(_) => None,
+        }
+    }
+
+    /// The error of a response with an unexpected status, read from its `Status` body
+    pub(crate) fn from_response(status: StatusCode, content: &str) -> Self {
+        match status {
+            StatusCode::NOT_FOUND => RancherError::NotFound,
+            StatusCode::UNAUTHORIZED => RancherError::Unauthorized(ApiStatus::parse(content)),
+            StatusCode::FORBIDDEN => RancherError::Forbidden(ApiStatus::parse(content)),
+            StatusCode::CONFLICT => RancherError::Conflict {
+                message: status_message(content),
+                details: status_details(content),
+            },
+            StatusCode::TOO_MANY_REQUESTS => RancherError::RateLimited { retry_after: None },
+            status => RancherError::Api(status, ApiStatus::parse(content)),
+        }
+    }
+
+    /// A domain struct that could not be converted to or from the Rancher model
+    pub(crate) fn conversion(message: &'static str) -> Self {
+        RancherError::Invalid(message.to_string())
+    }
+
+    /// A replace attempted without a resource version, the server would reject it
+    pub(crate) fn missing_resource_version() -> Self {
+        RancherError::Invalid("missing resource version, fetch the object before replacing it".to_string())
+    }
+
+    /// A patch attempting to change a field managed by the server
+    pub(crate) fn immutable_field(path: &str) -> Self {
+        RancherError::Invalid(format!("field {} cannot be changed", path))
+    }
+}
+
+impl<E> From<Error<E>> for RancherError {
+    fn from(error: Error<E>) -> Self {
+        match error {
+            Error::Reqwest(e) => RancherError::Transport(e),
+            Error::Serde(e) => RancherError::Serde(e),
+            Error::Io(e) => RancherError::Invalid(e.to_string()),
+            Error::ResponseError(response) => RancherError::from_response(response.status, &response.content),
+        }
+    }
+}
+
+impl std::fmt::Display for RancherError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RancherError::NotFound => write!(f, "not found"),
+            RancherError::Unauthorized(status) => status.describe(f, "Unauthorized"),
+            RancherError::Forbidden(status) => status.describe(f, "Forbidden"),
+            RancherError::Conflict { message, .. } => write!(f, "conflict: {}", message),
+            RancherError::RateLimited { retry_after: Some(delay) } => {
+                write!(f, "rate limited, retry after {}s", delay.as_secs())
+            }
+            RancherError::RateLimited { retry_after: None } => write!(f, "rate limited"),
+            RancherError::Invalid(message) => write!(f, "invalid request: {}", message),
+            RancherError::Api(code, status) => status.describe(f, &code.to_string()),
+            RancherError::Serde(e) => write!(f, "invalid body: {}", e),
+            RancherError::Transport(e) => write!(f, "request failed: {}", e),
+            RancherError::Timeout(timeout) => {
+                write!(f, "request timed out after {}ms", timeout.as_millis())
+            }
+        }
+    }
+}
+
+impl std::error::Error for RancherError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            RancherError::Serde(e) => Some(e),
+            RancherError::Transport(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+/// A list limit rejected before the request was sent, see `crate::checked_limit`
+///
+/// It converts into `RancherError::Invalid`, and into the `Io` error of the generated API for the
+/// list functions returning it, with this error as the source.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InvalidLimit(pub i32);
+
+impl std::fmt::Display for InvalidLimit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "limit must be greater than 0, got {}", self.0)
+    }
+}
+
+impl std::error::Error for InvalidLimit {}
+
+impl From<InvalidLimit> for RancherError {
+    fn from(error: InvalidLimit) -> Self {
+        RancherError::Invalid(error.to_string())
+    }
+}
+
+impl<E> From<InvalidLimit> for Error<E> {
+    fn from(error: InvalidLimit) -> Self {
+        Error::Io(std::io::Error::new(std::io::ErrorKind::InvalidInput, error))
+    }
+}
+
+/// The object a request was about
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResourceContext {
+    pub kind: ResourceKind,
+    /// The namespace of the object, `None` for cluster scoped kinds
+    pub namespace: Option<String>,
+    pub name: String,
+}
+
+impl ResourceContext {
+    pub fn new(kind: ResourceKind, namespace: Option<&str>, name: &str) -> Self {
+        ResourceContext {
+            kind,
+            namespace: namespace.map(String::from),
+            name: name.to_string(),
+        }
+    }
+}
+
+impl std::fmt::Display for ResourceContext {
+    /// `<kind> <namespace>/<name>`, or `<kind> <name>` without a namespace
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.namespace {
+            Some(namespace) => write!(f, "{} {}/{}", self.kind, namespace, self.name),
+            None => write!(f, "{} {}", self.kind, self.name),
+        }
+    }
+}
+
+/// An error along with the object it happened on
+///
+/// The wrapped error stays reachable through `std::error::Error::source`.
+#[derive(Debug)]
+pub struct WithResource<E> {
+    pub resource: ResourceContext,
+    pub error: E,
+}
+
+impl<E> WithResource<E> {
+    /// The wrapped error, for callers that attach the context again further up
+    pub fn into_error(self) -> E {
+        self.error
+    }
+}
+
+impl<E: std::fmt::Display> std::fmt::Display for WithResource<E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.resource, self.error)
+    }
+}
+
+impl<E: std::error::Error + 'static> std::error::Error for WithResource<E> {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.error)
+    }
+}
+
+/// Attach the object a request was about to its error
+pub trait WithResourceExt<T, E> {
+    /// Wrap the error, if any, in a `WithResource` naming the object
+    fn with_resource(
+        self,
+        kind: ResourceKind,
+        namespace: Option<&str>,
+        name: &str,
+    ) -> Result<T, WithResource<E>>;
+}
+
+impl<T, E> WithResourceExt<T, E> for Result<T, E> {
+    fn with_resource(
+        self,
+        kind: ResourceKind,
+        namespace: Option<&str>,
+        name: &str,
+    ) -> Result<T, WithResource<E>> {
+        self.map_err(|error| WithResource {
+            resource: ResourceContext::new(kind, namespace, name),
+            error,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rancher_client::apis::ResponseContent;
+
+    fn response_error(status: u16, message: &str) -> Error<Value> {
+        Error::ResponseError(ResponseContent {
+            status: StatusCode::from_u16(status).unwrap(),
+            content: serde_json::json!({ "kind": "Status", "message": message }).to_string(),
+            entity: None,
+        })
+    }
+
+    #[test]
+    fn test_statuses_map_to_variants() {
+        assert!(matches!(
+            RancherError::from(response_error(404, "gone")),
+            RancherError::NotFound
+        ));
+        assert!(matches!(
+            RancherError::from(response_error(401, "expired")),
+            RancherError::Unauthorized(status) if status.message == "expired"
+        ));
+        assert!(matches!(
+            RancherError::from(response_error(403, "denied")),
+            RancherError::Forbidden(status) if status.message == "denied"
+        ));
+        assert!(matches!(
+            RancherError::from(response_error(409, "modified")),
+            RancherError::Conflict { message, details: None } if message == "modified"
+        ));
+        assert!(matches!(
+            RancherError::from(response_error(429, "slow down")),
+            RancherError::RateLimited { retry_after: None }
+        ));
+        assert!(matches!(
+            RancherError::from(response_error(500, "boom")),
+            RancherError::Api(StatusCode::INTERNAL_SERVER_ERROR, status) if status.message == "boom"
+        ));
+    }
+
+    #[test]
+    fn test_forbidden_status_is_surfaced() {
+        let body = serde_json::json!({
+            "kind": "Status",
+            "apiVersion": "v1",
+            "status": "Failure",
+            "message": "roletemplates.management.cattle.io \"rt-1\" is forbidden: User \"u-alice\" cannot get resource \"roletemplates\"",
+            "reason": "Forbidden",
+            "details": { "name": "rt-1", "group": "management.cattle.io", "kind": "roletemplates" },
+            "code": 403,
+        });
+        let error = RancherError::from(Error::<Value>::ResponseError(ResponseContent {
+            status: StatusCode::FORBIDDEN,
+            content: body.to_string(),
+            entity: None,
+        }));
+
+        let RancherError::Forbidden(status) = &error else {
+            panic!("expected Forbidden, got {:?}", error);
+        };
+        assert_eq!(status.reason.as_deref(), Some("Forbidden"));
+        assert_eq!(status.code, Some(403));
+        assert_eq!(
+            error.to_string(),
+            "Forbidden: roletemplates.management.cattle.io \"rt-1\" is forbidden: User \"u-alice\" cannot get resource \"roletemplates\""
+        );
+    }
+
+    #[test]
+    fn test_conflict_keeps_status_details() {
+        let body = serde_json::json!({
+            "kind": "Status",
+            "apiVersion": "v1",
+            "metadata": {},
+            "status": "Failure",
+            "message": "Operation cannot be fulfilled on roletemplates.management.cattle.io \"rt-1\": the object has been modified; please apply your changes to the latest version and try again",
+            "reason": "Conflict",
+            "details": { "name": "rt-1", "group": "management.cattle.io", "kind": "roletemplates" },
+            "code": 409,
+        });
+        let error = RancherError::from(Error::<Value>::ResponseError(ResponseContent {
+            status: StatusCode::CONFLICT,
+            content: body.to_string(),
+            entity: None,
+        }));
+
+        let RancherError::Conflict { message, details } = &error else {
+            panic!("expected Conflict, got {:?}", error);
+        };
+        assert!(message.contains("the object has been modified"));
+        let details = details.as_ref().unwrap();
+        assert_eq!(details["name"], "rt-1");
+        assert_eq!(details["kind"], "roletemplates");
+    }
+
+    #[test]
+    fn test_non_status_body_is_kept_as_message() {
+        let status = ApiStatus::parse("<html>Bad Gateway</html>");
+
+        assert_eq!(status.message, "<html>Bad Gateway</html>");
+        assert_eq!(status.reason, None);
+        assert_eq!(status.code, None);
+    }
+
+    #[test]
+    fn test_write_errors_are_invalid() {
+        assert!(matches!(
+            RancherError::conversion("missing metadata"),
+            RancherError::Invalid(m) if m == "missing metadata"
+        ));
+        assert!(matches!(
+            RancherError::immutable_field("metadata.resourceVersion"),
+            RancherError::Invalid(m) if m == "field metadata.resourceVersion cannot be changed"
+        ));
+        assert!(matches!(
+            RancherError::missing_resource_version(),
+            RancherError::Invalid(m) if m.starts_with("missing resource version")
+        ));
+    }
+
+    #[test]
+    fn test_resource_context_is_preserved_through_the_chain() {
+        let result: Result<(), RancherError> = Err(response_error(500, "boom").into());
+
+        let error = result
+            .with_resource(ResourceKind::ProjectRoleTemplateBinding, Some("p-1"), "prtb-1")
+            .unwrap_err();
+        assert_eq!(error.resource.name, "prtb-1");
+        assert!(error
+            .to_string()
+            .starts_with("ProjectRoleTemplateBinding p-1/prtb-1: "));
+
+        let boxed: Box<dyn std::error::Error> = Box::new(error);
+        let source = std::error::Error::source(boxed.as_ref()).unwrap();
+        assert!(matches!(
+            source.downcast_ref::<RancherError>(),
+            Some(RancherError::Api(StatusCode::INTERNAL_SERVER_ERROR, status)) if status.message == "boom"
+        ));
+    }
+
+    #[test]
+    fn test_cluster_scoped_context_has_no_namespace() {
+        let context = ResourceContext::new(ResourceKind::RoleTemplate, None, "rt-1");
+
+        assert_eq!(context.to_string(), "RoleTemplate rt-1");
+    }
+}
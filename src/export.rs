@@ -0,0 +1,477 @@
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use rancher_client::models::{IoCattleManagementv3ProjectRoleTemplateBinding, IoCattleManagementv3RoleTemplate};
+
+use crate::diff::prune_paths;
+use crate::metadata::{exportable_metadata, validate_name};
+use crate::prtb::{api_form, split_project_name, ProjectRoleTemplateBinding, PRTB_EXCLUDE_PATHS};
+use crate::rt::{self, RoleTemplate, RT_EXCLUDE_PATHS};
+use crate::ResourceKind;
+
+/// The line starting every document of a YAML stream
+pub const DOCUMENT_SEPARATOR: &str = "---\n";
+
+/// Convert a camelCase API field name to the snake_case name of the domain types
+fn snake_case(name: &str) -> String {
+    let mut snake = String::with_capacity(name.len() + 4);
+    for c in name.chars() {
+        if c.is_ascii_uppercase() {
+            snake.push('_');
+            snake.push(c.to_ascii_lowercase());
+        } else {
+            snake.push(c);
+        }
+    }
+    snake
+}
+
+/// Serialize the domain form of an object to YAML without its excluded fields
+///
+/// The domain types flatten `metadata`, so every `metadata.*` path of `exclude_paths` is also
+/// pruned under its snake_case name, e.g. `metadata.resourceVersion` as `resource_version`.
+///
+/// # Arguments
+///
+/// * `object` - The object to serialize
+/// * `exclude_paths` - The API paths of the server managed fields, e.g. `PRTB_EXCLUDE_PATHS`
+///
+/// # Returns
+///
+/// * `String` - The YAML document
+///
+/// # Errors
+///
+/// * `serde_yaml::Error` - The error that occurred while serializing
+pub fn to_export_yaml<T: Serialize>(object: &T, exclude_paths: &[&str]) -> Result<String, serde_yaml::Error> {
+    let mut value =
+        serde_json::to_value(object).map_err(<serde_yaml::Error as serde::ser::Error>::custom)?;
+    prune_paths(&mut value, exclude_paths);
+    let flattened: Vec<String> = exclude_paths
+        .iter()
+        .filter_map(|path| path.strip_prefix("metadata."))
+        .map(snake_case)
+        .collect();
+    prune_paths(&mut value, &flattened.iter().map(String::as_str).collect::<Vec<_>>());
+    serde_yaml::to_string(&value)
+}
+
+/// The API form of a role template with only its user managed labels and annotations
+pub(crate) fn role_template_document(rt: &RoleTemplate) -> Result<Value, serde_json::Error> {
+    let mut rt = rt.clone();
+    rt.annotations = exportable_metadata(rt.annotations.as_ref());
+    rt.labels = exportable_metadata(rt.labels.as_ref());
+    let mut value = serde_json::to_value(rt::api_form(rt))?;
+    prune_paths(&mut value, RT_EXCLUDE_PATHS);
+    Ok(value)
+}
+
+/// The API form of a binding with only its user managed labels and annotations
+pub(crate) fn binding_document(binding: &ProjectRoleTemplateBinding) -> Result<Value, serde_json::Error> {
+    let mut binding = binding.clone();
+    binding.annotations = exportable_metadata(binding.annotations.as_ref());
+    binding.labels = exportable_metadata(binding.labels.as_ref());
+    let binding = api_form(binding);
+    let mut value = serde_json::to_value(binding)?;
+    prune_paths(&mut value, PRTB_EXCLUDE_PATHS);
+    Ok(value)
+}
+
+/// Export role templates and bindings as a single multi-document YAML stream
+///
+/// Every object is written with its `to_manifest_yaml` form, so the stream can also be applied
+/// with `kubectl apply -f`. Role templates come first, then bindings, each sorted by key and
+/// each document starting with `DOCUMENT_SEPARATOR`. Keys are sorted so the output is stable.
+///
+/// # Arguments
+///
+/// * `role_templates` - The role templates to export
+/// * `bindings` - The project role template bindings to export
+///
+/// # Returns
+///
+/// * `String` - The YAML stream, empty when there is nothing to export
+///
+/// # Errors
+///
+/// * `serde_json::Error` - The error that occurred while serializing an object
+pub fn export_multidoc(
+    role_templates: &[RoleTemplate],
+    bindings: &[ProjectRoleTemplateBinding],
+) -> Result<String, serde_json::Error> {
+    let mut role_templates: Vec<&RoleTemplate> = role_templates.iter().collect();
+    role_templates.sort_by_key(|rt| rt.key());
+    let mut bindings: Vec<&ProjectRoleTemplateBinding> = bindings.iter().collect();
+    bindings.sort_by_key(|binding| binding.key());
+
+    let mut documents = Vec::with_capacity(role_templates.len() + bindings.len());
+    for rt in role_templates {
+        documents.push(role_template_document(rt)?);
+    }
+    for binding in bindings {
+        documents.push(binding_document(binding)?);
+    }
+
+    let mut stream = String::new();
+    for document in documents {
+        let manifest = serde_yaml::to_string(&document)
+            .map_err(<serde_json::Error as serde::ser::Error>::custom)?;
+        stream.push_str(DOCUMENT_SEPARATOR);
+        stream.push_str(&manifest);
+    }
+    Ok(stream)
+}
+
+/// The objects read from a multi-document YAML stream
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MultidocImport {
+    pub role_templates: Vec<RoleTemplate>,
+    pub bindings: Vec<ProjectRoleTemplateBinding>,
+    /// The documents whose kind is not supported, as `(index, kind)` with the index counted from 0
+    pub ignored: Vec<(usize, String)>,
+}
+
+/// Deserialize a document either in its API form, with `metadata`, or in its domain form
+fn from_document<Api, Domain>(document: Value) -> Result<Domain, String>
+where
+    Api: serde::de::DeserializeOwned,
+    Domain: TryFrom<Api, Error = &'static str> + serde::de::DeserializeOwned,
+{
+    if document.get("metadata").is_some() {
+        let api: Api = serde_json::from_value(document).map_err(|e| e.to_string())?;
+        Domain::try_from(api).map_err(str::to_string)
+    } else {
+        serde_json::from_value(document).map_err(|e| e.to_string())
+    }
+}
+
+/// Import role templates and bindings from a multi-document YAML stream
+///
+/// Each document's `kind` selects its type. Documents may be in the API form written by
+/// `export_multidoc` or in the domain form read by `apply::load_directory`. Documents of any other
+/// kind are listed in `ignored` rather than failing the import, and empty documents are skipped.
+///
+/// # Arguments
+///
+/// * `s` - The YAML stream
+///
+/// # Returns
+///
+/// * `MultidocImport` - The objects found, in stream order, and the ignored documents
+///
+/// # Errors
+///
+/// * `String` - A message naming the document that could not be parsed or has no `kind`
+pub fn import_multidoc(s: &str) -> Result<MultidocImport, String> {
+    let mut import = MultidocImport::default();
+    for (index, document) in serde_yaml::Deserializer::from_str(s).enumerate() {
+        let document =
+            Value::deserialize(document).map_err(|e| format!("Failed to parse document {}: {}", index, e))?;
+        if document.is_null() {
+            continue;
+        }
+        let kind = document
+            .get("kind")
+            .and_then(Value::as_str)
+            .ok_or_else(|| format!("Missing kind in document {}", index))?
+            .to_string();
+
+        match kind.as_str() {
+            kind if kind == ResourceKind::RoleTemplate.as_str() => {
+                let rt = from_document::<IoCattleManagementv3RoleTemplate, RoleTemplate>(document)
+                    .map_err(|e| format!("Invalid {} in document {}: {}", kind, index, e))?;
+                import.role_templates.push(rt);
+            }
+            kind if kind == ResourceKind::ProjectRoleTemplateBinding.as_str() => {
+                let binding = from_document::<
+                    IoCattleManagementv3ProjectRoleTemplateBinding,
+                    ProjectRoleTemplateBinding,
+                >(document)
+                .map_err(|e| format!("Invalid {} in document {}: {}", kind, index, e))?;
+                import.bindings.push(binding);
+            }
+            _ => import.ignored.push((index, kind)),
+        }
+    }
+    Ok(import)
+}
+
+/// Write a file by writing a temporary file next to it and renaming it over the target
+///
+/// Readers see either the old or the new content, never a partially written file.
+fn write_atomically(path: &Path, content: &str) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create directory {}: {}", parent.display(), e))?;
+    }
+    let mut temp = path.as_os_str().to_owned();
+    temp.push(".tmp");
+    let temp = PathBuf::from(temp);
+    std::fs::write(&temp, content).map_err(|e| format!("Failed to write {}: {}", temp.display(), e))?;
+    std::fs::rename(&temp, path).map_err(|e| {
+        let _ = std::fs::remove_file(&temp);
+        format!("Failed to write {}: {}", path.display(), e)
+    })
+}
+
+/// A domain YAML document with the `kind` that `apply::load_directory` dispatches on
+fn kind_document(
+    kind: ResourceKind,
+    yaml: Result<String, serde_yaml::Error>,
+    id: &str,
+) -> Result<String, String> {
+    yaml.map(|yaml| format!("kind: {}\n{}", kind.as_str(), yaml))
+        .map_err(|e| format!("Failed to serialize {} {}: {}", kind.as_str(), id, e))
+}
+
+/// Check that an ID is a valid name before it becomes a path component, so it cannot leave `root`
+fn path_component<'a>(kind: ResourceKind, id: &str, component: &'a str) -> Result<&'a str, String> {
+    validate_name(component)
+        .map(|()| component)
+        .map_err(|e| format!("Invalid path component {} of {} {}: {}", component, kind.as_str(), id, e))
+}
+
+/// Export role templates and bindings as one file per object below a directory
+///
+/// Role templates are written to `root/roletemplates/<id>.yaml` and bindings to
+/// `root/projects/<cluster-id>/<project-id>/prtb/<id>.yaml`, the cluster and project IDs being
+/// taken from `project_name`. Files hold the domain form plus a `kind`, so the directory can be
+/// read back with `apply::load_directory`. Existing files are replaced atomically.
+///
+/// # Arguments
+///
+/// * `root` - The directory to export to, created when missing
+/// * `role_templates` - The role templates to export
+/// * `bindings` - The project role template bindings to export
+///
+/// # Returns
+///
+/// * `Vec<PathBuf>` - The paths of the files written, role templates first
+///
+/// # Errors
+///
+/// * `String` - A message naming the object with an ID that is not a valid name or an invalid `project_name`,
+///   or the file that could not be written
+pub fn export_directory(
+    root: &Path,
+    role_templates: &[RoleTemplate],
+    bindings: &[ProjectRoleTemplateBinding],
+) -> Result<Vec<PathBuf>, String> {
+    let mut written = Vec::new();
+    for rt in role_templates {
+        let id = path_component(ResourceKind::RoleTemplate, &rt.id, &rt.id)?;
+        let path = root.join("roletemplates").join(format!("{}.yaml", id));
+        write_atomically(&path, &kind_document(ResourceKind::RoleTemplate, rt.to_yaml(), &rt.id)?)?;
+        written.push(path);
+    }
+    for binding in bindings {
+        let (cluster_id, project_id) = split_project_name(&binding.project_name)
+            .map_err(|e| format!("{} of binding {}", e, binding.id))?;
+        let component = |value| path_component(ResourceKind::ProjectRoleTemplateBinding, &binding.id, value);
+        let path = root
+            .join("projects")
+            .join(component(cluster_id)?)
+            .join(component(project_id)?)
+            .join("prtb")
+            .join(format!("{}.yaml", component(binding.id.as_str())?));
+        let yaml = kind_document(ResourceKind::ProjectRoleTemplateBinding, binding.to_yaml(), &binding.id)?;
+        write_atomically(&path, &yaml)?;
+        written.push(path);
+    }
+    Ok(written)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TestDir;
+
+    fn role_template(id: &str) -> RoleTemplate {
+        RoleTemplate::from_yaml(&format!("id: {}\ncontext: project\ndisplay_name: {}\n", id, id)).unwrap()
+    }
+
+    fn binding(id: &str) -> ProjectRoleTemplateBinding {
+        ProjectRoleTemplateBinding::from_yaml(&format!(
+            "id: {}\nnamespace: p-1\nproject_name: c-1:p-1\nrole_template_name: rt-1\nuser_name: u-alice\n",
+            id
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_export_multidoc_parses_back_by_kind() {
+        let output = export_multidoc(
+            &[role_template("rt-1"), role_template("rt-2")],
+            &[binding("prtb-1"), binding("prtb-2"), binding("prtb-3")],
+        )
+        .unwrap();
+
+        let documents: Vec<Value> = serde_yaml::Deserializer::from_str(&output)
+            .map(|document| Value::deserialize(document).unwrap())
+            .collect();
+        let count = |kind: &str| documents.iter().filter(|d| d["kind"] == kind).count();
+
+        assert_eq!(documents.len(), 5);
+        assert_eq!(count("RoleTemplate"), 2);
+        assert_eq!(count("ProjectRoleTemplateBinding"), 3);
+        assert!(documents.iter().all(|d| d["apiVersion"] == "management.cattle.io/v3"));
+        assert_eq!(documents[2]["metadata"]["namespace"], "p-1");
+    }
+
+    #[test]
+    fn test_export_multidoc_of_nothing_is_empty() {
+        assert_eq!(export_multidoc(&[], &[]).unwrap(), "");
+    }
+
+    #[test]
+    fn test_export_multidoc_renders_invalid_ids() {
+        let output = export_multidoc(&[role_template("Admin_RT")], &[]).unwrap();
+
+        assert!(output.contains("name: Admin_RT"));
+    }
+
+    #[test]
+    fn test_import_multidoc_round_trips_export() {
+        let role_templates = vec![role_template("rt-1")];
+        let bindings = vec![binding("prtb-1"), binding("prtb-2")];
+
+        let import = import_multidoc(&export_multidoc(&role_templates, &bindings).unwrap()).unwrap();
+
+        assert_eq!(import.role_templates, role_templates);
+        assert_eq!(import.bindings, bindings);
+        assert!(import.ignored.is_empty());
+    }
+
+    #[test]
+    fn test_import_multidoc_mixed_kinds() {
+        let stream = "---
+kind: RoleTemplate
+id: rt-1
+context: project
+---
+apiVersion: v1
+kind: ConfigMap
+metadata:
+  name: settings
+---
+apiVersion: management.cattle.io/v3
+kind: ProjectRoleTemplateBinding
+metadata:
+  name: prtb-1
+  namespace: p-1
+projectName: c-1:p-1
+roleTemplateName: rt-1
+groupName: g-admins
+---
+";
+
+        let import = import_multidoc(stream).unwrap();
+
+        assert_eq!(import.role_templates.len(), 1);
+        assert_eq!(import.role_templates[0].id, "rt-1");
+        assert_eq!(import.bindings.len(), 1);
+        assert_eq!(import.bindings[0].id, "prtb-1");
+        assert_eq!(import.bindings[0].group_name.as_deref(), Some("g-admins"));
+        assert_eq!(import.ignored, vec![(1, "ConfigMap".to_string())]);
+    }
+
+    #[test]
+    fn test_import_multidoc_names_document_without_kind() {
+        let err = import_multidoc("---\nkind: RoleTemplate\nid: rt-1\n---\nid: rt-2\n").unwrap_err();
+        assert!(err.contains("Missing kind in document 1"));
+    }
+
+    #[test]
+    fn test_export_directory_layout() {
+        let dir = TestDir::new("layout");
+        let mut other = binding("prtb-2");
+        other.project_name = "c-2:p-9".to_string();
+        other.namespace = "p-9".to_string();
+
+        let written =
+            export_directory(&dir.0, &[role_template("rt-1")], &[binding("prtb-1"), other]).unwrap();
+
+        assert_eq!(
+            written,
+            vec![
+                dir.0.join("roletemplates/rt-1.yaml"),
+                dir.0.join("projects/c-1/p-1/prtb/prtb-1.yaml"),
+                dir.0.join("projects/c-2/p-9/prtb/prtb-2.yaml"),
+            ]
+        );
+        let resources = crate::apply::load_directory(&dir.0).unwrap();
+        assert_eq!(resources.role_templates, vec![role_template("rt-1")]);
+        assert_eq!(resources.bindings["p-1"], vec![binding("prtb-1")]);
+    }
+
+    #[test]
+    fn test_export_directory_overwrites_existing_files() {
+        let dir = TestDir::new("overwrite");
+        export_directory(&dir.0, &[role_template("rt-1")], &[]).unwrap();
+        let mut changed = role_template("rt-1");
+        changed.display_name = Some("Renamed".to_string());
+
+        export_directory(&dir.0, &[changed.clone()], &[]).unwrap();
+
+        let content = std::fs::read_to_string(dir.0.join("roletemplates/rt-1.yaml")).unwrap();
+        assert_eq!(RoleTemplate::from_yaml(&content).unwrap(), changed);
+        assert_eq!(std::fs::read_dir(dir.0.join("roletemplates")).unwrap().count(), 1);
+    }
+
+    #[test]
+    fn test_export_directory_rejects_invalid_project_name() {
+        let dir = TestDir::new("invalid");
+        let mut invalid = binding("prtb-1");
+        invalid.project_name = "p-1".to_string();
+
+        let err = export_directory(&dir.0, &[], &[invalid]).unwrap_err();
+
+        assert!(err.contains("Invalid project_name p-1 of binding prtb-1"));
+    }
+
+    #[test]
+    fn test_export_directory_rejects_ids_escaping_root() {
+        let dir = TestDir::new("escape");
+        let mut escaping = binding("../../x");
+        let err = export_directory(&dir.0, &[], &[escaping.clone()]).unwrap_err();
+        assert!(err.contains("Invalid path component ../../x of ProjectRoleTemplateBinding ../../x"));
+
+        escaping.id = "prtb-1".to_string();
+        escaping.project_name = "..:p-1".to_string();
+        let err = export_directory(&dir.0, &[], &[escaping]).unwrap_err();
+        assert!(err.contains("Invalid path component .. of ProjectRoleTemplateBinding prtb-1"));
+
+        let err = export_directory(&dir.0, &[role_template("../x")], &[]).unwrap_err();
+        assert!(err.contains("Invalid path component ../x of RoleTemplate ../x"));
+        assert!(!dir.0.parent().unwrap().join("x.yaml").exists());
+    }
+
+    #[test]
+    fn test_exported_yaml_has_no_excluded_keys() {
+        let dir = TestDir::new("excluded");
+        let mut served = binding("prtb-1");
+        served.resource_version = Some("42".to_string());
+        served.uid = Some("0b1c-uid".to_string());
+
+        export_directory(&dir.0, &[], &[served.clone()]).unwrap();
+        let outputs = [
+            served.to_yaml().unwrap(),
+            std::fs::read_to_string(dir.0.join("projects/c-1/p-1/prtb/prtb-1.yaml")).unwrap(),
+            export_multidoc(&[], &[served]).unwrap(),
+        ];
+
+        for output in outputs {
+            for key in ["resource_version", "resourceVersion", "uid", "42", "0b1c-uid"] {
+                assert!(!output.contains(key), "{} found in {}", key, output);
+            }
+        }
+    }
+
+    #[test]
+    fn test_snake_case() {
+        assert_eq!(snake_case("resourceVersion"), "resource_version");
+        assert_eq!(snake_case("uid"), "uid");
+    }
+}
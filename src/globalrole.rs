@@ -0,0 +1,368 @@
+use std::collections::HashMap;
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use rancher_client::apis::configuration::Configuration;
+use rancher_client::models::{
+    IoCattleManagementv3GlobalRole, IoCattleManagementv3GlobalRoleList,
+    IoCattleManagementv3GlobalRoleRulesInner, IoK8sApimachineryPkgApisMetaV1ObjectMeta,
+};
+
+use crate::client::RequestOptions;
+use crate::metadata::{user_metadata_eq, validate_name};
+use crate::paginate::{ContinueToken, Paginator};
+use crate::request::{get_list, list_query};
+use crate::retry::RetryPolicy;
+use crate::rt::normalize_rules;
+use crate::{checked_limit, continue_token, RancherError, DEFAULT_PAGE_SIZE};
+
+/// The API path of the global role list
+const GLOBAL_ROLES_PATH: &str = "/apis/management.cattle.io/v3/globalroles";
+
+pub const GLOBALROLE_EXCLUDE_PATHS: &[&str] = &[
+    "metadata.creationTimestamp",
+    "metadata.finalizers",
+    "metadata.generateName",
+    "metadata.generation",
+    "metadata.managedFields",
+    "metadata.resourceVersion",
+    "metadata.selfLink",
+    "metadata.uid",
+];
+
+/// Get a page of global roles
+///
+/// A page rate limited by the server is retried with the default `RetryPolicy`, and the request
+/// times out after the timeout of `options`.
+///
+/// # Arguments
+///
+/// * `configuration` - The configuration to use for the request
+/// * `field_selector` - A selector to restrict the global roles by their fields
+/// * `label_selector` - A selector to restrict the global roles by their labels
+/// * `limit` - The largest number of global roles to return, clamped to the largest page size of `options`
+/// * `continue_` - The token returned with the previous page, `None` for the first page
+/// * `options` - The timeout and largest page size of the request
+///
+/// # Returns
+///
+/// * `IoCattleManagementv3GlobalRoleList` - The page of global roles
+/// * `Option<ContinueToken>` - The token to fetch the next page, `None` on the last page
+///
+/// # Errors
+///
+/// * `RancherError` - The error that occurred while trying to get the global roles
+///
+#[async_backtrace::framed]
+pub async fn get_global_roles(
+    configuration: &Configuration,
+    field_selector: Option<&str>,
+    label_selector: Option<&str>,
+    limit: Option<i32>,
+    continue_: Option<&ContinueToken>,
+    options: &RequestOptions,
+) -> Result<(IoCattleManagementv3GlobalRoleList, Option<ContinueToken>), RancherError> {
+    let limit = checked_limit(limit, options)?;
+    let query = list_query(field_selector, label_selector, limit, continue_.map(ContinueToken::as_str));
+    let list: IoCattleManagementv3GlobalRoleList = get_list(
+        configuration,
+        GLOBAL_ROLES_PATH,
+        &query,
+        &RetryPolicy::default(),
+        options,
+    )
+    .await?;
+    let token = continue_token(list.metadata.as_deref()).map(ContinueToken::new);
+    Ok((list, token))
+}
+
+/// Get every global role, following the continue token
+///
+/// A page rate limited by the server is retried with the default `RetryPolicy`, and every
+/// request times out after the timeout of `options`. Pages are `DEFAULT_PAGE_SIZE` items clamped
+/// to the largest page size of `options`.
+///
+/// # Arguments
+///
+/// * `configuration` - The configuration to use for the requests
+/// * `field_selector` - A selector to restrict the global roles by their fields
+/// * `label_selector` - A selector to restrict the global roles by their labels
+/// * `options` - The timeout and largest page size of the requests
+///
+/// # Returns
+///
+/// * `Vec<GlobalRole>` - The global roles of every page
+///
+/// # Errors
+///
+/// * `RancherError` - The first error that occurred while fetching a page or converting a global role
+///
+#[async_backtrace::framed]
+pub async fn get_all_global_roles(
+    configuration: &Configuration,
+    field_selector: Option<&str>,
+    label_selector: Option<&str>,
+    options: &RequestOptions,
+) -> Result<Vec<GlobalRole>, RancherError> {
+    let items = Paginator::new(move |continue_: Option<String>| async move {
+        let continue_ = continue_.map(ContinueToken::new);
+        let (list, next) = get_global_roles(
+            configuration,
+            field_selector,
+            label_selector,
+            Some(DEFAULT_PAGE_SIZE),
+            continue_.as_ref(),
+            options,
+        )
+        .await?;
+        Ok::<_, RancherError>((list.items, next.map(ContinueToken::into_inner)))
+    })
+    .collect_all()
+    .await?;
+
+    items
+        .into_iter()
+        .map(|item| GlobalRole::try_from(item).map_err(|e| RancherError::Invalid(e.to_string())))
+        .collect()
+}
+
+/// A set of permissions granted across every cluster of Rancher, the global analogue of
+/// `RoleTemplate`
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq)]
+pub struct GlobalRole {
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "crate::metadata::serialize_sorted"
+    )]
+    pub annotations: Option<HashMap<String, String>>,
+    /// Whether the global role is created and managed by Rancher. Read-only.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub builtin: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub display_name: Option<String>,
+    pub id: String,
+    /// The cluster role templates granted on every downstream cluster to the holders of the role
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub inherited_cluster_roles: Option<Vec<String>>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "crate::metadata::serialize_sorted"
+    )]
+    pub labels: Option<HashMap<String, String>>,
+    /// Whether the global role is granted to new users
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub new_user_default: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schemars(with = "Option<Vec<crate::schema::PolicyRuleSchema>>")]
+    pub rules: Option<Vec<IoCattleManagementv3GlobalRoleRulesInner>>,
+}
+
+impl TryFrom<IoCattleManagementv3GlobalRole> for GlobalRole {
+    type Error = &'static str;
+
+    fn try_from(value: IoCattleManagementv3GlobalRole) -> Result<Self, Self::Error> {
+        let metadata: IoK8sApimachineryPkgApisMetaV1ObjectMeta = value.metadata.ok_or("missing metadata")?;
+
+        Ok(GlobalRole {
+            annotations: metadata.annotations,
+            builtin: value.builtin,
+            description: value.description,
+            display_name: value.display_name,
+            id: metadata.name.ok_or("missing metadata.name")?,
+            inherited_cluster_roles: value.inherited_cluster_roles,
+            labels: metadata.labels,
+            new_user_default: value.new_user_default,
+            rules: value.rules,
+        })
+    }
+}
+
+impl TryFrom<GlobalRole> for IoCattleManagementv3GlobalRole {
+    type Error = &'static str;
+
+    fn try_from(value: GlobalRole) -> Result<Self, Self::Error> {
+        validate_name(&value.id)?;
+        let metadata = IoK8sApimachineryPkgApisMetaV1ObjectMeta {
+            annotations: value.annotations,
+            labels: value.labels,
+            name: Some(value.id.clone()),
+            ..Default::default()
+        };
+
+        Ok(IoCattleManagementv3GlobalRole {
+            api_version: Some("management.cattle.io/v3".to_string()),
+            builtin: value.builtin,
+            description: value.description,
+            display_name: value.display_name,
+            inherited_cluster_roles: value.inherited_cluster_roles,
+            kind: Some("GlobalRole".to_string()),
+            metadata: Some(metadata),
+            new_user_default: value.new_user_default,
+            rules: value.rules,
+            ..Default::default()
+        })
+    }
+}
+
+/// Sort inherited cluster role names, which Rancher treats as a set
+fn sorted_cluster_roles(names: Option<&Vec<String>>) -> Option<Vec<String>> {
+    names.map(|names| {
+        let mut names = names.clone();
+        names.sort();
+        names
+    })
+}
+
+impl PartialEq<GlobalRole> for IoCattleManagementv3GlobalRole {
+    fn eq(&self, other: &GlobalRole) -> bool {
+        let metadata = self.metadata.as_ref();
+
+        metadata.and_then(|m| m.name.as_deref()) == Some(other.id.as_str())
+            && user_metadata_eq(metadata.and_then(|m| m.annotations.as_ref()), other.annotations.as_ref())
+            && user_metadata_eq(metadata.and_then(|m| m.labels.as_ref()), other.labels.as_ref())
+            && self.builtin == other.builtin
+            && self.description == other.description
+            && self.display_name == other.display_name
+            && sorted_cluster_roles(self.inherited_cluster_roles.as_ref())
+                == sorted_cluster_roles(other.inherited_cluster_roles.as_ref())
+            && self.new_user_default == other.new_user_default
+            && self.rules.as_deref().map(normalize_rules)
+                == other.rules.as_deref().map(normalize_rules)
+    }
+}
+
+impl PartialEq<IoCattleManagementv3GlobalRole> for GlobalRole {
+    fn eq(&self, other: &IoCattleManagementv3GlobalRole) -> bool {
+        other == self
+    }
+}
+
+/// A grant of a `GlobalRole` to a user or group
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq)]
+pub struct GlobalRoleBinding {
+    /// The ID of the global role granted
+    pub global_role_name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub group_principal_name: Option<String>,
+    /// The name of the global role binding (typically the Kubernetes metadata.name).
+    pub id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user_principal_name: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_rule(resources: &[&str], verbs: &[&str]) -> IoCattleManagementv3GlobalRoleRulesInner {
+        IoCattleManagementv3GlobalRoleRulesInner {
+            api_groups: Some(vec!["management.cattle.io".to_string()]),
+            resources: Some(resources.iter().map(|r| r.to_string()).collect()),
+            verbs: verbs.iter().map(|v| v.to_string()).collect(),
+            ..Default::default()
+        }
+    }
+
+    fn sample_global_role() -> GlobalRole {
+        GlobalRole {
+            annotations: Some(HashMap::new()),
+            builtin: Some(false),
+            description: Some("Manage every cluster".to_string()),
+            display_name: Some("Cluster operator".to_string()),
+            id: "cluster-operator".to_string(),
+            inherited_cluster_roles: Some(vec!["cluster-owner".to_string()]),
+            labels: Some(HashMap::new()),
+            new_user_default: Some(false),
+            rules: Some(vec![sample_rule(&["clusters"], &["get", "list"])]),
+        }
+    }
+
+    fn sample_iocattle_global_role() -> IoCattleManagementv3GlobalRole {
+        IoCattleManagementv3GlobalRole {
+            api_version: Some("management.cattle.io/v3".to_string()),
+            builtin: Some(false),
+            description: Some("Manage every cluster".to_string()),
+            display_name: Some("Cluster operator".to_string()),
+            inherited_cluster_roles: Some(vec!["cluster-owner".to_string()]),
+            kind: Some("GlobalRole".to_string()),
+            metadata: Some(IoK8sApimachineryPkgApisMetaV1ObjectMeta {
+                name: Some("cluster-operator".to_string()),
+                ..Default::default()
+            }),
+            new_user_default: Some(false),
+            rules: Some(vec![sample_rule(&["clusters"], &["get", "list"])]),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_iocattle_to_global_role_conversion_success() {
+        let gr = GlobalRole::try_from(sample_iocattle_global_role()).unwrap();
+
+        assert_eq!(gr.id, "cluster-operator");
+        assert_eq!(gr.display_name.as_deref(), Some("Cluster operator"));
+        assert_eq!(gr.inherited_cluster_roles, Some(vec!["cluster-owner".to_string()]));
+    }
+
+    #[test]
+    fn test_global_role_to_iocattle_conversion_success() {
+        let iogr = IoCattleManagementv3GlobalRole::try_from(sample_global_role()).unwrap();
+
+        assert_eq!(iogr.metadata.as_ref().unwrap().name.as_deref(), Some("cluster-operator"));
+        assert_eq!(iogr.kind.as_deref(), Some("GlobalRole"));
+    }
+
+    #[test]
+    fn test_reordered_rules_are_equal() {
+        let mut gr = sample_global_role();
+        gr.rules = Some(vec![
+            sample_rule(&["clusters", "projects"], &["get", "list"]),
+            sample_rule(&["users"], &["get"]),
+        ]);
+
+        let mut iogr = sample_iocattle_global_role();
+        iogr.rules = Some(vec![
+            sample_rule(&["users"], &["get"]),
+            sample_rule(&["projects", "clusters"], &["list", "get"]),
+        ]);
+
+        assert_eq!(gr, iogr);
+        assert_eq!(iogr, gr);
+    }
+
+    #[test]
+    fn test_changed_verb_is_not_equal() {
+        let gr = sample_global_role();
+        let mut iogr = sample_iocattle_global_role();
+        iogr.rules = Some(vec![sample_rule(&["clusters"], &["get", "watch"])]);
+
+        assert_ne!(gr, iogr);
+        assert_ne!(iogr, gr);
+    }
+
+    #[test]
+    fn test_reordered_inherited_cluster_roles_are_equal() {
+        let mut gr = sample_global_role();
+        gr.inherited_cluster_roles = Some(vec!["cluster-owner".to_string(), "cluster-member".to_string()]);
+        let mut iogr = sample_iocattle_global_role();
+        iogr.inherited_cluster_roles = Some(vec!["cluster-member".to_string(), "cluster-owner".to_string()]);
+
+        assert_eq!(gr, iogr);
+    }
+
+    #[test]
+    fn test_inequality_on_different_labels() {
+        let gr = sample_global_role();
+        let mut iogr = sample_iocattle_global_role();
+        iogr.metadata.as_mut().unwrap().labels =
+            Some(HashMap::from([("team".to_string(), "platform".to_string())]));
+
+        assert_ne!(gr, iogr);
+        assert_ne!(iogr, gr);
+    }
+}
@@ -0,0 +1,564 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde::Serialize;
+
+use crate::config::ClusterConfig;
+use crate::crtb::ClusterRoleTemplateBinding;
+use crate::globalrole::GlobalRoleBinding;
+use crate::prtb::ProjectRoleTemplateBinding;
+
+/// The kind of subject a binding grants access to
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[serde(rename_all = "lowercase")]
+pub enum SubjectKind {
+    User,
+    Group,
+    ServiceAccount,
+}
+
+impl SubjectKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SubjectKind::User => "user",
+            SubjectKind::Group => "group",
+            SubjectKind::ServiceAccount => "serviceaccount",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum NodeKind {
+    Subject(SubjectKind),
+    Binding,
+    Template,
+    Project,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum EdgeKind {
+    /// subject → binding
+    Binds,
+    /// binding → role template
+    Grants,
+    /// binding → project the access is granted in
+    Scopes,
+    /// role template → role template it inherits from
+    Inherits,
+}
+
+impl EdgeKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EdgeKind::Binds => "binds",
+            EdgeKind::Grants => "grants",
+            EdgeKind::Scopes => "scopes",
+            EdgeKind::Inherits => "inherits",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Node {
+    /// Unique id of the node, prefixed by its kind (e.g. `template:project-member`)
+    pub id: String,
+    pub kind: NodeKind,
+    /// Human readable label
+    pub label: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Edge {
+    pub from: String,
+    pub to: String,
+    pub kind: EdgeKind,
+}
+
+/// Dependency graph of the RBAC configuration of a cluster
+#[derive(Debug, Clone, Default)]
+pub struct RbacGraph {
+    pub nodes: Vec<Node>,
+    pub edges: Vec<Edge>,
+}
+
+/// The subjects referenced by a binding, preferring the principal form of each subject
+pub(crate) fn subject_refs(binding: &ProjectRoleTemplateBinding) -> Vec<(SubjectKind, String)> {
+    let mut subjects = Vec::new();
+    if let Some(user) = binding
+        .user_principal_name
+        .as_ref()
+        .or(binding.user_name.as_ref())
+    {
+        subjects.push((SubjectKind::User, user.clone()));
+    }
+    if let Some(group) = binding
+        .group_principal_name
+        .as_ref()
+        .or(binding.group_name.as_ref())
+    {
+        subjects.push((SubjectKind::Group, group.clone()));
+    }
+    if let Some(service_account) = &binding.service_account {
+        subjects.push((SubjectKind::ServiceAccount, service_account.clone()));
+    }
+    subjects
+}
+
+/// A subject referenced by at least one binding
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Subject {
+    pub kind: SubjectKind,
+    /// The principal form of the subject (e.g. `local://u-abc`), if any binding used it
+    pub principal_name: Option<String>,
+    /// The name form of the subject (e.g. `u-abc`), if any binding used it
+    pub name: Option<String>,
+}
+
+/// A subject reference as `(kind, principal, name)`
+type SubjectFields = (SubjectKind, Option<String>, Option<String>);
+
+/// The user and group fields of a binding as `(kind, principal, name)`
+fn user_and_group_subjects(
+    user_principal_name: Option<&String>,
+    user_name: Option<&String>,
+    group_principal_name: Option<&String>,
+    group_name: Option<&String>,
+) -> Vec<SubjectFields> {
+    let mut subjects = Vec::new();
+    if user_principal_name.is_some() || user_name.is_some() {
+        subjects.push((SubjectKind::User, user_principal_name.cloned(), user_name.cloned()));
+    }
+    if group_principal_name.is_some() || group_name.is_some() {
+        subjects.push((SubjectKind::Group, group_principal_name.cloned(), group_name.cloned()));
+    }
+    subjects
+}
+
+/// The subject fields of a project role template binding
+fn binding_subjects(binding: &ProjectRoleTemplateBinding) -> Vec<SubjectFields> {
+    let mut subjects = user_and_group_subjects(
+        binding.user_principal_name.as_ref(),
+        binding.user_name.as_ref(),
+        binding.group_principal_name.as_ref(),
+        binding.group_name.as_ref(),
+    );
+    if let Some(service_account) = &binding.service_account {
+        subjects.push((SubjectKind::ServiceAccount, None, Some(service_account.clone())));
+    }
+    subjects
+}
+
+/// The subject fields of a cluster role template binding
+fn cluster_binding_subjects(binding: &ClusterRoleTemplateBinding) -> Vec<SubjectFields> {
+    user_and_group_subjects(
+        binding.user_principal_name.as_ref(),
+        binding.user_name.as_ref(),
+        binding.group_principal_name.as_ref(),
+        binding.group_name.as_ref(),
+    )
+}
+
+/// The subject fields of a global role binding, which has no group name form
+fn global_binding_subjects(binding: &GlobalRoleBinding) -> Vec<SubjectFields> {
+    user_and_group_subjects(
+        binding.user_principal_name.as_ref(),
+        binding.user_name.as_ref(),
+        binding.group_principal_name.as_ref(),
+        None,
+    )
+}
+
+/// List every subject referenced by a binding of any kind, deduplicated and classified by type
+///
+/// A subject referenced by its principal in one binding and by its name in another is only
+/// listed once, as long as some binding, of any kind, references it with both forms. The project
+/// role template bindings are taken from the snapshot, the cluster role template bindings and
+/// global role bindings are passed alongside it.
+///
+/// # Arguments
+///
+/// * `snapshot` - The cluster configuration to scan
+/// * `cluster_bindings` - The cluster role template bindings of the cluster
+/// * `global_bindings` - The global role bindings of Rancher
+///
+/// # Returns
+///
+/// * `Vec<Subject>` - The subjects, sorted by kind then principal then name
+pub fn all_subjects(
+    snapshot: &ClusterConfig,
+    cluster_bindings: &[ClusterRoleTemplateBinding],
+    global_bindings: &[GlobalRoleBinding],
+) -> Vec<Subject> {
+    let refs: Vec<SubjectFields> = snapshot
+        .projects
+        .values()
+        .flat_map(|(_, bindings)| bindings.iter().flat_map(binding_subjects))
+        .chain(cluster_bindings.iter().flat_map(cluster_binding_subjects))
+        .chain(global_bindings.iter().flat_map(global_binding_subjects))
+        .collect();
+
+    // names that some binding pairs with a principal
+    let mut principal_of_name: BTreeMap<(SubjectKind, String), String> = BTreeMap::new();
+    for (kind, principal, name) in &refs {
+        if let (Some(principal), Some(name)) = (principal, name) {
+            principal_of_name
+                .entry((*kind, name.clone()))
+                .or_insert_with(|| principal.clone());
+        }
+    }
+
+    let mut subjects: BTreeMap<(SubjectKind, String), Subject> = BTreeMap::new();
+    for (kind, principal, name) in refs {
+        let principal = principal.or_else(|| {
+            name.as_ref()
+                .and_then(|name| principal_of_name.get(&(kind, name.clone())).cloned())
+        });
+        let key = match (&principal, &name) {
+            (Some(principal), _) => format!("principal:{}", principal),
+            (None, Some(name)) => format!("name:{}", name),
+            (None, None) => continue,
+        };
+        let subject = subjects.entry((kind, key)).or_insert(Subject {
+            kind,
+            principal_name: None,
+            name: None,
+        });
+        if subject.principal_name.is_none() {
+            subject.principal_name = principal;
+        }
+        if subject.name.is_none() {
+            subject.name = name;
+        }
+    }
+
+    let mut subjects: Vec<Subject> = subjects.into_values().collect();
+    subjects.sort();
+    subjects
+}
+
+/// Build the dependency graph of a cluster's RBAC configuration
+///
+/// Nodes are created for every subject, binding, role template and project, with edges for
+/// subjects bound by bindings, templates granted by bindings, the project a binding is scoped to
+/// and template inheritance. Role templates that are referenced but missing from the snapshot
+/// still get a node so dangling references are visible.
+///
+/// # Arguments
+///
+/// * `snapshot` - The cluster configuration to graph
+///
+/// # Returns
+///
+/// * `RbacGraph` - The graph with nodes and edges in a deterministic order
+pub fn build_graph(snapshot: &ClusterConfig) -> RbacGraph {
+    let mut nodes: BTreeMap<String, Node> = BTreeMap::new();
+    let mut edges: BTreeSet<Edge> = BTreeSet::new();
+
+    for rt in &snapshot.role_templates {
+        let template_id = format!("template:{}", rt.id);
+        add_node(&mut nodes, NodeKind::Template, template_id.clone(), rt.id.clone());
+        for inherited in rt.role_template_names.iter().flatten() {
+            let inherited_id = format!("template:{}", inherited);
+            add_node(&mut nodes, NodeKind::Template, inherited_id.clone(), inherited.clone());
+            edges.insert(Edge {
+                from: template_id.clone(),
+                to: inherited_id,
+                kind: EdgeKind::Inherits,
+            });
+        }
+    }
+
+    for (project_id, (project, bindings)) in &snapshot.projects {
+        let project_node = format!("project:{}", project_id);
+        add_node(&mut nodes, NodeKind::Project, project_node.clone(), project.display_name.clone());
+
+        for binding in bindings {
+            let binding_node = format!("binding:{}/{}", binding.namespace, binding.id);
+            add_node(&mut nodes, NodeKind::Binding, binding_node.clone(), binding.id.clone());
+
+            let template_node = format!("template:{}", binding.role_template_name);
+            add_node(
+                &mut nodes,
+                NodeKind::Template,
+                template_node.clone(),
+                binding.role_template_name.clone(),
+            );
+
+            edges.insert(Edge {
+                from: binding_node.clone(),
+                to: template_node,
+                kind: EdgeKind::Grants,
+            });
+            edges.insert(Edge {
+                from: binding_node.clone(),
+                to: project_node.clone(),
+                kind: EdgeKind::Scopes,
+            });
+
+            for (kind, name) in subject_refs(binding) {
+                let subject_node = format!("subject:{}:{}", kind.as_str(), name);
+                add_node(&mut nodes, NodeKind::Subject(kind), subject_node.clone(), name);
+                edges.insert(Edge {
+                    from: subject_node,
+                    to: binding_node.clone(),
+                    kind: EdgeKind::Binds,
+                });
+            }
+        }
+    }
+
+    RbacGraph {
+        nodes: nodes.into_values().collect(),
+        edges: edges.into_iter().collect(),
+    }
+}
+
+fn add_node(nodes: &mut BTreeMap<String, Node>, kind: NodeKind, id: String, label: String) {
+    nodes.entry(id.clone()).or_insert(Node { id, kind, label });
+}
+
+impl RbacGraph {
+    /// Render the graph in Graphviz DOT format, e.g. for `dot -Tsvg`
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph rbac {\n");
+        for node in &self.nodes {
+            let shape = match node.kind {
+                NodeKind::Subject(_) => "ellipse",
+                NodeKind::Binding => "diamond",
+                NodeKind::Template => "box",
+                NodeKind::Project => "folder",
+            };
+            dot.push_str(&format!(
+                "  \"{}\" [label=\"{}\", shape={}];\n",
+                escape_dot(&node.id),
+                escape_dot(&node.label),
+                shape
+            ));
+        }
+        for edge in &self.edges {
+            dot.push_str(&format!(
+                "  \"{}\" -> \"{}\" [label=\"{}\"];\n",
+                escape_dot(&edge.from),
+                escape_dot(&edge.to),
+                edge.kind.as_str()
+            ));
+        }
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+fn escape_dot(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    use crate::cluster::Cluster;
+    use crate::project::Project;
+    use crate::rt::RoleTemplate;
+
+    fn sample_role_template(id: &str, inherits: Option<Vec<String>>) -> RoleTemplate {
+        RoleTemplate::new(
+            None, None, None, None, None, None, None, None, None, None, None,
+            id.to_string(),
+            None,
+            inherits,
+            None,
+        )
+    }
+
+    fn sample_binding(id: &str, user: Option<&str>, group: Option<&str>) -> ProjectRoleTemplateBinding {
+        ProjectRoleTemplateBinding::new(
+            None,
+            None,
+            group.map(|g| g.to_string()),
+            id.to_string(),
+            None,
+            "p-1".to_string(),
+            "c-1:p-1".to_string(),
+            None,
+            "project-member".to_string(),
+            None,
+            None,
+            user.map(|u| u.to_string()),
+            None,
+        )
+    }
+
+    fn sample_snapshot() -> ClusterConfig {
+        let project = Project::new(
+            None,
+            "c-1".to_string(),
+            None,
+            String::new(),
+            "Project One".to_string(),
+            None,
+            "p-1".to_string(),
+            None,
+            None,
+            "c-1".to_string(),
+            None,
+            None,
+            None,
+        );
+        let mut projects = HashMap::new();
+        projects.insert(
+            "p-1".to_string(),
+            (
+                project,
+                vec![
+                    sample_binding("prtb-1", Some("u-alice"), None),
+                    sample_binding("prtb-2", None, Some("local://devs")),
+                ],
+            ),
+        );
+
+        ClusterConfig {
+            cluster: Cluster::new("c-1".to_string(), "Cluster One".to_string(), None),
+            role_templates: vec![
+                sample_role_template("project-member", Some(vec!["view".to_string()])),
+                sample_role_template("view", None),
+            ],
+            projects,
+        }
+    }
+
+    #[test]
+    fn test_node_and_edge_counts() {
+        let graph = build_graph(&sample_snapshot());
+
+        // 2 templates, 1 project, 2 bindings, 2 subjects
+        assert_eq!(graph.nodes.len(), 7);
+        // 1 inherits, 2 grants, 2 scopes, 2 binds
+        assert_eq!(graph.edges.len(), 7);
+        assert_eq!(
+            graph.edges.iter().filter(|e| e.kind == EdgeKind::Inherits).count(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_dot_output_is_structurally_valid() {
+        let graph = build_graph(&sample_snapshot());
+        let dot = graph.to_dot();
+
+        assert!(dot.starts_with("digraph rbac {\n"));
+        assert!(dot.trim_end().ends_with('}'));
+        assert_eq!(dot.matches('{').count(), dot.matches('}').count());
+        assert_eq!(dot.matches(" -> ").count(), graph.edges.len());
+        assert_eq!(dot.matches("shape=").count(), graph.nodes.len());
+    }
+
+    #[test]
+    fn test_all_subjects_deduplicates_principal_and_name_forms() {
+        let mut snapshot = sample_snapshot();
+        let (_, bindings) = snapshot.projects.get_mut("p-1").unwrap();
+
+        // the same group by principal only, and by both forms
+        bindings.push(sample_binding("prtb-3", None, Some("local://devs")));
+        let mut both = sample_binding("prtb-4", None, None);
+        both.group_name = Some("devs".to_string());
+        both.group_principal_name = Some("local://devs".to_string());
+        bindings.push(both);
+        // the same group by name only
+        let mut by_name = sample_binding("prtb-5", None, None);
+        by_name.group_name = Some("devs".to_string());
+        bindings.push(by_name);
+        // a user and a service account
+        bindings.push(sample_binding("prtb-6", Some("u-alice"), None));
+        let mut service_account = sample_binding("prtb-7", None, None);
+        service_account.service_account = Some("ns:deployer".to_string());
+        bindings.push(service_account);
+
+        let subjects = all_subjects(&snapshot, &[], &[]);
+
+        assert_eq!(
+            subjects,
+            vec![
+                Subject {
+                    kind: SubjectKind::User,
+                    principal_name: None,
+                    name: Some("u-alice".to_string()),
+                },
+                Subject {
+                    kind: SubjectKind::Group,
+                    principal_name: Some("local://devs".to_string()),
+                    name: Some("devs".to_string()),
+                },
+                Subject {
+                    kind: SubjectKind::ServiceAccount,
+                    principal_name: None,
+                    name: Some("ns:deployer".to_string()),
+                },
+            ]
+        );
+    }
+
+    fn sample_cluster_binding(id: &str) -> ClusterRoleTemplateBinding {
+        ClusterRoleTemplateBinding {
+            annotations: None,
+            cluster_name: "c-1".to_string(),
+            group_name: None,
+            group_principal_name: None,
+            id: id.to_string(),
+            labels: None,
+            namespace: "c-1".to_string(),
+            resource_version: None,
+            role_template_name: "cluster-member".to_string(),
+            uid: None,
+            user_name: None,
+            user_principal_name: None,
+        }
+    }
+
+    #[test]
+    fn test_all_subjects_deduplicates_across_binding_kinds() {
+        // the snapshot references u-alice by name and local://devs by principal
+        let snapshot = sample_snapshot();
+        let mut both_group_forms = sample_cluster_binding("crtb-1");
+        both_group_forms.group_name = Some("devs".to_string());
+        both_group_forms.group_principal_name = Some("local://devs".to_string());
+        let mut group_by_name = sample_cluster_binding("crtb-2");
+        group_by_name.group_name = Some("devs".to_string());
+        let global_binding = GlobalRoleBinding {
+            global_role_name: "user".to_string(),
+            group_principal_name: None,
+            id: "grb-1".to_string(),
+            user_name: Some("u-alice".to_string()),
+            user_principal_name: Some("local://u-alice".to_string()),
+        };
+
+        let subjects = all_subjects(&snapshot, &[both_group_forms, group_by_name], &[global_binding]);
+
+        assert_eq!(
+            subjects,
+            vec![
+                Subject {
+                    kind: SubjectKind::User,
+                    principal_name: Some("local://u-alice".to_string()),
+                    name: Some("u-alice".to_string()),
+                },
+                Subject {
+                    kind: SubjectKind::Group,
+                    principal_name: Some("local://devs".to_string()),
+                    name: Some("devs".to_string()),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_all_subjects_keeps_kinds_apart() {
+        let mut snapshot = sample_snapshot();
+        let (_, bindings) = snapshot.projects.get_mut("p-1").unwrap();
+        bindings.push(sample_binding("prtb-3", None, Some("u-alice")));
+
+        let subjects = all_subjects(&snapshot, &[], &[]);
+
+        assert_eq!(subjects.len(), 3);
+        assert_eq!(subjects.iter().filter(|s| s.kind == SubjectKind::Group).count(), 2);
+    }
+}
@@ -0,0 +1,210 @@
+use std::path::Path;
+
+use base64::Engine;
+use serde::Deserialize;
+
+use rancher_client::apis::configuration::Configuration;
+
+use crate::client::configure_tls;
+use crate::{rancher_config_init, RancherError};
+
+/// The parts of a kubeconfig file needed to reach Rancher
+#[derive(Deserialize)]
+struct Kubeconfig {
+    #[serde(rename = "current-context")]
+    current_context: Option<String>,
+    #[serde(default)]
+    clusters: Vec<Named<KubeconfigCluster>>,
+    #[serde(default)]
+    contexts: Vec<Named<KubeconfigContext>>,
+    #[serde(default)]
+    users: Vec<Named<KubeconfigUser>>,
+}
+
+/// An entry of the `clusters`, `contexts` or `users` list, e.g. `{name: local, cluster: {...}}`
+#[derive(Deserialize)]
+struct Named<T> {
+    name: String,
+    #[serde(alias = "cluster", alias = "context", alias = "user")]
+    value: T,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "kebab-case")]
+struct KubeconfigCluster {
+    server: String,
+    certificate_authority: Option<String>,
+    certificate_authority_data: Option<String>,
+    #[serde(default)]
+    insecure_skip_tls_verify: bool,
+}
+
+#[derive(Deserialize)]
+struct KubeconfigContext {
+    cluster: String,
+    user: String,
+}
+
+#[derive(Deserialize)]
+struct KubeconfigUser {
+    token: Option<String>,
+}
+
+/// Find the entry of a kubeconfig list by name
+fn find<'a, T>(entries: &'a [Named<T>], kind: &str, name: &str) -> Result<&'a T, RancherError> {
+    entries
+        .iter()
+        .find(|entry| entry.name == name)
+        .map(|entry| &entry.value)
+        .ok_or_else(|| RancherError::Invalid(format!("kubeconfig has no {} named {}", kind, name)))
+}
+
+/// Build a configuration from the content of a kubeconfig file
+///
+/// `base_dir` resolves a relative `certificate-authority` path, as kubectl does.
+fn parse_kubeconfig(
+    content: &str,
+    base_dir: &Path,
+    context: Option<&str>,
+) -> Result<Configuration, RancherError> {
+    let kubeconfig: Kubeconfig = serde_yaml::from_str(content)
+        .map_err(|e| RancherError::Invalid(format!("invalid kubeconfig: {}", e)))?;
+
+    let context_name = context
+        .map(str::to_string)
+        .or(kubeconfig.current_context)
+        .ok_or_else(|| RancherError::Invalid("kubeconfig has no current-context".to_string()))?;
+    let context = find(&kubeconfig.contexts, "context", &context_name)?;
+    let cluster = find(&kubeconfig.clusters, "cluster", &context.cluster)?;
+    let user = find(&kubeconfig.users, "user", &context.user)?;
+    let token = user.token.as_deref().ok_or_else(|| {
+        RancherError::Invalid(format!(
+            "kubeconfig user {} has no token, only bearer tokens are supported",
+            context.user
+        ))
+    })?;
+
+    let mut configuration = rancher_config_init(cluster.server.trim_end_matches('/'), token);
+
+    // the inline data takes precedence over the file, as with kubectl
+    let certificate_authority = if let Some(data) = &cluster.certificate_authority_data {
+        let pem = base64::engine::general_purpose::STANDARD
+            .decode(data.trim())
+            .map_err(|e| RancherError::Invalid(format!("invalid certificate-authority-data: {}", e)))?;
+        Some(pem)
+    } else if let Some(path) = &cluster.certificate_authority {
+        let pem = std::fs::read(base_dir.join(path)).map_err(|e| {
+            RancherError::Invalid(format!("failed to read certificate-authority {}: {}", path, e))
+        })?;
+        Some(pem)
+    } else {
+        None
+    };
+    configure_tls(
+        &mut configuration,
+        certificate_authority.as_deref(),
+        cluster.insecure_skip_tls_verify,
+    )?;
+    Ok(configuration)
+}
+
+/// Build a configuration from a kubeconfig file pointing at the Rancher local cluster
+///
+/// The server URL of the context becomes the base path, its user's token the bearer token, and
+/// the certificate authority of its cluster is trusted, from `certificate-authority-data` or
+/// `certificate-authority`. Users authenticating with a client certificate or an exec plugin are
+/// not supported.
+///
+/// # Arguments
+///
+/// * `path` - The path of the kubeconfig file, e.g. `~/.kube/config`
+/// * `context` - The context to use, `None` for the `current-context` of the file
+///
+/// # Returns
+///
+/// * `Configuration` - The configuration to use for the requests
+///
+/// # Errors
+///
+/// * `RancherError` - `Invalid` when the file cannot be read or parsed, the context, its cluster
+///   or its user is missing, the user has no token or the certificate authority is invalid
+pub fn configuration_from_kubeconfig(
+    path: &Path,
+    context: Option<&str>,
+) -> Result<Configuration, RancherError> {
+    let content = std::fs::read_to_string(path).map_err(|e| {
+        RancherError::Invalid(format!("failed to read kubeconfig {}: {}", path.display(), e))
+    })?;
+    parse_kubeconfig(&content, path.parent().unwrap_or(Path::new(".")), context)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KUBECONFIG: &str = "
+apiVersion: v1
+kind: Config
+current-context: local
+clusters:
+- name: local
+  cluster:
+    server: https://rancher.example.com/k8s/clusters/local/
+- name: staging
+  cluster:
+    server: https://rancher.staging.example.com
+    insecure-skip-tls-verify: true
+contexts:
+- name: local
+  context:
+    cluster: local
+    user: local
+- name: staging
+  context:
+    cluster: staging
+    user: staging
+users:
+- name: local
+  user:
+    token: kubeconfig-u-abc12:secret
+- name: staging
+  user:
+    token: kubeconfig-u-def34:other
+";
+
+    fn bearer_token(configuration: &Configuration) -> Option<&str> {
+        configuration.api_key.as_ref().map(|key| key.key.as_str())
+    }
+
+    #[test]
+    fn test_current_context_is_the_default() {
+        let configuration = parse_kubeconfig(KUBECONFIG, Path::new("."), None).unwrap();
+
+        assert_eq!(configuration.base_path, "https://rancher.example.com/k8s/clusters/local");
+        assert_eq!(bearer_token(&configuration), Some("kubeconfig-u-abc12:secret"));
+        assert_eq!(
+            configuration.api_key.as_ref().and_then(|key| key.prefix.as_deref()),
+            Some("Bearer")
+        );
+    }
+
+    #[test]
+    fn test_explicit_context() {
+        let configuration = parse_kubeconfig(KUBECONFIG, Path::new("."), Some("staging")).unwrap();
+
+        assert_eq!(configuration.base_path, "https://rancher.staging.example.com");
+        assert_eq!(bearer_token(&configuration), Some("kubeconfig-u-def34:other"));
+    }
+
+    #[test]
+    fn test_unknown_context() {
+        let result = parse_kubeconfig(KUBECONFIG, Path::new("."), Some("production"));
+
+        match result {
+            Err(RancherError::Invalid(message)) => {
+                assert_eq!(message, "kubeconfig has no context named production")
+            }
+            other => panic!("expected an invalid kubeconfig, got {:?}", other.map(|c| c.base_path)),
+        }
+    }
+}
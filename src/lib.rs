@@ -1,10 +1,36 @@
 // This file will contain all the functions that will be used to interact and extract from the Rancher API
+pub mod apply;
+pub mod catalog;
+pub mod client;
 pub mod cluster;
 pub mod config;
+pub mod crtb;
+pub mod diff;
+pub mod error;
+pub mod export;
 pub mod git;
+pub mod globalrole;
+pub mod graph;
+pub mod kubeconfig;
+pub mod metadata;
+pub mod paginate;
 pub mod project;
 pub mod prtb;
+pub mod reconcile;
+pub mod report;
+pub(crate) mod request;
+pub mod retry;
 pub mod rt;
+pub mod schema;
+pub mod selector;
+pub mod setting;
+#[cfg(test)]
+pub(crate) mod test_support;
+pub mod token;
+pub mod user;
+
+pub use error::{ApiStatus, InvalidLimit, RancherError, ResourceContext, WithResource, WithResourceExt};
+pub use selector::{FieldSelector, LabelSelector};
 
 use json_patch::diff;
 use serde_json::Value;
@@ -15,11 +41,10 @@ use std::collections::{BTreeSet, HashMap};
 
 use cluster::Cluster;
 use config::{ClusterConfig, RancherClusterConfig};
-use project::{Project, PROJECT_EXCLUDE_PATHS};
-use prtb::{ProjectRoleTemplateBinding, PRTB_EXCLUDE_PATHS};
-use rt::{get_role_templates, RoleTemplate, RT_EXCLUDE_PATHS};
+use project::Project;
+use prtb::ProjectRoleTemplateBinding;
+use rt::{get_role_templates, RoleTemplate};
 
-use rancher_client::models::{IoCattleManagementv3Project, IoCattleManagementv3ProjectRoleTemplateBinding, IoCattleManagementv3RoleTemplate};
 use rancher_client::apis::configuration::{ApiKey, Configuration};
 
 
@@ -295,100 +320,84 @@ pub async fn download_current_configuration(
     }
 }
 
+/// Load the current configuration of a cluster from the Rancher API
+///
+/// Exits the process when the configuration cannot be loaded, use
+/// `try_load_configuration_from_rancher` to handle the error instead.
 #[async_backtrace::framed]
 pub async fn load_configuration_from_rancher(
     configuration: &Configuration,
     cluster_id: &str) -> RancherClusterConfig {
-
-    
-
-    // Get the current configuration from the Rancher API
-    let rancher_clusters = cluster::get_clusters(configuration)
+    try_load_configuration_from_rancher(configuration, cluster_id)
         .await
-        .map_err(|e| {
-            println!("Failed to get clusters: {:?}", e);
+        .unwrap_or_else(|e| {
+            println!("Failed to load the configuration of cluster {}: {}", cluster_id, e);
             std::process::exit(1);
         })
-        .unwrap();
+}
+
+/// Load the current configuration of a cluster from the Rancher API
+///
+/// # Arguments
+///
+/// * `configuration` - The configuration to use for the requests
+/// * `cluster_id` - The ID of the cluster to load, e.g. `local`
+///
+/// # Returns
+///
+/// * `RancherClusterConfig` - The cluster with its role templates, projects and their bindings
+///
+/// # Errors
+///
+/// * `RancherError` - `NotFound` when the cluster does not exist, or the error of the first
+///   request that failed
+#[async_backtrace::framed]
+pub async fn try_load_configuration_from_rancher(
+    configuration: &Configuration,
+    cluster_id: &str,
+) -> Result<RancherClusterConfig, RancherError> {
+    let rancher_clusters = cluster::get_clusters(configuration).await?;
     let rancher_cluster = rancher_clusters
         .items
         .into_iter()
         .find(|cluster| cluster.metadata.as_ref().and_then(|m| m.name.as_deref()) == Some(cluster_id))
-        .unwrap();
+        .ok_or(RancherError::NotFound)?;
 
-    let rancher_role_templates = get_role_templates(
-        configuration,
-        None,
-        None,
-        None,
-        None,
-        None,
-        None,
-    ).await.map_err(|e| {
-        println!("Failed to get role templates: {:?}", e);
-        std::process::exit(1);
-    }).unwrap();
-
-    let rrt: Vec<IoCattleManagementv3RoleTemplate> = rancher_role_templates.items.clone();
-
-    // let cluster_projects: HashMap<String, (IoCattleManagementv3Project, Vec<IoCattleManagementv3ProjectRoleTemplateBinding>)> = HashMap::new();
-
-    let rancher_projects = project::get_projects(
-        configuration,
-        cluster_id,
-        None,
-        None,
-        None,
-        None,
-        None,
-        None,
-    ).await.map_err(|e| {
-        println!("Failed to get projects: {:?}", e);
-        std::process::exit(1);
-    }).unwrap();
+    let rancher_role_templates =
+        get_role_templates(configuration, None, None, None, None, None, None).await?;
 
+    let rancher_projects =
+        project::get_projects(configuration, cluster_id, None, None, None, None, None, None).await?;
 
     let mut rancher_cluster_config = RancherClusterConfig {
         cluster: rancher_cluster,
-        role_templates: rrt,
+        role_templates: rancher_role_templates.items,
         projects: HashMap::new(),
     };
 
-    let rprojects: Vec<IoCattleManagementv3Project> = rancher_projects.items.clone();
-        
-    for rproject in rprojects {
-        let project = rproject.clone();
-        let project_id = project.metadata.as_ref().and_then(|m| m.name.as_deref()).unwrap();
-        let rancher_project_role_template_bindings =
-            prtb::get_namespaced_project_role_template_bindings(
-                configuration,
-                project_id,
-                None,
-                None,
-                None,
-                None,
-                None,
-                None,
-            ).await.map_err(|e| {
-            println!("Failed to get project role template bindings: {:?}", e);
-            std::process::exit(1);
-        }).unwrap();
-        let rprtbs: Vec<IoCattleManagementv3ProjectRoleTemplateBinding> = rancher_project_role_template_bindings.items.clone();
-        
-        rancher_cluster_config.projects.insert(
-            project_id.to_string(),
-            (project, rprtbs),
-        );
-    }
-
-
-    return rancher_cluster_config;
-
+    for project in rancher_projects.items {
+        let project_id = project
+            .metadata
+            .as_ref()
+            .and_then(|m| m.name.clone())
+            .ok_or_else(|| RancherError::Invalid("project without metadata.name".to_string()))?;
+        let rprtbs = prtb::get_namespaced_project_role_template_bindings(
+            configuration,
+            &project_id,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+        )
+        .await?
+        .items;
+        rancher_cluster_config.projects.insert(project_id, (project, rprtbs));
     }
 
-
-
-
+    Ok(rancher_cluster_config)
+}
 
 // load the entire configuration from the base path
 ///
@@ -605,63 +614,12 @@ pub fn compute_cluster_diff(
 
     let desired_state: RancherClusterConfig = serde_json::from_value(desired_state.clone()).unwrap();
 
-
-
-    // let cluster = current_state.cluster.clone();
-    // create a new role template object
-    let c_role_template = current_state.role_templates.clone();
-    // create a new project object
-    let c_project = current_state.projects.clone();
-
-    let mut patches = Vec::new();
-
-    // loop through the role templates and compare them
-    for crt in &c_role_template {
-        // check if the role template exists in the desired state
-        if let Some(desired_rt) = desired_state
-            .role_templates
-            .iter()
-            .find(|drole_template| drole_template.metadata.as_ref().unwrap().name == crt.metadata.as_ref().unwrap().name) {
-            // compute the diff between the current state and the desired state
-            // convert the current state to a JSON value
-            let mut crtv = serde_json::to_value(crt).unwrap();
-            let mut drtv = serde_json::to_value(desired_rt).unwrap();
-            clean_up_value(&mut crtv, RT_EXCLUDE_PATHS);
-            clean_up_value(&mut drtv, RT_EXCLUDE_PATHS);
-            let patch = create_json_patch::<IoCattleManagementv3RoleTemplate>(&crtv, &drtv);
-            if let Some(patch) = patch { patches.push(patch) }
-        }
-    }
-
-    // loop through the projects and compare them
-    for (c_project_id, (c_project, cprtbs)) in &c_project {
-        // check if the project exists in the desired state
-        if let Some((d_project, dprtbs)) = desired_state.projects.get(c_project_id) {
-
-            let mut cpv = serde_json::to_value(c_project).unwrap();
-            let mut dpv = serde_json::to_value(d_project).unwrap();
-            clean_up_value(&mut cpv, PROJECT_EXCLUDE_PATHS);
-            clean_up_value(&mut dpv, PROJECT_EXCLUDE_PATHS);
-            // TODO: fix conversion from IoCattleManagementv3Project to Value to Project will cause errors bc of fields not matching ie clusterName -> clusterName -> cluster_name
-            let patch = create_json_patch::<IoCattleManagementv3Project>(&cpv, &dpv);
-            if let Some(patch) = patch { patches.push(patch) }
-
-            // loop through the project role template bindings and compare them
-            for cprtb in cprtbs {
-                // check if the project role template binding exists in the desired state
-                if let Some(desired_prtb) = dprtbs.iter().find(|dprtb| dprtb.metadata.as_ref().unwrap().name == cprtb.metadata.as_ref().unwrap().name) {
-                    let mut cprtbv = serde_json::to_value(cprtb).unwrap();
-                    let mut dprtbv = serde_json::to_value(desired_prtb).unwrap();
-                    clean_up_value(&mut cprtbv, PRTB_EXCLUDE_PATHS);
-                    clean_up_value(&mut dprtbv, PRTB_EXCLUDE_PATHS);
-                    let patch = create_json_patch::<IoCattleManagementv3ProjectRoleTemplateBinding>(&cprtbv, &dprtbv);
-                    if let Some(patch) = patch { patches.push(patch) };
-                }
-            }
-        }
-    }
-
-    patches
+    reconcile::plan_cluster(&current_state, &desired_state)
+        .unwrap()
+        .changes
+        .into_iter()
+        .map(|change| change.patch)
+        .collect()
 }
 
 /// load a specific project configuration from the base path
@@ -720,30 +678,7 @@ pub async fn load_project(
 /// * `value` - The mutable JSON object to clean
 /// * `exclude_paths` - A list of dot-separated paths to remove (e.g., ["status", "metadata.creationTimestamp"])
 pub fn clean_up_value(value: &mut Value, exclude_paths: &[&str]) {
-    for path in exclude_paths {
-        let parts: Vec<&str> = path.split('.').collect();
-        remove_path_and_return(value, &parts);
-    }
-}
-
-/// Remove a deeply nested field from a JSON object and remove it.
-/// Traverses objects by key. Returns `None` if any key is missing or the path is invalid.
-fn remove_path_and_return(value: &mut Value, path: &[&str]) -> Option<Value> {
-    if path.is_empty() {
-        return None;
-    }
-
-    let mut current = value;
-
-    // Traverse to the parent of the key to remove
-    for &key in &path[..path.len() - 1] {
-        current = current.as_object_mut()?.get_mut(key)?;
-    }
-
-    let last_key = *path.last().unwrap();
-
-    // Remove the target key
-    current.as_object_mut().unwrap().remove(last_key)
+    diff::prune_paths(value, exclude_paths);
 }
 
 /// Compare two optional annotation‐maps and print per‐key changes.
@@ -921,3 +856,141 @@ impl std::str::FromStr for ResourceVersionMatch {
         }
     }
 }
+
+/// The kinds of Rancher objects managed by this crate
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ResourceKind {
+    Cluster,
+    Project,
+    ProjectRoleTemplateBinding,
+    RoleTemplate,
+}
+
+impl ResourceKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ResourceKind::Cluster => "Cluster",
+            ResourceKind::Project => "Project",
+            ResourceKind::ProjectRoleTemplateBinding => "ProjectRoleTemplateBinding",
+            ResourceKind::RoleTemplate => "RoleTemplate",
+        }
+    }
+
+    /// The API path of a single object of this kind
+    ///
+    /// # Arguments
+    /// * `namespace` - The namespace of the object, ignored for cluster scoped kinds
+    /// * `name` - The name of the object
+    pub fn api_path(&self, namespace: Option<&str>, name: &str) -> String {
+        let namespace = namespace.unwrap_or_default();
+        match self {
+            ResourceKind::Cluster => format!("/apis/management.cattle.io/v3/clusters/{}", name),
+            ResourceKind::Project => format!(
+                "/apis/management.cattle.io/v3/namespaces/{}/projects/{}",
+                namespace, name
+            ),
+            ResourceKind::ProjectRoleTemplateBinding => format!(
+                "/apis/management.cattle.io/v3/namespaces/{}/projectroletemplatebindings/{}",
+                namespace, name
+            ),
+            ResourceKind::RoleTemplate => {
+                format!("/apis/management.cattle.io/v3/roletemplates/{}", name)
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for ResourceKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// The identity of an object, to index objects in a `HashMap` or `HashSet`
+///
+/// `namespace` is empty for cluster scoped kinds such as role templates.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ResourceKey {
+    pub namespace: String,
+    pub name: String,
+}
+
+impl ResourceKey {
+    pub fn new(namespace: impl Into<String>, name: impl Into<String>) -> Self {
+        ResourceKey {
+            namespace: namespace.into(),
+            name: name.into(),
+        }
+    }
+}
+
+impl std::fmt::Display for ResourceKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.namespace.is_empty() {
+            write!(f, "{}", self.name)
+        } else {
+            write!(f, "{}/{}", self.namespace, self.name)
+        }
+    }
+}
+
+/// The message of a Kubernetes `Status` response body, or the raw body when it has none
+pub(crate) fn status_message(content: &str) -> String {
+    serde_json::from_str::<Value>(content)
+        .ok()
+        .and_then(|status| status.get("message").and_then(Value::as_str).map(String::from))
+        .unwrap_or_else(|| content.to_string())
+}
+
+/// Whether a JSON merge patch sets a value at a dotted path (e.g. `metadata.resourceVersion`)
+///
+/// A `null` on the way, such as `{"metadata": null}`, removes the whole member and every field
+/// below it, so it touches the path as well.
+pub(crate) fn merge_patch_touches(patch: &Value, path: &str) -> bool {
+    let mut value = patch;
+    for key in path.split('.') {
+        match value.get(key) {
+            Some(Value::Null) => return true,
+            Some(member) => value = member,
+            None => return false,
+        }
+    }
+    true
+}
+
+/// Page size used by the helpers that fetch every page of a list
+pub const DEFAULT_PAGE_SIZE: i32 = 500;
+
+/// Largest page size the list helpers send by default, see `RequestOptions::max_page_size`
+pub const MAX_PAGE_SIZE: i32 = 1000;
+
+/// Validate the limit of a list request and clamp it to the largest page size of `options`
+///
+/// # Arguments
+///
+/// * `limit` - The requested limit, `None` lets the server decide
+/// * `options` - The options of the request, holding the largest page size to send
+///
+/// # Returns
+///
+/// * `Option<i32>` - The limit to send
+///
+/// # Errors
+///
+/// * `InvalidLimit` - When the limit, or the largest page size it is clamped to, is zero or
+///   negative, it converts into `RancherError::Invalid`
+pub fn checked_limit(limit: Option<i32>, options: &client::RequestOptions) -> Result<Option<i32>, InvalidLimit> {
+    match limit.map(|limit| limit.min(options.max_page_size)) {
+        Some(limit) if limit <= 0 => Err(InvalidLimit(limit)),
+        limit => Ok(limit),
+    }
+}
+
+/// The continue token of a list, `None` when this was the last page
+pub(crate) fn continue_token(
+    metadata: Option<&rancher_client::models::IoK8sApimachineryPkgApisMetaV1ListMeta>,
+) -> Option<String> {
+    metadata
+        .and_then(|m| m.r#continue.clone())
+        .filter(|token| !token.is_empty())
+}
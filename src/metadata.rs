@@ -0,0 +1,609 @@
+use std::collections::{BTreeMap, HashMap};
+
+use reqwest::Method;
+use serde::Serializer;
+use serde_json::{json, Map, Value};
+
+use rancher_client::apis::{configuration::Configuration, Error};
+
+use crate::request::{parse_response, send_request, JSON_CONTENT_TYPE, MERGE_PATCH_CONTENT_TYPE};
+use crate::ResourceKind;
+
+/// Options controlling how labels and annotations are reconciled
+#[derive(Debug, Clone, Default)]
+pub struct MetadataOptions {
+    /// Keys starting with any of these prefixes are never added, changed or removed
+    /// (e.g. `field.cattle.io/` or `lifecycle.cattle.io/` which are managed by Rancher)
+    pub ignore_prefixes: Vec<String>,
+}
+
+impl MetadataOptions {
+    fn is_ignored(&self, key: &str) -> bool {
+        self.ignore_prefixes
+            .iter()
+            .any(|prefix| key.starts_with(prefix.as_str()))
+    }
+}
+
+/// Prefixes of the label and annotation keys Rancher and Kubernetes set on objects themselves
+pub const SERVER_MANAGED_KEY_PREFIXES: &[&str] = &[
+    "authz.management.cattle.io/",
+    "cleanup.cattle.io/",
+    "field.cattle.io/creatorId",
+    "kubectl.kubernetes.io/last-applied-configuration",
+    "lifecycle.cattle.io/",
+];
+
+/// Annotation holding the content hash of the desired state an object was last applied from
+pub const CONTENT_HASH_ANNOTATION: &str = "rancher-cac/hash";
+
+/// Format a content hash the way it is stored in `CONTENT_HASH_ANNOTATION`
+pub fn format_content_hash(hash: u64) -> String {
+    format!("{:016x}", hash)
+}
+
+/// The content hash stored in the annotations of an object, if any
+pub fn stored_content_hash(annotations: Option<&HashMap<String, String>>) -> Option<&str> {
+    annotations
+        .and_then(|annotations| annotations.get(CONTENT_HASH_ANNOTATION))
+        .map(String::as_str)
+}
+
+/// Prepare the API form of an object for hashing
+///
+/// Drops `CONTENT_HASH_ANNOTATION`, then the annotation and label maps when they are empty, so an
+/// object hashes the same with or without its hash annotation. A `null` metadata is dropped too,
+/// the same as a missing one.
+pub(crate) fn strip_for_content_hash(value: &mut Value) {
+    if value.get("metadata").is_some_and(Value::is_null) {
+        if let Some(object) = value.as_object_mut() {
+            object.remove("metadata");
+        }
+    }
+    let metadata = match value.get_mut("metadata").and_then(Value::as_object_mut) {
+        Some(metadata) => metadata,
+        None => return,
+    };
+    if let Some(annotations) = metadata.get_mut("annotations").and_then(Value::as_object_mut) {
+        annotations.remove(CONTENT_HASH_ANNOTATION);
+    }
+    for field in ["annotations", "labels"] {
+        if metadata
+            .get(field)
+            .and_then(Value::as_object)
+            .is_some_and(Map::is_empty)
+        {
+            metadata.remove(field);
+        }
+    }
+}
+
+/// Serialize an optional label or annotation map with its keys in alphabetical order
+///
+/// A `HashMap` iterates in a random order, so without sorting two exports of the same object
+/// would differ. Use with `#[serde(serialize_with = "...")]`.
+pub fn serialize_sorted<S: Serializer>(
+    map: &Option<HashMap<String, String>>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    match map {
+        Some(map) => serializer.serialize_some(&map.iter().collect::<BTreeMap<_, _>>()),
+        None => serializer.serialize_none(),
+    }
+}
+
+/// Whether a label or annotation key is set by Rancher or Kubernetes rather than the user
+fn is_server_managed(key: &str) -> bool {
+    SERVER_MANAGED_KEY_PREFIXES
+        .iter()
+        .any(|prefix| key.starts_with(prefix))
+}
+
+/// The entries of a label or annotation map worth writing to a file
+///
+/// Drops the `SERVER_MANAGED_KEY_PREFIXES` keys and `CONTENT_HASH_ANNOTATION`, which only describe
+/// the object as it is on the server. An empty result is `None`, so the map is left out entirely.
+///
+/// # Arguments
+///
+/// * `map` - The labels or annotations of an object
+///
+/// # Returns
+///
+/// * `Option<HashMap<String, String>>` - The user managed entries, `None` when there are none
+pub fn exportable_metadata(map: Option<&HashMap<String, String>>) -> Option<HashMap<String, String>> {
+    let entries: HashMap<String, String> = map
+        .into_iter()
+        .flatten()
+        .filter(|(key, _)| !is_server_managed(key) && key.as_str() != CONTENT_HASH_ANNOTATION)
+        .map(|(key, value)| (key.clone(), value.clone()))
+        .collect();
+    if entries.is_empty() {
+        None
+    } else {
+        Some(entries)
+    }
+}
+
+/// Compare two label or annotation maps, ignoring `SERVER_MANAGED_KEY_PREFIXES` keys
+///
+/// A missing map is equal to an empty one.
+///
+/// # Arguments
+///
+/// * `a` - The first map
+/// * `b` - The second map
+///
+/// # Returns
+///
+/// * `bool` - Whether the maps hold the same user managed entries
+pub fn user_metadata_eq(
+    a: Option<&HashMap<String, String>>,
+    b: Option<&HashMap<String, String>>,
+) -> bool {
+    fn user_entries(map: Option<&HashMap<String, String>>) -> HashMap<&str, &str> {
+        map.into_iter()
+            .flatten()
+            .filter(|(key, _)| !is_server_managed(key))
+            .map(|(key, value)| (key.as_str(), value.as_str()))
+            .collect()
+    }
+    user_entries(a) == user_entries(b)
+}
+
+/// Build a merge patch that only touches `metadata.labels` and `metadata.annotations`
+///
+/// A desired map of `None` leaves that map untouched. Keys present on the server but absent from
+/// the desired map are removed (set to `null`) unless they match an ignored prefix.
+///
+/// # Arguments
+///
+/// * `current_labels` - The labels currently set on the object
+/// * `current_annotations` - The annotations currently set on the object
+/// * `desired_labels` - The labels the object should have
+/// * `desired_annotations` - The annotations the object should have
+/// * `options` - The reconcile options
+///
+/// # Returns
+///
+/// * `Option<Value>` - The merge patch, or `None` when the metadata is already in sync
+pub fn metadata_patch(
+    current_labels: Option<&HashMap<String, String>>,
+    current_annotations: Option<&HashMap<String, String>>,
+    desired_labels: Option<&HashMap<String, String>>,
+    desired_annotations: Option<&HashMap<String, String>>,
+    options: &MetadataOptions,
+) -> Option<Value> {
+    let mut metadata = Map::new();
+
+    if let Some(desired) = desired_labels {
+        let changes = map_patch(current_labels, desired, options);
+        if !changes.is_empty() {
+            metadata.insert("labels".to_string(), Value::Object(changes));
+        }
+    }
+    if let Some(desired) = desired_annotations {
+        let changes = map_patch(current_annotations, desired, options);
+        if !changes.is_empty() {
+            metadata.insert("annotations".to_string(), Value::Object(changes));
+        }
+    }
+
+    if metadata.is_empty() {
+        return None;
+    }
+    Some(json!({ "metadata": metadata }))
+}
+
+/// Compute the merge patch entries needed to turn `current` into `desired`
+fn map_patch(
+    current: Option<&HashMap<String, String>>,
+    desired: &HashMap<String, String>,
+    options: &MetadataOptions,
+) -> Map<String, Value> {
+    let empty = HashMap::new();
+    let current = current.unwrap_or(&empty);
+    let mut changes = Map::new();
+
+    for (key, value) in desired {
+        if options.is_ignored(key) {
+            continue;
+        }
+        if current.get(key) != Some(value) {
+            changes.insert(key.clone(), Value::String(value.clone()));
+        }
+    }
+    for key in current.keys() {
+        if options.is_ignored(key) || desired.contains_key(key) {
+            continue;
+        }
+        changes.insert(key.clone(), Value::Null);
+    }
+
+    changes
+}
+
+/// Read a string map (labels or annotations) out of a raw object's metadata
+fn metadata_map(object: &Value, field: &str) -> Option<HashMap<String, String>> {
+    let map = object.get("metadata")?.get(field)?.as_object()?;
+    Some(
+        map.iter()
+            .filter_map(|(key, value)| value.as_str().map(|v| (key.clone(), v.to_string())))
+            .collect(),
+    )
+}
+
+/// Sync only the labels and annotations of an object, leaving the rest of it untouched
+///
+/// # Arguments
+///
+/// * `configuration` - The configuration to use for the request
+/// * `kind` - The kind of the object
+/// * `namespace` - The namespace of the object, `None` for cluster scoped kinds
+/// * `name` - The name of the object
+/// * `desired_labels` - The labels the object should have, `None` to leave labels untouched
+/// * `desired_annotations` - The annotations the object should have, `None` to leave annotations untouched
+/// * `options` - The reconcile options
+///
+/// # Returns
+///
+/// * `Option<Value>` - The patched object, or `None` when nothing needed to change
+///
+/// # Errors
+///
+/// * `Error<Value>` - The error that occurred while fetching or patching the object
+#[async_backtrace::framed]
+pub async fn reconcile_metadata(
+    configuration: &Configuration,
+    kind: ResourceKind,
+    namespace: Option<&str>,
+    name: &str,
+    desired_labels: Option<&HashMap<String, String>>,
+    desired_annotations: Option<&HashMap<String, String>>,
+    options: &MetadataOptions,
+) -> Result<Option<Value>, Error<Value>> {
+    let path = kind.api_path(namespace, name);
+
+    let current = parse_response(
+        send_request(configuration, Method::GET, &path, JSON_CONTENT_TYPE, None).await?,
+    )?;
+
+    let patch = match metadata_patch(
+        metadata_map(&current, "labels").as_ref(),
+        metadata_map(&current, "annotations").as_ref(),
+        desired_labels,
+        desired_annotations,
+        options,
+    ) {
+        Some(patch) => patch,
+        None => return Ok(None),
+    };
+
+    let patched = parse_response(
+        send_request(
+            configuration,
+            Method::PATCH,
+            &path,
+            MERGE_PATCH_CONTENT_TYPE,
+            Some(&patch),
+        )
+        .await?,
+    )?;
+    Ok(Some(patched))
+}
+
+/// A label or annotation entry Kubernetes would reject
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidMetadata {
+    /// `labels` or `annotations`
+    pub field: &'static str,
+    pub key: String,
+    /// Why the entry is rejected
+    pub reason: String,
+}
+
+/// Every label and annotation entry Kubernetes would reject, returned by `validate_metadata`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MetadataError {
+    pub invalid: Vec<InvalidMetadata>,
+}
+
+impl std::fmt::Display for MetadataError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let entries: Vec<String> = self
+            .invalid
+            .iter()
+            .map(|invalid| format!("{} key {:?}: {}", invalid.field, invalid.key, invalid.reason))
+            .collect();
+        write!(f, "invalid metadata: {}", entries.join("; "))
+    }
+}
+
+impl std::error::Error for MetadataError {}
+
+/// The longest name part of a key, and the longest label value
+const MAX_NAME_LENGTH: usize = 63;
+
+/// The longest prefix of a key, a DNS subdomain
+const MAX_PREFIX_LENGTH: usize = 253;
+
+/// Check a name part or a label value: alphanumeric at both ends, `-`, `_` and `.` in between
+pub(crate) fn check_name(name: &str, what: &str) -> Result<(), String> {
+    if name.len() > MAX_NAME_LENGTH {
+        return Err(format!("{} must be at most {} characters", what, MAX_NAME_LENGTH));
+    }
+    let alphanumeric_ends = name.starts_with(|c: char| c.is_ascii_alphanumeric())
+        && name.ends_with(|c: char| c.is_ascii_alphanumeric());
+    let allowed = name
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'));
+    if !alphanumeric_ends || !allowed {
+        return Err(format!(
+            "{} must consist of alphanumeric characters, '-', '_' or '.', and start and end with an alphanumeric character",
+            what
+        ));
+    }
+    Ok(())
+}
+
+/// Check a key prefix: a DNS subdomain of lowercase RFC 1123 labels separated by dots
+fn check_prefix(prefix: &str) -> Result<(), String> {
+    if prefix.len() > MAX_PREFIX_LENGTH {
+        return Err(format!("prefix must be at most {} characters", MAX_PREFIX_LENGTH));
+    }
+    let valid_label = |label: &str| {
+        !label.is_empty()
+            && label.len() <= MAX_NAME_LENGTH
+            && label.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+            && !label.starts_with('-')
+            && !label.ends_with('-')
+    };
+    if !prefix.split('.').all(valid_label) {
+        return Err("prefix must be a lowercase DNS subdomain, e.g. example.com".to_string());
+    }
+    Ok(())
+}
+
+/// Check a qualified key: an optional DNS subdomain prefix and `/`, then a name
+pub(crate) fn check_key(key: &str) -> Result<(), String> {
+    match key.split_once('/') {
+        Some((prefix, name)) => {
+            check_prefix(prefix)?;
+            if name.is_empty() {
+                return Err("name must not be empty".to_string());
+            }
+            check_name(name, "name")
+        }
+        None if key.is_empty() => Err("name must not be empty".to_string()),
+        None => check_name(key, "name"),
+    }
+}
+
+/// Check a label value: empty, or a name part
+pub(crate) fn check_label_value(value: &str) -> Result<(), String> {
+    if value.is_empty() {
+        Ok(())
+    } else {
+        check_name(value, "value")
+    }
+}
+
+/// Check that an object name is a DNS-1123 subdomain, as Kubernetes requires of `metadata.name`
+///
+/// A name is at most 253 characters of lowercase alphanumeric characters, `-` and `.`, and each
+/// dot-separated segment starts and ends with an alphanumeric character.
+///
+/// # Arguments
+///
+/// * `name` - The name to check, e.g. `p-abc12`
+///
+/// # Errors
+///
+/// * `&'static str` - What makes the name invalid
+pub fn validate_name(name: &str) -> Result<(), &'static str> {
+    if name.is_empty() {
+        return Err("name must not be empty");
+    }
+    if name.len() > MAX_PREFIX_LENGTH {
+        return Err("name must be at most 253 characters");
+    }
+    if name.chars().any(|c| c.is_ascii_uppercase()) {
+        return Err("name must be lowercase");
+    }
+    if !name
+        .chars()
+        .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '.')
+    {
+        return Err("name must consist of lowercase alphanumeric characters, '-' or '.'");
+    }
+    let alphanumeric_ends = |segment: &str| {
+        segment.starts_with(|c: char| c.is_ascii_alphanumeric())
+            && segment.ends_with(|c: char| c.is_ascii_alphanumeric())
+    };
+    if !name.split('.').all(alphanumeric_ends) {
+        return Err("name must start and end with an alphanumeric character, around every '.' too");
+    }
+    Ok(())
+}
+
+/// Check labels and annotations against the Kubernetes rules before they are sent
+///
+/// Keys are an optional DNS subdomain prefix of at most 253 characters followed by `/`, then a
+/// name of at most 63 characters. Label values follow the rules of a name and may be empty,
+/// annotation values are free form.
+///
+/// # Arguments
+///
+/// * `annotations` - The annotations of the object
+/// * `labels` - The labels of the object
+///
+/// # Errors
+///
+/// * `MetadataError` - Every offending entry, sorted by field and key
+pub fn validate_metadata(
+    annotations: Option<&HashMap<String, String>>,
+    labels: Option<&HashMap<String, String>>,
+) -> Result<(), MetadataError> {
+    let mut invalid = Vec::new();
+    for (key, _) in annotations.into_iter().flatten() {
+        if let Err(reason) = check_key(key) {
+            invalid.push(InvalidMetadata {
+                field: "annotations",
+                key: key.clone(),
+                reason,
+            });
+        }
+    }
+    for (key, value) in labels.into_iter().flatten() {
+        let checked = check_key(key).and_then(|_| check_label_value(value));
+        if let Err(reason) = checked {
+            invalid.push(InvalidMetadata {
+                field: "labels",
+                key: key.clone(),
+                reason,
+            });
+        }
+    }
+
+    if invalid.is_empty() {
+        return Ok(());
+    }
+    invalid.sort_by(|a, b| (a.field, &a.key).cmp(&(b.field, &b.key)));
+    Err(MetadataError { invalid })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn map(entries: &[(&str, &str)]) -> HashMap<String, String> {
+        entries
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_patch_only_contains_metadata_keys() {
+        let current_labels = map(&[("team", "a"), ("stale", "x")]);
+        let desired_labels = map(&[("team", "b")]);
+        let desired_annotations = map(&[("owner", "platform")]);
+
+        let patch = metadata_patch(
+            Some(&current_labels),
+            None,
+            Some(&desired_labels),
+            Some(&desired_annotations),
+            &MetadataOptions::default(),
+        )
+        .unwrap();
+
+        let root = patch.as_object().unwrap();
+        assert_eq!(root.keys().collect::<Vec<_>>(), vec!["metadata"]);
+        let metadata = root["metadata"].as_object().unwrap();
+        assert!(metadata.keys().all(|k| k == "labels" || k == "annotations"));
+        assert_eq!(metadata["labels"]["team"], "b");
+        assert_eq!(metadata["labels"]["stale"], Value::Null);
+        assert_eq!(metadata["annotations"]["owner"], "platform");
+    }
+
+    #[test]
+    fn test_ignored_prefixes_are_preserved() {
+        let current_annotations = map(&[("field.cattle.io/creatorId", "user-abc")]);
+        let desired_annotations = map(&[]);
+        let options = MetadataOptions {
+            ignore_prefixes: vec!["field.cattle.io/".to_string()],
+        };
+
+        let patch = metadata_patch(
+            None,
+            Some(&current_annotations),
+            None,
+            Some(&desired_annotations),
+            &options,
+        );
+        assert!(patch.is_none());
+    }
+
+    #[test]
+    fn test_in_sync_metadata_yields_no_patch() {
+        let labels = map(&[("team", "a")]);
+        let patch = metadata_patch(
+            Some(&labels),
+            None,
+            Some(&labels),
+            None,
+            &MetadataOptions::default(),
+        );
+        assert!(patch.is_none());
+    }
+
+    #[test]
+    fn test_strip_for_content_hash_treats_null_metadata_as_missing() {
+        let mut null_metadata = json!({ "metadata": null, "description": "d" });
+        let mut missing_metadata = json!({ "description": "d" });
+
+        strip_for_content_hash(&mut null_metadata);
+        strip_for_content_hash(&mut missing_metadata);
+
+        assert_eq!(null_metadata, missing_metadata);
+    }
+
+    #[test]
+    fn test_validate_metadata_accepts_kubernetes_keys() {
+        let annotations = map(&[("field.cattle.io/description", "free form, text!"), ("owner", "")]);
+        let labels = map(&[("app.kubernetes.io/name", "rancher-cac"), ("team", "")]);
+
+        assert_eq!(validate_metadata(Some(&annotations), Some(&labels)), Ok(()));
+        assert_eq!(validate_metadata(None, None), Ok(()));
+    }
+
+    #[test]
+    fn test_validate_metadata_lists_every_offending_key() {
+        let too_long = "a".repeat(64);
+        let annotations = map(&[(too_long.as_str(), "x"), ("owner", "platform")]);
+        let labels = map(&[("Example_Com/team", "a"), ("tier", "gold!")]);
+
+        let error = validate_metadata(Some(&annotations), Some(&labels)).unwrap_err();
+
+        let offending: Vec<(&str, &str)> = error
+            .invalid
+            .iter()
+            .map(|invalid| (invalid.field, invalid.key.as_str()))
+            .collect();
+        assert_eq!(
+            offending,
+            vec![
+                ("annotations", too_long.as_str()),
+                ("labels", "Example_Com/team"),
+                ("labels", "tier"),
+            ]
+        );
+        assert_eq!(error.invalid[0].reason, "name must be at most 63 characters");
+        assert!(error.invalid[1].reason.starts_with("prefix must be a lowercase DNS subdomain"));
+        assert!(error.to_string().contains("labels key \"tier\""));
+    }
+
+    #[test]
+    fn test_validate_name() {
+        for valid in ["p-abc12", "local", "admin-template", "token-x1", "a.b-c.d", "0"] {
+            assert_eq!(validate_name(valid), Ok(()), "{}", valid);
+        }
+
+        assert_eq!(validate_name(""), Err("name must not be empty"));
+        assert_eq!(validate_name(&"a".repeat(254)), Err("name must be at most 253 characters"));
+        assert_eq!(validate_name("Admin-Template"), Err("name must be lowercase"));
+        assert_eq!(
+            validate_name("admin_template"),
+            Err("name must consist of lowercase alphanumeric characters, '-' or '.'")
+        );
+        for hyphenated in ["-admin", "admin-", "admin-.template", "admin..template"] {
+            assert_eq!(
+                validate_name(hyphenated),
+                Err("name must start and end with an alphanumeric character, around every '.' too"),
+                "{}",
+                hyphenated
+            );
+        }
+    }
+}
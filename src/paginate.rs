@@ -0,0 +1,197 @@
+use std::future::Future;
+
+use async_stream::try_stream;
+use futures::Stream;
+use rancher_client::models::IoK8sApimachineryPkgApisMetaV1ListMeta;
+
+use crate::continue_token;
+
+/// The opaque continue token of a list page, pass it to the next call of the same list
+///
+/// Derefs to `str` so it can be passed where a raw `Option<&str>` token is expected.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ContinueToken(String);
+
+impl ContinueToken {
+    pub fn new(token: String) -> Self {
+        ContinueToken(token)
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    pub fn into_inner(self) -> String {
+        self.0
+    }
+}
+
+impl std::ops::Deref for ContinueToken {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl std::fmt::Display for ContinueToken {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// A single page of a list
+#[derive(Debug, Clone, PartialEq)]
+pub struct ListPage<T> {
+    pub items: Vec<T>,
+    /// The token to fetch the next page, `None` on the last page
+    pub continue_token: Option<ContinueToken>,
+    /// The number of items left after this page, when the server reports it (useful for progress)
+    pub remaining_item_count: Option<i64>,
+}
+
+impl<T> ListPage<T> {
+    /// Build a page from the items and metadata of a list
+    pub fn from_list(items: Vec<T>, metadata: Option<&IoK8sApimachineryPkgApisMetaV1ListMeta>) -> Self {
+        ListPage {
+            items,
+            continue_token: continue_token(metadata).map(ContinueToken::new),
+            remaining_item_count: metadata.and_then(|m| m.remaining_item_count),
+        }
+    }
+}
+
+/// Drives a paginated list call by following its continue token
+///
+/// `fetch` is called with `None` for the first page and with the continue token returned by the
+/// previous page afterwards, until a page returns no token.
+pub struct Paginator<F> {
+    fetch: F,
+}
+
+impl<F> Paginator<F> {
+    pub fn new(fetch: F) -> Self {
+        Paginator { fetch }
+    }
+}
+
+impl<F, Fut, T, E> Paginator<F>
+where
+    F: Fn(Option<String>) -> Fut,
+    Fut: Future<Output = Result<(Vec<T>, Option<String>), E>>,
+{
+    /// Fetch every page and return all the items
+    ///
+    /// # Errors
+    ///
+    /// * `E` - The first error returned by `fetch`
+    pub async fn collect_all(&self) -> Result<Vec<T>, E> {
+        let mut items = Vec::new();
+        let mut continue_: Option<String> = None;
+        loop {
+            let (page, next) = (self.fetch)(continue_).await?;
+            items.extend(page);
+            match next {
+                Some(token) => continue_ = Some(token),
+                None => return Ok(items),
+            }
+        }
+    }
+
+    /// Stream the items, fetching the next page when the current one is drained
+    ///
+    /// The stream ends after the first error returned by `fetch`.
+    pub fn into_stream(self) -> impl Stream<Item = Result<T, E>> {
+        try_stream! {
+            let mut continue_: Option<String> = None;
+            loop {
+                let (page, next) = (self.fetch)(continue_).await?;
+                for item in page {
+                    yield item;
+                }
+                match next {
+                    Some(token) => continue_ = Some(token),
+                    None => break,
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+
+    use futures::StreamExt;
+
+    async fn two_pages(continue_: Option<String>) -> Result<(Vec<u32>, Option<String>), String> {
+        match continue_.as_deref() {
+            None => Ok((vec![1, 2], Some("page-2".to_string()))),
+            Some("page-2") => Ok((vec![3], None)),
+            Some(other) => Err(format!("unexpected token {}", other)),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_collect_all_follows_continue_token() {
+        let calls = RefCell::new(Vec::new());
+        let paginator = Paginator::new(|continue_: Option<String>| {
+            calls.borrow_mut().push(continue_.clone());
+            two_pages(continue_)
+        });
+
+        let items = paginator.collect_all().await.unwrap();
+
+        assert_eq!(items, vec![1, 2, 3]);
+        assert_eq!(*calls.borrow(), vec![None, Some("page-2".to_string())]);
+    }
+
+    #[tokio::test]
+    async fn test_into_stream_yields_all_items() {
+        let items: Vec<Result<u32, String>> = Paginator::new(two_pages).into_stream().collect().await;
+
+        assert_eq!(items, vec![Ok(1), Ok(2), Ok(3)]);
+    }
+
+    #[test]
+    fn test_continue_token_derefs_to_str() {
+        let token = Some(ContinueToken::new("page-2".to_string()));
+
+        assert_eq!(token.as_deref(), Some("page-2"));
+        assert_eq!(token.unwrap().into_inner(), "page-2");
+    }
+
+    #[test]
+    fn test_list_page_from_list_metadata() {
+        let metadata = IoK8sApimachineryPkgApisMetaV1ListMeta {
+            r#continue: Some("page-2".to_string()),
+            remaining_item_count: Some(42),
+            ..Default::default()
+        };
+
+        let page = ListPage::from_list(vec![1, 2], Some(&metadata));
+
+        assert_eq!(page.continue_token.as_deref(), Some("page-2"));
+        assert_eq!(page.remaining_item_count, Some(42));
+
+        let last = ListPage::<u32>::from_list(Vec::new(), None);
+        assert!(last.continue_token.is_none());
+        assert!(last.remaining_item_count.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_error_stops_pagination() {
+        let paginator = Paginator::new(|continue_: Option<String>| async move {
+            match continue_ {
+                None => Ok((vec![1], Some("page-2".to_string()))),
+                Some(_) => Err("boom".to_string()),
+            }
+        });
+
+        assert_eq!(paginator.collect_all().await, Err("boom".to_string()));
+
+        let items: Vec<Result<u32, String>> = paginator.into_stream().collect().await;
+        assert_eq!(items, vec![Ok(1), Err("boom".to_string())]);
+    }
+}
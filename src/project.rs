@@ -11,9 +11,13 @@ use rancher_client::{
     apis::{
         configuration::Configuration,
         management_cattle_io_v3_api::{
+            create_management_cattle_io_v3_namespaced_project,
+            delete_management_cattle_io_v3_namespaced_project,
             list_management_cattle_io_v3_namespaced_project,
             patch_management_cattle_io_v3_namespaced_project,
             read_management_cattle_io_v3_namespaced_project,
+            CreateManagementCattleIoV3NamespacedProjectError,
+            DeleteManagementCattleIoV3NamespacedProjectError,
             ListManagementCattleIoV3NamespacedProjectError,
             PatchManagementCattleIoV3NamespacedProjectError,
             ReadManagementCattleIoV3NamespacedProjectError,
@@ -30,7 +34,12 @@ use rancher_client::{
     },
 };
 
-use crate::{diff_boxed_hashmap_string_string, ResourceVersionMatch};
+use crate::client::RequestOptions;
+use crate::metadata::{validate_metadata, validate_name};
+use crate::request::{is_write_success, written_object};
+use crate::{
+    checked_limit, diff_boxed_hashmap_string_string, ApiStatus, RancherError, ResourceVersionMatch,
+};
 
 pub const PROJECT_EXCLUDE_PATHS: &[&str] = &[
     "metadata.creationTimestamp",
@@ -69,6 +78,7 @@ pub async fn get_projects(
     continue_: Option<&str>,
 ) -> Result<IoCattleManagementv3ProjectList, Error<ListManagementCattleIoV3NamespacedProjectError>>
 {
+    let limit = checked_limit(limit, &RequestOptions::default())?;
     let result = list_management_cattle_io_v3_namespaced_project(
         configuration,
         cluster_id,
@@ -252,6 +262,146 @@ pub async fn update_project(
     }
 }
 
+/// Create a project in its cluster
+///
+/// The project is created in `namespace`, or in the namespace of its cluster when `namespace`
+/// is empty.
+///
+/// # Arguments
+///
+/// * `configuration` - The configuration to use for the request
+/// * `project` - The project to create
+///
+/// # Returns
+///
+/// * `Project` - The project as created by the server
+///
+/// # Errors
+///
+/// * `RancherError` - `Invalid` when the project has invalid labels or annotations, or the error that occurred while converting or creating the project
+///
+#[async_backtrace::framed]
+pub async fn create_project(
+    configuration: &Configuration,
+    project: &Project,
+) -> Result<Project, RancherError> {
+    validate_metadata(project.annotations.as_ref(), project.labels.as_ref())
+        .map_err(|e| RancherError::Invalid(e.to_string()))?;
+    let body = IoCattleManagementv3Project::try_from(project.clone()).map_err(RancherError::conversion)?;
+    let namespace = body
+        .metadata
+        .as_ref()
+        .and_then(|m| m.namespace.clone())
+        .unwrap_or_default();
+
+    let result = create_management_cattle_io_v3_namespaced_project(
+        configuration,
+        &namespace,
+        body,
+        None,
+        None,
+        None,
+        None,
+    )
+    .await;
+
+    match result {
+        Err(e) => Err(e.into()),
+        Ok(response_content) => {
+            // Match on the status code and deserialize accordingly
+            match response_content.status {
+                status if is_write_success(status) => {
+                    // Deserialize the created IoCattleManagementv3Project when the body holds it
+                    match written_object::<IoCattleManagementv3Project>(&response_content.content) {
+                        Ok(Some(data)) => Project::try_from(data).map_err(RancherError::conversion),
+                        // accepted without the object, it is created as sent
+                        Ok(None) => Ok(project.clone()),
+                        Err(deserialize_err) => Err(RancherError::Serde(deserialize_err)),
+                    }
+                }
+                _ => {
+                    // If not a success, treat as UnknownValue
+                    match serde_json::from_str::<serde_json::Value>(&response_content.content) {
+                        Ok(unknown_data) => Err(Error::ResponseError(ResponseContent {
+                            status: response_content.status,
+                            content: response_content.content,
+                            entity: Some(
+                                CreateManagementCattleIoV3NamespacedProjectError::UnknownValue(
+                                    unknown_data,
+                                ),
+                            ),
+                        })
+                        .into()),
+                        Err(unknown_deserialize_err) => Err(Error::Serde(unknown_deserialize_err).into()),
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Delete a project by its ID
+///
+/// Rancher removes the namespaces of the project from it, along with its role template bindings.
+///
+/// # Arguments
+///
+/// * `configuration` - The configuration to use for the request
+/// * `cluster_id` - The ID of the cluster (namespace) of the project
+/// * `project_id` - The ID of the project to delete
+///
+/// # Errors
+///
+/// * `RancherError` - `NotFound` when the project does not exist, `Forbidden` when the server refuses the deletion, or the error that occurred while deleting the project
+///
+#[async_backtrace::framed]
+pub async fn delete_project(
+    configuration: &Configuration,
+    cluster_id: &str,
+    project_id: &str,
+) -> Result<(), RancherError> {
+    let result = delete_management_cattle_io_v3_namespaced_project(
+        configuration,
+        project_id,
+        cluster_id,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+    )
+    .await;
+
+    match result {
+        Err(e) => Err(e.into()),
+        Ok(response_content) => {
+            // Match on the status code
+            match response_content.status {
+                status if is_write_success(status) => Ok(()),
+                StatusCode::NOT_FOUND => Err(RancherError::NotFound),
+                StatusCode::FORBIDDEN => Err(RancherError::Forbidden(ApiStatus::parse(&response_content.content))),
+                _ => {
+                    // Otherwise treat as UnknownValue
+                    match serde_json::from_str::<serde_json::Value>(&response_content.content) {
+                        Ok(unknown_data) => Err(Error::ResponseError(ResponseContent {
+                            status: response_content.status,
+                            content: response_content.content,
+                            entity: Some(
+                                DeleteManagementCattleIoV3NamespacedProjectError::UnknownValue(
+                                    unknown_data,
+                                ),
+                            ),
+                        })
+                        .into()),
+                        Err(unknown_deserialize_err) => Err(Error::Serde(unknown_deserialize_err).into()),
+                    }
+                }
+            }
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, SerdeDiff, Debug, Clone, PartialEq)]
 pub struct Project {
     /// Name of the Kubernetes cluster this project belongs to.
@@ -372,9 +522,17 @@ impl TryFrom<IoCattleManagementv3Project> for Project {
                 .collect::<std::collections::HashMap<String, String>>()
         });
 
+        // projects are namespaced by their cluster, so the namespace is the cluster id
+        let namespace = metadata.namespace.unwrap_or_default();
+        let cluster_name = if spec.cluster_name.is_empty() {
+            namespace.clone()
+        } else {
+            spec.cluster_name
+        };
+
         Ok(Project {
             annotations,
-            cluster_name: spec.cluster_name,
+            cluster_name,
             container_default_resource_limit,
             description: spec.description.unwrap_or_default(),
             display_name: spec.display_name,
@@ -382,7 +540,7 @@ impl TryFrom<IoCattleManagementv3Project> for Project {
             id: metadata.name.ok_or("missing metadata.name")?,
             labels,
             namespace_default_resource_quota,
-            namespace: metadata.namespace.unwrap_or_default(),
+            namespace,
             resource_quota: resource_quota_limit,
             resource_version: metadata.resource_version,
             uid: metadata.uid,
@@ -394,6 +552,14 @@ impl TryFrom<Project> for IoCattleManagementv3Project {
     type Error = &'static str;
 
     fn try_from(value: Project) -> Result<Self, Self::Error> {
+        validate_name(&value.id)?;
+        // the namespace of a project is its cluster id
+        let namespace = if value.namespace.is_empty() {
+            value.cluster_name.clone()
+        } else {
+            value.namespace.clone()
+        };
+
         // Construct metadata
         let metadata = IoK8sApimachineryPkgApisMetaV1ObjectMeta {
             name: Some(value.id.clone()),
@@ -405,7 +571,7 @@ impl TryFrom<Project> for IoCattleManagementv3Project {
                 a.into_iter()
                     .collect::<std::collections::HashMap<String, String>>()
             }),
-            namespace: Some(value.namespace.clone()),
+            namespace: Some(namespace),
             resource_version: value.resource_version.clone(),
             uid: value.uid.clone(),
             ..Default::default()
@@ -485,6 +651,7 @@ impl PartialEq<Project> for IoCattleManagementv3Project {
         });
 
         metadata.name.as_deref() == Some(&other.id)
+            && metadata.namespace.as_deref().unwrap_or_default() == other.namespace
             && spec.cluster_name == other.cluster_name
             && spec.description.as_deref().unwrap_or_default() == other.description
             && spec.display_name == other.display_name
@@ -630,4 +797,68 @@ mod tests {
         assert_ne!(rancher_project, project);
         assert_ne!(project, rancher_project);
     }
+
+    #[test]
+    fn test_round_trip_keeps_cluster_name_and_namespace() {
+        let project = sample_project();
+        let rancher_project = IoCattleManagementv3Project::try_from(project.clone()).unwrap();
+        let back = Project::try_from(rancher_project).unwrap();
+
+        assert_eq!(back, project);
+        assert_eq!(back.cluster_name, back.namespace);
+    }
+
+    #[test]
+    fn test_cluster_name_defaults_to_namespace() {
+        let mut rancher_project = sample_iocattle_project();
+        rancher_project.spec.as_mut().unwrap().cluster_name = String::new();
+
+        let project = Project::try_from(rancher_project).unwrap();
+        assert_eq!(project.cluster_name, "cluster-1");
+    }
+
+    #[test]
+    fn test_inequality_on_namespace() {
+        let mut project = sample_project();
+        let rancher_project = sample_iocattle_project();
+
+        project.namespace = "cluster-2".to_string();
+
+        assert_ne!(rancher_project, project);
+        assert_ne!(project, rancher_project);
+    }
+
+    #[tokio::test]
+    async fn test_create_project_in_cluster_namespace() {
+        let rancher = crate::test_support::MockRancher::start().await;
+        rancher
+            .expect("POST", "/apis/management.cattle.io/v3/namespaces/cluster-1/projects")
+            .with_body_partial(serde_json::json!({
+                "metadata": { "name": "proj-1", "namespace": "cluster-1" },
+                "spec": { "displayName": "Project One" }
+            }))
+            .expect_calls(1)
+            .returning_json(201, serde_json::to_value(sample_iocattle_project()).unwrap())
+            .await;
+
+        let mut project = sample_project();
+        project.namespace = String::new();
+        let created = create_project(&rancher.configuration(), &project).await.unwrap();
+
+        assert_eq!(created, sample_project());
+    }
+
+    #[tokio::test]
+    async fn test_delete_project() {
+        let rancher = crate::test_support::MockRancher::start().await;
+        rancher
+            .expect("DELETE", "/apis/management.cattle.io/v3/namespaces/cluster-1/projects/proj-1")
+            .expect_calls(1)
+            .returning_json(200, serde_json::json!({ "kind": "Status", "status": "Success" }))
+            .await;
+
+        delete_project(&rancher.configuration(), "cluster-1", "proj-1")
+            .await
+            .unwrap();
+    }
 }
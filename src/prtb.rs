@@ -1,3 +1,5 @@
+use futures::{Stream, StreamExt};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 use rancher_client::apis::{configuration::Configuration, Error, ResponseContent};
@@ -5,10 +7,18 @@ use reqwest::StatusCode;
 
 use rancher_client::{
     apis::management_cattle_io_v3_api::{
+        create_management_cattle_io_v3_namespaced_project_role_template_binding,
+        delete_management_cattle_io_v3_namespaced_project_role_template_binding,
         list_management_cattle_io_v3_namespaced_project_role_template_binding,
         list_management_cattle_io_v3_project_role_template_binding_for_all_namespaces,
+        read_management_cattle_io_v3_namespaced_project_role_template_binding,
+        replace_management_cattle_io_v3_namespaced_project_role_template_binding,
+        CreateManagementCattleIoV3NamespacedProjectRoleTemplateBindingError,
+        DeleteManagementCattleIoV3NamespacedProjectRoleTemplateBindingError,
         ListManagementCattleIoV3NamespacedProjectRoleTemplateBindingError,
         ListManagementCattleIoV3ProjectRoleTemplateBindingForAllNamespacesError,
+        ReadManagementCattleIoV3NamespacedProjectRoleTemplateBindingError,
+        ReplaceManagementCattleIoV3NamespacedProjectRoleTemplateBindingError,
     },
     models::{
         IoCattleManagementv3ProjectRoleTemplateBinding,
@@ -17,6 +27,29 @@ use rancher_client::{
     },
 };
 
+use crate::diff::{hash_value, json_patch_between, prune_paths, DiffReport};
+use crate::error::{WithResource, WithResourceExt};
+use crate::metadata::{
+    exportable_metadata, format_content_hash, strip_for_content_hash, user_metadata_eq,
+    validate_metadata, validate_name, CONTENT_HASH_ANNOTATION,
+};
+use crate::paginate::{ContinueToken, ListPage, Paginator};
+use crate::request::{
+    get_list, is_write_success, list_query, optional_object, parse_response, send_request,
+    written_object, MERGE_PATCH_CONTENT_TYPE,
+};
+use crate::client::RequestOptions;
+use crate::retry::{
+    retry_with_backoff, RetryPolicy, DEFAULT_BACKOFF_ATTEMPTS, DEFAULT_BACKOFF_BASE_DELAY,
+};
+use crate::rt::{check_context, BindingScope, RoleTemplate};
+use crate::{
+    checked_limit, continue_token, merge_patch_touches, ApiStatus, RancherError, ResourceKey, ResourceKind,
+    DEFAULT_PAGE_SIZE,
+};
+
+/// The API path of the binding list across all namespaces
+const PRTB_ALL_NAMESPACES_PATH: &str = "/apis/management.cattle.io/v3/projectroletemplatebindings";
 
 pub const PRTB_EXCLUDE_PATHS: &[&str] = &[
     "metadata.creationTimestamp",
@@ -55,11 +88,83 @@ pub async fn get_project_role_template_bindings(
 ) -> Result<
     IoCattleManagementv3ProjectRoleTemplateBindingList,
     Error<ListManagementCattleIoV3ProjectRoleTemplateBindingForAllNamespacesError>,
+> {
+    let limit = checked_limit(limit, &RequestOptions::default())?;
+    let continue_ = continue_.map(|token| ContinueToken::new(token.to_string()));
+    list_project_role_template_bindings(
+        configuration,
+        field_selector,
+        label_selector,
+        limit,
+        resource_version,
+        resource_version_match,
+        continue_.as_ref(),
+    )
+    .await
+    .map(|(list, _)| list)
+}
+
+/// Get a page of project role template bindings along with the token of the next page
+///
+/// # Arguments
+///
+/// * `configuration` - The configuration to use for the request
+/// * `continue_` - The token returned with the previous page, `None` for the first page
+/// * `options` - The options of the request, `limit` is clamped to their largest page size
+///
+/// # Returns
+///
+/// * `IoCattleManagementv3ProjectRoleTemplateBindingList` - The list of project role template bindings
+/// * `Option<ContinueToken>` - The token to fetch the next page, `None` on the last page
+///
+/// # Errors
+///
+/// * `RancherError` - `Invalid` when the limit is zero or negative, or the error that occurred while trying to get the bindings
+///
+#[async_backtrace::framed]
+pub async fn get_project_role_template_bindings_with_token(
+    configuration: &Configuration,
+    field_selector: Option<&str>,
+    label_selector: Option<&str>,
+    limit: Option<i32>,
+    resource_version: Option<&str>,
+    resource_version_match: Option<&str>,
+    continue_: Option<&ContinueToken>,
+    options: &RequestOptions,
+) -> Result<(IoCattleManagementv3ProjectRoleTemplateBindingList, Option<ContinueToken>), RancherError> {
+    let limit = checked_limit(limit, options)?;
+    list_project_role_template_bindings(
+        configuration,
+        field_selector,
+        label_selector,
+        limit,
+        resource_version,
+        resource_version_match,
+        continue_,
+    )
+    .await
+    .map_err(RancherError::from)
+}
+
+/// List project role template bindings with an already checked limit, returning the token of the
+/// next page
+#[async_backtrace::framed]
+async fn list_project_role_template_bindings(
+    configuration: &Configuration,
+    field_selector: Option<&str>,
+    label_selector: Option<&str>,
+    limit: Option<i32>,
+    resource_version: Option<&str>,
+    resource_version_match: Option<&str>,
+    continue_: Option<&ContinueToken>,
+) -> Result<
+    (IoCattleManagementv3ProjectRoleTemplateBindingList, Option<ContinueToken>),
+    Error<ListManagementCattleIoV3ProjectRoleTemplateBindingForAllNamespacesError>,
 > {
     let result = list_management_cattle_io_v3_project_role_template_binding_for_all_namespaces(
         configuration,
         None,
-        continue_,
+        continue_.map(ContinueToken::as_str),
         field_selector,
         label_selector,
         limit,
@@ -81,8 +186,11 @@ pub async fn get_project_role_template_bindings(
             match response_content.status {
                 StatusCode::OK => {
                     // Try to deserialize the content into IoCattleManagementv3ProjectRoleTemplateBindingList (Status200 case)
-                    match serde_json::from_str(&response_content.content) {
-                        Ok(data) => Ok(data),
+                    match serde_json::from_str::<IoCattleManagementv3ProjectRoleTemplateBindingList>(&response_content.content) {
+                        Ok(data) => {
+                            let token = continue_token(data.metadata.as_deref()).map(ContinueToken::new);
+                            Ok((data, token))
+                        }
                         Err(deserialize_err) => Err(Error::Serde(deserialize_err)),
                     }
                 }
@@ -107,6 +215,141 @@ pub async fn get_project_role_template_bindings(
     }
 }
 
+/// Get a single page of project role template bindings across all namespaces
+///
+/// # Arguments
+///
+/// * `configuration` - The configuration to use for the request
+/// * `field_selector` - A selector to restrict the bindings by their fields
+/// * `label_selector` - A selector to restrict the bindings by their labels
+/// * `limit` - The maximum number of bindings to return
+/// * `continue_` - The continue token of the previous page
+///
+/// # Returns
+///
+/// * `ListPage<IoCattleManagementv3ProjectRoleTemplateBinding>` - The bindings, continue token and remaining item count
+///
+/// # Errors
+///
+/// * `Error<ListManagementCattleIoV3ProjectRoleTemplateBindingForAllNamespacesError>` - The error that occurred while trying to get the bindings
+///
+#[async_backtrace::framed]
+pub async fn get_project_role_template_bindings_page(
+    configuration: &Configuration,
+    field_selector: Option<&str>,
+    label_selector: Option<&str>,
+    limit: Option<i32>,
+    continue_: Option<&str>,
+) -> Result<
+    ListPage<IoCattleManagementv3ProjectRoleTemplateBinding>,
+    Error<ListManagementCattleIoV3ProjectRoleTemplateBindingForAllNamespacesError>,
+> {
+    let list = get_project_role_template_bindings(
+        configuration,
+        field_selector,
+        label_selector,
+        limit,
+        None,
+        None,
+        continue_,
+    )
+    .await?;
+    Ok(ListPage::from_list(list.items, list.metadata.as_deref()))
+}
+
+/// Get every project role template binding across all namespaces, following the continue token
+///
+/// A page rate limited by the server is retried with the default `RetryPolicy`, one failing
+/// transiently with an exponential backoff, and every request times out after the timeout of
+/// `options`. Pages are `DEFAULT_PAGE_SIZE` items clamped to the largest page size of `options`.
+///
+/// # Arguments
+///
+/// * `configuration` - The configuration to use for the requests
+/// * `field_selector` - A selector to restrict the bindings by their fields
+/// * `label_selector` - A selector to restrict the bindings by their labels
+/// * `options` - The timeout and largest page size of the requests
+///
+/// # Returns
+///
+/// * `Vec<ProjectRoleTemplateBinding>` - The bindings of every page
+///
+/// # Errors
+///
+/// * `RancherError` - The first error that occurred while fetching a page or converting a binding
+///
+#[async_backtrace::framed]
+pub async fn get_all_project_role_template_bindings(
+    configuration: &Configuration,
+    field_selector: Option<&str>,
+    label_selector: Option<&str>,
+    options: &RequestOptions,
+) -> Result<Vec<ProjectRoleTemplateBinding>, RancherError> {
+    let limit = checked_limit(Some(DEFAULT_PAGE_SIZE), options)?;
+    let items = Paginator::new(move |continue_: Option<String>| async move {
+        let query = list_query(field_selector, label_selector, limit, continue_.as_deref());
+        let list: IoCattleManagementv3ProjectRoleTemplateBindingList = get_list(
+            configuration,
+            PRTB_ALL_NAMESPACES_PATH,
+            &query,
+            &RetryPolicy::default(),
+            options,
+        )
+        .await?;
+        Ok::<_, RancherError>((list.items, continue_token(list.metadata.as_deref())))
+    })
+    .collect_all()
+    .await?;
+
+    items
+        .into_iter()
+        .map(|item| {
+            ProjectRoleTemplateBinding::try_from(item).map_err(|e| RancherError::Invalid(e.to_string()))
+        })
+        .collect()
+}
+
+/// Stream every project role template binding across all namespaces, fetching the next page when
+/// the current one is drained
+///
+/// Only one page is held in memory at a time, pages are `DEFAULT_PAGE_SIZE` items clamped to the
+/// largest page size of `options`.
+///
+/// # Arguments
+///
+/// * `configuration` - The configuration to use for the requests
+/// * `options` - The timeout and largest page size of the requests
+///
+/// # Returns
+///
+/// * `impl Stream<Item = Result<ProjectRoleTemplateBinding, RancherError>>` - The bindings, the stream ends after the first failed request
+pub fn stream_project_role_template_bindings<'a>(
+    configuration: &'a Configuration,
+    options: &'a RequestOptions,
+) -> impl Stream<Item = Result<ProjectRoleTemplateBinding, RancherError>> + 'a {
+    Paginator::new(move |continue_: Option<String>| async move {
+        let continue_ = continue_.map(ContinueToken::new);
+        get_project_role_template_bindings_with_token(
+            configuration,
+            None,
+            None,
+            Some(DEFAULT_PAGE_SIZE),
+            None,
+            None,
+            continue_.as_ref(),
+            options,
+        )
+        .await
+        .map(|(page, next)| (page.items, next.map(ContinueToken::into_inner)))
+    })
+    .into_stream()
+    .map(|item| {
+        item.and_then(|item| {
+            ProjectRoleTemplateBinding::try_from(item).map_err(|e| RancherError::Invalid(e.to_string()))
+        })
+    })
+}
+
 /// Get all project role template bindings from a namespace using the provided configuration
 ///
 /// # Arguments
@@ -135,6 +378,7 @@ pub async fn get_namespaced_project_role_template_bindings(
     IoCattleManagementv3ProjectRoleTemplateBindingList,
     Error<ListManagementCattleIoV3NamespacedProjectRoleTemplateBindingError>,
 > {
+    let limit = checked_limit(limit, &RequestOptions::default())?;
     let result = list_management_cattle_io_v3_namespaced_project_role_template_binding(
         configuration,
         project_id,
@@ -187,92 +431,706 @@ pub async fn get_namespaced_project_role_template_bindings(
     }
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
-pub struct ProjectRoleTemplateBinding {
-    // annotations: Option<std::collections::HashMap<String, String>>,
-    /// Annotations applied to the project role template binding.
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub annotations: Option<std::collections::HashMap<String, String>>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub group_name: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub group_principal_name: Option<String>,
-    /// The name of the project role template binding (typically the Kubernetes metadata.name).
-    pub id: String,
-
-    /// Labels applied to the project role template binding
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub labels: Option<std::collections::HashMap<String, String>>,
-
-    /// the project (namespace) the project role template exists in
-    pub namespace: String,
-
-    /// The name of the project the project role template is bound to (cluster-id:project-id)
-    pub project_name: String,
-
-    pub role_template_name: String,
-
-    /// An opaque value that represents the internal version of this object that can be used by clients to determine when objects have changed. May be used for optimistic concurrency, change detection, and the watch operation on a resource or set of resources. Clients must treat these values as opaque and passed unmodified back to the server. They may only be valid for a particular resource or set of resources.  Populated by the system. Read-only. Value must be treated as opaque by clients and . More info: https://git.k8s.io/community/contributors/devel/sig-architecture/api-conventions.md#concurrency-control-and-consistency
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub resource_version: Option<String>,
-
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub service_account: Option<String>,
-
-    /// The UID of the project. This cannot be changed. Rancher will set this value when the project is created.
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub uid: Option<String>,
+/// Get a project role template binding by name
+///
+/// # Arguments
+///
+/// * `configuration` - The configuration to use for the request
+/// * `namespace` - The namespace (project id) of the binding
+/// * `name` - The name of the binding
+///
+/// # Returns
+///
+/// * `Option<ProjectRoleTemplateBinding>` - The binding, or `None` when it does not exist
+///
+/// # Errors
+///
+/// * `RancherError` - The error that occurred while reading or converting the binding
+///
+#[async_backtrace::framed]
+pub async fn get_project_role_template_binding(
+    configuration: &Configuration,
+    namespace: &str,
+    name: &str,
+) -> Result<Option<ProjectRoleTemplateBinding>, RancherError> {
+    let result = read_management_cattle_io_v3_namespaced_project_role_template_binding(
+        configuration,
+        name,
+        namespace,
+        None,
+        None,
+    )
+    .await;
+    optional_object::<IoCattleManagementv3ProjectRoleTemplateBinding, _>(
+        result,
+        ReadManagementCattleIoV3NamespacedProjectRoleTemplateBindingError::UnknownValue,
+    )?
+    .map(ProjectRoleTemplateBinding::try_from)
+    .transpose()
+    .map_err(RancherError::conversion)
+}
 
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub user_name: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub user_principal_name: Option<String>,
+/// Create a project role template binding
+///
+/// The binding is created in its resolved namespace (the project id), see
+/// `ProjectRoleTemplateBinding::resolved_namespace`.
+///
+/// # Arguments
+///
+/// * `configuration` - The configuration to use for the request
+/// * `binding` - The project role template binding to create
+///
+/// # Returns
+///
+/// * `ProjectRoleTemplateBinding` - The binding as created by the server
+///
+/// # Errors
+///
+/// * `WithResource<RancherError>` - The binding the error is about, along with the error: `Invalid` when the binding has invalid labels or annotations, or the error that occurred while converting or creating the binding
+///
+#[async_backtrace::framed]
+pub async fn create_project_role_template_binding(
+    configuration: &Configuration,
+    binding: &ProjectRoleTemplateBinding,
+) -> Result<ProjectRoleTemplateBinding, WithResource<RancherError>> {
+    send_create_project_role_template_binding(configuration, binding)
+        .await
+        .with_resource(
+            ResourceKind::ProjectRoleTemplateBinding,
+            Some(&binding.resolved_namespace()),
+            &binding.id,
+        )
 }
 
-impl ProjectRoleTemplateBinding {
-    pub fn new(
-        annotations: Option<std::collections::HashMap<String, String>>,
-        group_name: Option<String>,
-        group_principal_name: Option<String>,
-        id: String,
-        labels: Option<std::collections::HashMap<String, String>>,
-        namespace: String,
-        project_name: String,
-        uid: Option<String>,
-        role_template_name: String,
-        resource_version: Option<String>,
-        service_account: Option<String>,
-        user_name: Option<String>,
-        user_principal_name: Option<String>,
-    ) -> Self {
-        ProjectRoleTemplateBinding {
-            annotations,
-            group_name,
-            group_principal_name,
-            id,
-            labels,
-            namespace,
-            project_name,
-            role_template_name,
-            resource_version,
-            service_account,
-            uid,
-            user_name,
-            user_principal_name,
+/// Send the request of `create_project_role_template_binding`, the error is wrapped with the binding by the caller
+async fn send_create_project_role_template_binding(
+    configuration: &Configuration,
+    binding: &ProjectRoleTemplateBinding,
+) -> Result<ProjectRoleTemplateBinding, RancherError> {
+    validate_metadata(binding.annotations.as_ref(), binding.labels.as_ref())
+        .map_err(|e| RancherError::Invalid(e.to_string()))?;
+    let namespace = binding.resolved_namespace();
+    let body = IoCattleManagementv3ProjectRoleTemplateBinding::try_from(binding.clone())
+        .map_err(RancherError::conversion)?;
+
+    let result = create_management_cattle_io_v3_namespaced_project_role_template_binding(
+        configuration,
+        &namespace,
+        body,
+        None,
+        None,
+        None,
+        None,
+    )
+    .await;
+    match result {
+        Err(e) => Err(e.into()),
+        Ok(response_content) => {
+            // Match on the status code and deserialize accordingly
+            match response_content.status {
+                status if is_write_success(status) => {
+                    // Deserialize the created IoCattleManagementv3ProjectRoleTemplateBinding when the body holds it
+                    match written_object::<IoCattleManagementv3ProjectRoleTemplateBinding>(&response_content.content) {
+                        Ok(Some(data)) => ProjectRoleTemplateBinding::try_from(data).map_err(RancherError::conversion),
+                        // accepted without the object, it is created as sent
+                        Ok(None) => Ok(binding.clone()),
+                        Err(deserialize_err) => Err(RancherError::Serde(deserialize_err)),
+                    }
+                }
+                _ => {
+                    // If not a success, treat as UnknownValue
+                    match serde_json::from_str::<serde_json::Value>(&response_content.content) {
+                        Ok(unknown_data) => {
+                            // Handle the unknown response
+                            Err(Error::ResponseError(ResponseContent {
+                                status: response_content.status,
+                                content: response_content.content,
+                                entity: Some(CreateManagementCattleIoV3NamespacedProjectRoleTemplateBindingError::UnknownValue(
+                                    unknown_data,
+                                )),
+                            })
+                            .into())
+                        }
+                        Err(deserialize_err) => Err(RancherError::Serde(deserialize_err)),
+                    }
+                }
+            }
         }
     }
 }
 
-impl TryFrom<IoCattleManagementv3ProjectRoleTemplateBinding> for ProjectRoleTemplateBinding {
-    type Error = &'static str;
-
-    fn try_from(
-        value: IoCattleManagementv3ProjectRoleTemplateBinding,
-    ) -> Result<Self, Self::Error> {
-        let metadata: IoK8sApimachineryPkgApisMetaV1ObjectMeta =
-            value.metadata.ok_or("missing metadata")?;
-
-        let id = metadata.name.ok_or("missing name")?;
+/// Replace an existing project role template binding
+///
+/// The binding must carry the resource version it was read at, the server rejects the update
+/// if the object changed since.
+///
+/// # Arguments
+///
+/// * `configuration` - The configuration to use for the request
+/// * `binding` - The desired project role template binding, `id` and `namespace` name the object to replace
+///
+/// # Returns
+///
+/// * `ProjectRoleTemplateBinding` - The binding as updated by the server
+///
+/// # Errors
+///
+/// * `WithResource<RancherError>` - The binding the error is about, along with the error: `Invalid` when `resource_version` is `None` or the binding has invalid labels or annotations, `Conflict` when the binding changed on the server, or the error that occurred while converting or replacing the binding
+///
+#[async_backtrace::framed]
+pub async fn replace_project_role_template_binding(
+    configuration: &Configuration,
+    binding: &ProjectRoleTemplateBinding,
+) -> Result<ProjectRoleTemplateBinding, WithResource<RancherError>> {
+    send_replace_project_role_template_binding(configuration, binding)
+        .await
+        .with_resource(
+            ResourceKind::ProjectRoleTemplateBinding,
+            Some(&binding.resolved_namespace()),
+            &binding.id,
+        )
+}
+
+/// The body replacing a binding at its resource version, checked like
+/// `replace_project_role_template_binding` does
+///
+/// # Errors
+///
+/// * `RancherError` - `Invalid` when `resource_version` is `None` or the binding has invalid labels or annotations, or the error that occurred while converting the binding
+pub(crate) fn binding_replace_body(
+    binding: &ProjectRoleTemplateBinding,
+) -> Result<IoCattleManagementv3ProjectRoleTemplateBinding, RancherError> {
+    let resource_version = binding
+        .resource_version
+        .clone()
+        .ok_or_else(RancherError::missing_resource_version)?;
+    validate_metadata(binding.annotations.as_ref(), binding.labels.as_ref())
+        .map_err(|e| RancherError::Invalid(e.to_string()))?;
+    let mut body = IoCattleManagementv3ProjectRoleTemplateBinding::try_from(binding.clone())
+        .map_err(RancherError::conversion)?;
+    if let Some(metadata) = body.metadata.as_mut() {
+        metadata.resource_version = Some(resource_version);
+    }
+    Ok(body)
+}
+
+/// Send the request of `replace_project_role_template_binding`, the error is wrapped with the binding by the caller
+async fn send_replace_project_role_template_binding(
+    configuration: &Configuration,
+    binding: &ProjectRoleTemplateBinding,
+) -> Result<ProjectRoleTemplateBinding, RancherError> {
+    let body = binding_replace_body(binding)?;
+    let namespace = binding.resolved_namespace();
+
+    let result = replace_management_cattle_io_v3_namespaced_project_role_template_binding(
+        configuration,
+        &binding.id,
+        &namespace,
+        body,
+        None,
+        None,
+        None,
+        None,
+    )
+    .await;
+    match result {
+        Err(e) => Err(e.into()),
+        Ok(response_content) => {
+            // Match on the status code and deserialize accordingly
+            match response_content.status {
+                status if is_write_success(status) => {
+                    // Deserialize the updated IoCattleManagementv3ProjectRoleTemplateBinding when the body holds it
+                    match written_object::<IoCattleManagementv3ProjectRoleTemplateBinding>(&response_content.content) {
+                        Ok(Some(data)) => ProjectRoleTemplateBinding::try_from(data).map_err(RancherError::conversion),
+                        // accepted without the object, it is updated as sent
+                        Ok(None) => Ok(binding.clone()),
+                        Err(deserialize_err) => Err(RancherError::Serde(deserialize_err)),
+                    }
+                }
+                StatusCode::CONFLICT => {
+                    Err(RancherError::from_response(StatusCode::CONFLICT, &response_content.content))
+                }
+                _ => {
+                    // If not a success, treat as UnknownValue
+                    match serde_json::from_str::<serde_json::Value>(&response_content.content) {
+                        Ok(unknown_data) => {
+                            // Handle the unknown response
+                            Err(Error::ResponseError(ResponseContent {
+                                status: response_content.status,
+                                content: response_content.content,
+                                entity: Some(ReplaceManagementCattleIoV3NamespacedProjectRoleTemplateBindingError::UnknownValue(
+                                    unknown_data,
+                                )),
+                            })
+                            .into())
+                        }
+                        Err(deserialize_err) => Err(RancherError::Serde(deserialize_err)),
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Apply a JSON merge patch (RFC 7386) to a project role template binding
+///
+/// The patch is rejected before sending when it touches any of `PRTB_EXCLUDE_PATHS`.
+///
+/// # Arguments
+///
+/// * `configuration` - The configuration to use for the request
+/// * `namespace` - The namespace (project id) of the binding
+/// * `name` - The name of the binding
+/// * `patch` - The merge patch, e.g. `{"groupPrincipalName": "local://g-abc"}`
+///
+/// # Returns
+///
+/// * `ProjectRoleTemplateBinding` - The binding as patched by the server
+///
+/// # Errors
+///
+/// * `WithResource<RancherError>` - The binding the error is about, along with the error: `Invalid` when the patch touches a server managed field, or the error that occurred while patching the binding
+///
+#[async_backtrace::framed]
+pub async fn patch_project_role_template_binding(
+    configuration: &Configuration,
+    namespace: &str,
+    name: &str,
+    patch: serde_json::Value,
+) -> Result<ProjectRoleTemplateBinding, WithResource<RancherError>> {
+    send_patch_project_role_template_binding(configuration, namespace, name, patch)
+        .await
+        .with_resource(ResourceKind::ProjectRoleTemplateBinding, Some(namespace), name)
+}
+
+/// Send the request of `patch_project_role_template_binding`, the error is wrapped with the binding by the caller
+async fn send_patch_project_role_template_binding(
+    configuration: &Configuration,
+    namespace: &str,
+    name: &str,
+    patch: serde_json::Value,
+) -> Result<ProjectRoleTemplateBinding, RancherError> {
+    if let Some(path) = PRTB_EXCLUDE_PATHS
+        .iter()
+        .find(|path| merge_patch_touches(&patch, path))
+    {
+        return Err(RancherError::immutable_field(path));
+    }
+
+    let path = ResourceKind::ProjectRoleTemplateBinding.api_path(Some(namespace), name);
+    let response_content = send_request(
+        configuration,
+        reqwest::Method::PATCH,
+        &path,
+        MERGE_PATCH_CONTENT_TYPE,
+        Some(&patch),
+    )
+    .await?;
+    let patched: IoCattleManagementv3ProjectRoleTemplateBinding =
+        serde_json::from_value(parse_response(response_content)?).map_err(RancherError::Serde)?;
+    ProjectRoleTemplateBinding::try_from(patched).map_err(RancherError::conversion)
+}
+
+/// Delete a project role template binding
+///
+/// # Arguments
+///
+/// * `configuration` - The configuration to use for the request
+/// * `namespace` - The namespace (project id) of the binding
+/// * `name` - The name of the binding to delete
+///
+/// # Errors
+///
+/// * `WithResource<RancherError>` - The binding the error is about, along with the error: `NotFound` when the binding does not exist, so callers can treat it as already deleted, `Forbidden` when the server refuses the deletion, or the error that occurred while deleting the binding
+///
+#[async_backtrace::framed]
+pub async fn delete_project_role_template_binding(
+    configuration: &Configuration,
+    namespace: &str,
+    name: &str,
+) -> Result<(), WithResource<RancherError>> {
+    send_delete_project_role_template_binding(configuration, namespace, name)
+        .await
+        .with_resource(ResourceKind::ProjectRoleTemplateBinding, Some(namespace), name)
+}
+
+/// Send the request of `delete_project_role_template_binding`, the error is wrapped with the binding by the caller
+async fn send_delete_project_role_template_binding(
+    configuration: &Configuration,
+    namespace: &str,
+    name: &str,
+) -> Result<(), RancherError> {
+    let result = delete_management_cattle_io_v3_namespaced_project_role_template_binding(
+        configuration,
+        name,
+        namespace,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+    )
+    .await;
+    match result {
+        Err(e) => Err(e.into()),
+        Ok(response_content) => {
+            // Match on the status code
+            match response_content.status {
+                status if is_write_success(status) => Ok(()),
+                StatusCode::NOT_FOUND => Err(RancherError::NotFound),
+                StatusCode::FORBIDDEN => Err(RancherError::Forbidden(ApiStatus::parse(&response_content.content))),
+                _ => {
+                    // Otherwise treat as UnknownValue
+                    match serde_json::from_str::<serde_json::Value>(&response_content.content) {
+                        Ok(unknown_data) => {
+                            // Handle the unknown response
+                            Err(Error::ResponseError(ResponseContent {
+                                status: response_content.status,
+                                content: response_content.content,
+                                entity: Some(DeleteManagementCattleIoV3NamespacedProjectRoleTemplateBindingError::UnknownValue(
+                                    unknown_data,
+                                )),
+                            })
+                            .into())
+                        }
+                        Err(deserialize_err) => Err(RancherError::Serde(deserialize_err)),
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Get every project role template binding of a project namespace, following the continue token
+///
+/// Items that fail to convert are returned with their error rather than failing the whole fetch. A page
+/// failing transiently is retried with an exponential backoff. Pages are `DEFAULT_PAGE_SIZE` items
+/// clamped to the largest page size of `options`.
+///
+/// # Arguments
+///
+/// * `configuration` - The configuration to use for the requests
+/// * `project_id` - The ID of the project (namespace) to get the bindings for
+/// * `field_selector` - A selector to restrict the bindings by their fields
+/// * `label_selector` - A selector to restrict the bindings by their labels
+/// * `options` - The largest page size of the requests
+///
+/// # Returns
+///
+/// * `Vec<ProjectRoleTemplateBinding>` - The bindings of every page that could be converted
+/// * `Vec<(Option<String>, &'static str)>` - The name of every skipped item with its conversion error
+///
+/// # Errors
+///
+/// * `Error<ListManagementCattleIoV3NamespacedProjectRoleTemplateBindingError>` - The first error that occurred while fetching a page
+///
+#[async_backtrace::framed]
+pub async fn get_all_namespaced_project_role_template_bindings(
+    configuration: &Configuration,
+    project_id: &str,
+    field_selector: Option<&str>,
+    label_selector: Option<&str>,
+    options: &RequestOptions,
+) -> Result<
+    (Vec<ProjectRoleTemplateBinding>, Vec<(Option<String>, &'static str)>),
+    Error<ListManagementCattleIoV3NamespacedProjectRoleTemplateBindingError>,
+> {
+    let limit = checked_limit(Some(DEFAULT_PAGE_SIZE), options)?;
+    let items = Paginator::new(move |continue_: Option<String>| async move {
+        retry_with_backoff(DEFAULT_BACKOFF_ATTEMPTS, DEFAULT_BACKOFF_BASE_DELAY, || {
+            get_namespaced_project_role_template_bindings(
+                configuration,
+                project_id,
+                field_selector,
+                label_selector,
+                limit,
+                None,
+                None,
+                continue_.as_deref(),
+            )
+        })
+        .await
+        .map(|page| (page.items, continue_token(page.metadata.as_deref())))
+    })
+    .collect_all()
+    .await?;
+
+    let mut bindings = Vec::new();
+    let mut skipped = Vec::new();
+    for item in items {
+        let name = item.metadata.as_ref().and_then(|m| m.name.clone());
+        match ProjectRoleTemplateBinding::try_from(item) {
+            Ok(binding) => bindings.push(binding),
+            Err(e) => skipped.push((name, e)),
+        }
+    }
+    Ok((bindings, skipped))
+}
+
+/// Get the project role template bindings of several project namespaces concurrently
+///
+/// Every namespace is fetched with `get_all_namespaced_project_role_template_bindings`, at most
+/// `concurrency` at a time. A failing namespace does not stop the others.
+///
+/// # Arguments
+///
+/// * `configuration` - The configuration to use for the requests
+/// * `project_ids` - The IDs of the projects (namespaces) to get the bindings for
+/// * `concurrency` - The maximum number of namespaces fetched at the same time, at least 1
+/// * `options` - The largest page size of the requests
+///
+/// # Returns
+///
+/// * `Vec<ProjectRoleTemplateBinding>` - The bindings of every namespace that was fetched, in no particular order
+/// * `Vec<(String, Error<ListManagementCattleIoV3NamespacedProjectRoleTemplateBindingError>)>` - The namespaces that failed with their error
+#[async_backtrace::framed]
+pub async fn get_project_role_template_bindings_for_namespaces(
+    configuration: &Configuration,
+    project_ids: &[&str],
+    concurrency: usize,
+    options: &RequestOptions,
+) -> (
+    Vec<ProjectRoleTemplateBinding>,
+    Vec<(String, Error<ListManagementCattleIoV3NamespacedProjectRoleTemplateBindingError>)>,
+) {
+    let results: Vec<_> = futures::stream::iter(project_ids.iter().map(|project_id| async move {
+        let result =
+            get_all_namespaced_project_role_template_bindings(configuration, project_id, None, None, options)
+                .await;
+        (project_id.to_string(), result)
+    }))
+    .buffer_unordered(concurrency.max(1))
+    .collect()
+    .await;
+
+    let mut bindings = Vec::new();
+    let mut errors = Vec::new();
+    for (project_id, result) in results {
+        match result {
+            Ok((namespace_bindings, _)) => bindings.extend(namespace_bindings),
+            Err(e) => errors.push((project_id, e)),
+        }
+    }
+    (bindings, errors)
+}
+
+/// A binding of a role template to a subject on a project
+///
+/// `Default` leaves every subject unset and `id`, `namespace`, `project_name` and
+/// `role_template_name` empty, for use with struct update syntax. `id`, `project_name`,
+/// `role_template_name` and one subject must be set before the binding is sent to Rancher.
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq, Default)]
+pub struct ProjectRoleTemplateBinding {
+    // annotations: Option<std::collections::HashMap<String, String>>,
+    /// Annotations applied to the project role template binding.
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "crate::metadata::serialize_sorted"
+    )]
+    pub annotations: Option<std::collections::HashMap<String, String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub group_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub group_principal_name: Option<String>,
+    /// The name of the project role template binding (typically the Kubernetes metadata.name).
+    pub id: String,
+
+    /// Labels applied to the project role template binding
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "crate::metadata::serialize_sorted"
+    )]
+    pub labels: Option<std::collections::HashMap<String, String>>,
+
+    /// the project (namespace) the project role template exists in
+    pub namespace: String,
+
+    /// The name of the project the project role template is bound to (cluster-id:project-id)
+    pub project_name: String,
+
+    pub role_template_name: String,
+
+    /// An opaque value that represents the internal version of this object that can be used by clients to determine when objects have changed. May be used for optimistic concurrency, change detection, and the watch operation on a resource or set of resources. Clients must treat these values as opaque and passed unmodified back to the server. They may only be valid for a particular resource or set of resources.  Populated by the system. Read-only. Value must be treated as opaque by clients and . More info: https://git.k8s.io/community/contributors/devel/sig-architecture/api-conventions.md#concurrency-control-and-consistency
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub resource_version: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub service_account: Option<String>,
+
+    /// The UID of the project. This cannot be changed. Rancher will set this value when the project is created.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub uid: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user_principal_name: Option<String>,
+}
+
+impl ProjectRoleTemplateBinding {
+    /// Start building a binding, see `ProjectRoleTemplateBindingBuilder`
+    pub fn builder() -> ProjectRoleTemplateBindingBuilder {
+        ProjectRoleTemplateBindingBuilder::default()
+    }
+
+    pub fn new(
+        annotations: Option<std::collections::HashMap<String, String>>,
+        group_name: Option<String>,
+        group_principal_name: Option<String>,
+        id: String,
+        labels: Option<std::collections::HashMap<String, String>>,
+        namespace: String,
+        project_name: String,
+        uid: Option<String>,
+        role_template_name: String,
+        resource_version: Option<String>,
+        service_account: Option<String>,
+        user_name: Option<String>,
+        user_principal_name: Option<String>,
+    ) -> Self {
+        ProjectRoleTemplateBinding {
+            annotations,
+            group_name,
+            group_principal_name,
+            id,
+            labels,
+            namespace,
+            project_name,
+            role_template_name,
+            resource_version,
+            service_account,
+            uid,
+            user_name,
+            user_principal_name,
+        }
+    }
+
+    /// Check that the role template this binding references can be bound to a project
+    ///
+    /// # Errors
+    ///
+    /// * `String` - The role template is a cluster role template, see `crate::rt::check_context`
+    pub fn check_role_template(&self, rt: &RoleTemplate) -> Result<(), String> {
+        check_context(rt, BindingScope::Project, &self.id)
+    }
+
+    /// The identity of the binding, its resolved namespace and ID
+    pub fn key(&self) -> ResourceKey {
+        ResourceKey::new(self.resolved_namespace(), self.id.clone())
+    }
+
+    /// Check that the binding references exactly one subject
+    ///
+    /// A user may be named by its ID, its principal or both, so `user_name` and
+    /// `user_principal_name` count as one subject. `group_name`, `group_principal_name` and
+    /// `service_account` are a subject each.
+    ///
+    /// # Errors
+    ///
+    /// * `&'static str` - The binding has no subject, or more than one
+    pub fn validate(&self) -> Result<(), &'static str> {
+        match self.subjects().len() {
+            0 => Err("binding has no subject"),
+            1 => Ok(()),
+            _ => Err("binding has more than one subject"),
+        }
+    }
+
+    /// The subjects set on the binding, by the name of their field
+    fn subjects(&self) -> Vec<&'static str> {
+        [
+            (self.user_name.is_some() || self.user_principal_name.is_some(), "user"),
+            (self.group_name.is_some(), "group_name"),
+            (self.group_principal_name.is_some(), "group_principal_name"),
+            (self.service_account.is_some(), "service_account"),
+        ]
+        .into_iter()
+        .filter_map(|(set, subject)| set.then_some(subject))
+        .collect()
+    }
+
+    /// Resolve the namespace the binding should be written to
+    ///
+    /// Returns `namespace` when it is set, otherwise derives it from the project id portion of
+    /// `project_name` (cluster-id:project-id), since Rancher rejects writes with an empty namespace.
+    /// An empty string is returned when `project_name` is malformed too.
+    pub fn resolved_namespace(&self) -> String {
+        if !self.namespace.is_empty() {
+            return self.namespace.clone();
+        }
+        split_project_name(&self.project_name)
+            .map(|(_, project_id)| project_id.to_string())
+            .unwrap_or_default()
+    }
+
+    /// Serialize the binding to YAML, suitable for committing to git
+    ///
+    /// Only the user managed labels and annotations are written, see `exportable_metadata`. The
+    /// fields of `PRTB_EXCLUDE_PATHS`, such as `resource_version` and `uid`, and subjects that are
+    /// not set are left out.
+    ///
+    /// # Returns
+    ///
+    /// * `String` - The YAML document
+    ///
+    /// # Errors
+    ///
+    /// * `serde_yaml::Error` - The error that occurred while serializing
+    pub fn to_yaml(&self) -> Result<String, serde_yaml::Error> {
+        let mut binding = self.clone();
+        binding.annotations = exportable_metadata(self.annotations.as_ref());
+        binding.labels = exportable_metadata(self.labels.as_ref());
+        crate::export::to_export_yaml(&binding, PRTB_EXCLUDE_PATHS)
+    }
+
+    /// Deserialize a binding from a YAML document written by `to_yaml`
+    ///
+    /// # Arguments
+    ///
+    /// * `s` - The YAML document
+    ///
+    /// # Returns
+    ///
+    /// * `ProjectRoleTemplateBinding` - The binding
+    ///
+    /// # Errors
+    ///
+    /// * `serde_yaml::Error` - The error that occurred while parsing the document
+    pub fn from_yaml(s: &str) -> Result<Self, serde_yaml::Error> {
+        serde_yaml::from_str(s)
+    }
+
+    /// Serialize the binding to a manifest that `kubectl apply -f` accepts
+    ///
+    /// Unlike `to_yaml`, the API form is written, with `apiVersion: management.cattle.io/v3`,
+    /// `kind: ProjectRoleTemplateBinding`, the ID as `metadata.name` and the resolved namespace
+    /// as `metadata.namespace`.
+    ///
+    /// # Returns
+    ///
+    /// * `String` - The YAML manifest
+    ///
+    /// # Errors
+    ///
+    /// * `serde_yaml::Error` - The error that occurred while serializing
+    pub fn to_manifest_yaml(&self) -> Result<String, serde_yaml::Error> {
+        let document = crate::export::binding_document(self)
+            .map_err(<serde_yaml::Error as serde::ser::Error>::custom)?;
+        serde_yaml::to_string(&document)
+    }
+}
+
+impl TryFrom<IoCattleManagementv3ProjectRoleTemplateBinding> for ProjectRoleTemplateBinding {
+    type Error = &'static str;
+
+    fn try_from(
+        value: IoCattleManagementv3ProjectRoleTemplateBinding,
+    ) -> Result<Self, Self::Error> {
+        let metadata: IoK8sApimachineryPkgApisMetaV1ObjectMeta =
+            value.metadata.ok_or("missing metadata")?;
+
+        let id = metadata.name.ok_or("missing name")?;
 
         // Extract the fields from the IoCattleManagementv3ProjectRoleTemplateBinding
         // and create a new ProjectRoleTemplateBinding instance
@@ -314,68 +1172,375 @@ impl TryFrom<IoCattleManagementv3ProjectRoleTemplateBinding> for ProjectRoleTemp
     }
 }
 
+/// Build the API form of a binding without validating it
+///
+/// Used where a malformed binding must still be rendered, e.g. to show it in a diff or export.
+pub(crate) fn api_form(value: ProjectRoleTemplateBinding) -> IoCattleManagementv3ProjectRoleTemplateBinding {
+    let namespace = value.resolved_namespace();
+    let metadata = IoK8sApimachineryPkgApisMetaV1ObjectMeta {
+        annotations: value.annotations,
+        labels: value.labels,
+        namespace: Some(namespace),
+        name: Some(value.id.clone()),
+        ..Default::default()
+    };
+
+    IoCattleManagementv3ProjectRoleTemplateBinding {
+        api_version: Some("management.cattle.io/v3".to_string()),
+        group_name: value.group_name,
+        group_principal_name: value.group_principal_name,
+        kind: Some("ProjectRoleTemplateBinding".to_string()),
+        metadata: Some(metadata),
+        project_name: value.project_name,
+        role_template_name: value.role_template_name,
+        service_account: value.service_account,
+        user_name: value.user_name,
+        user_principal_name: value.user_principal_name,
+    }
+}
+
 impl TryFrom<ProjectRoleTemplateBinding> for IoCattleManagementv3ProjectRoleTemplateBinding {
     type Error = &'static str;
 
+    /// Fails when `id` is not a DNS-1123 subdomain, when `project_name` is not
+    /// `cluster-id:project-id`, when `namespace` is set to something else than the project ID, or
+    /// when the binding does not reference exactly one subject, Rancher would reject the binding
     fn try_from(value: ProjectRoleTemplateBinding) -> Result<Self, Self::Error> {
-        // Create a new IoCattleManagementv3ProjectRoleTemplateBinding instance
-        let metadata = IoK8sApimachineryPkgApisMetaV1ObjectMeta {
-            annotations: value.annotations,
-            labels: value.labels,
-            namespace: Some(value.namespace),
-            name: Some(value.id.clone()),
-            ..Default::default()
-        };
+        validate_name(&value.id)?;
+        let (_, project_id) = split_project_name(&value.project_name)
+            .map_err(|_| "project_name must be cluster-id:project-id")?;
+        if !value.namespace.is_empty() && value.namespace != project_id {
+            return Err("namespace must be the project id of project_name");
+        }
+        value.validate()?;
 
-        Ok(IoCattleManagementv3ProjectRoleTemplateBinding {
-            api_version: Some("management.cattle.io/v3".to_string()),
-            group_name: value.group_name,
-            group_principal_name: value.group_principal_name,
-            kind: Some("ProjectRoleTemplateBinding".to_string()),
-            metadata: Some(metadata),
-            project_name: value.project_name,
-            role_template_name: value.role_template_name,
-            service_account: value.service_account,
-            user_name: value.user_name,
-            user_principal_name: value.user_principal_name,
-        })
+        Ok(api_form(value))
+    }
+}
+
+/// Serialize a binding in its API form with the server managed fields pruned
+fn pruned_api_value(binding: &ProjectRoleTemplateBinding) -> serde_json::Value {
+    let mut value = serde_json::to_value(api_form(binding.clone()))
+        // fall back to the domain form so an invalid binding still diffs
+        .unwrap_or_else(|_| serde_json::to_value(binding).unwrap_or_default());
+    prune_paths(&mut value, PRTB_EXCLUDE_PATHS);
+    value
+}
+
+/// Compare a desired binding to the actual one, ignoring `PRTB_EXCLUDE_PATHS`
+///
+/// Unlike `PartialEq`, every field is compared and each difference is reported.
+///
+/// # Arguments
+///
+/// * `desired` - The desired binding
+/// * `actual` - The binding as it exists on the server
+///
+/// # Returns
+///
+/// * `Option<DiffReport>` - The added, removed and changed fields, with API (camelCase, dotted) paths, or `None` when the bindings are equivalent
+pub fn diff(
+    desired: &ProjectRoleTemplateBinding,
+    actual: &ProjectRoleTemplateBinding,
+) -> Option<DiffReport> {
+    let report = DiffReport::between(&pruned_api_value(actual), &pruned_api_value(desired));
+    if report.is_empty() {
+        None
+    } else {
+        Some(report)
+    }
+}
+
+/// Compute the RFC 6902 JSON patch turning the actual binding into the desired one
+///
+/// Both sides are compared in their pruned API form, so `PRTB_EXCLUDE_PATHS` never appear as operations.
+/// The result can be sent with `JSON_PATCH_CONTENT_TYPE`.
+///
+/// # Arguments
+///
+/// * `desired` - The desired binding
+/// * `actual` - The binding as it exists on the server
+///
+/// # Returns
+///
+/// * `serde_json::Value` - The patch as a JSON array of operations, empty when the bindings are equivalent
+pub fn to_json_patch(desired: &ProjectRoleTemplateBinding, actual: &ProjectRoleTemplateBinding) -> serde_json::Value {
+    json_patch_between(&pruned_api_value(actual), &pruned_api_value(desired))
+}
+
+/// Compute a stable hash of the content of a binding
+///
+/// The hash covers the pruned, canonically ordered API form, without `CONTENT_HASH_ANNOTATION`,
+/// so it only changes when the binding itself does.
+///
+/// # Arguments
+///
+/// * `binding` - The binding to hash
+///
+/// # Returns
+///
+/// * `u64` - The hash, format it with `metadata::format_content_hash`
+pub fn content_hash(binding: &ProjectRoleTemplateBinding) -> u64 {
+    let mut value = pruned_api_value(binding);
+    strip_for_content_hash(&mut value);
+    hash_value(&value)
+}
+
+/// Return a copy of a binding with its content hash stored in `CONTENT_HASH_ANNOTATION`
+pub fn with_content_hash(binding: &ProjectRoleTemplateBinding) -> ProjectRoleTemplateBinding {
+    let mut hashed = binding.clone();
+    hashed
+        .annotations
+        .get_or_insert_with(Default::default)
+        .insert(CONTENT_HASH_ANNOTATION.to_string(), format_content_hash(content_hash(binding)));
+    hashed
+}
+
+impl PartialEq<ProjectRoleTemplateBinding> for IoCattleManagementv3ProjectRoleTemplateBinding {
+    fn eq(&self, other: &ProjectRoleTemplateBinding) -> bool {
+        let metadata = self.metadata.as_ref();
+        let lhs = metadata.and_then(|m| m.name.clone());
+        let rhs = Some(other.id.clone());
+
+        lhs == rhs
+            && metadata.and_then(|m| m.namespace.clone()) == Some(other.resolved_namespace())
+            && user_metadata_eq(metadata.and_then(|m| m.annotations.as_ref()), other.annotations.as_ref())
+            && user_metadata_eq(metadata.and_then(|m| m.labels.as_ref()), other.labels.as_ref())
+            && self.group_name == other.group_name
+            && self.group_principal_name == other.group_principal_name
+            && self.project_name == other.project_name
+            && self.role_template_name == other.role_template_name
+            && self.service_account == other.service_account
+            && self.user_name == other.user_name
+            && self.user_principal_name == other.user_principal_name
+    }
+}
+
+impl PartialEq<IoCattleManagementv3ProjectRoleTemplateBinding> for ProjectRoleTemplateBinding {
+    fn eq(&self, other: &IoCattleManagementv3ProjectRoleTemplateBinding) -> bool {
+        // let lhs = Some(self.id.clone());
+        // let rhs = other.metadata.as_ref().and_then(|m| m.name.clone());
+
+        // lhs == rhs
+        //     && self.group_name == other.group_name
+        //     && self.group_principal_name == other.group_principal_name
+        //     && self.project_name == other.project_name
+        //     && self.role_template_name == other.role_template_name
+        //     && self.service_account == other.service_account
+        //     && self.user_name == other.user_name
+        //     && self.user_principal_name == other.user_principal_name
+
+        other == self
+    }
+}
+
+/// The error of `split_project_name`, holding the project name that could not be split
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub project_name: String,
+}
+
+impl std::fmt::Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Invalid project_name {}", self.project_name)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Split a project name (`cluster-id:project-id`) into its cluster and project IDs
+///
+/// # Arguments
+///
+/// * `project_name` - The project name, e.g. `c-123:p-456`
+///
+/// # Returns
+///
+/// * `(&str, &str)` - The cluster ID and the project ID
+///
+/// # Errors
+///
+/// * `ParseError` - When the name does not hold exactly one colon or either ID is empty
+pub fn split_project_name(project_name: &str) -> Result<(&str, &str), ParseError> {
+    match project_name.split_once(':') {
+        Some((cluster_id, project_id))
+            if !cluster_id.is_empty() && !project_id.is_empty() && !project_id.contains(':') =>
+        {
+            Ok((cluster_id, project_id))
+        }
+        _ => Err(ParseError {
+            project_name: project_name.to_string(),
+        }),
+    }
+}
+
+/// Join a cluster ID and a project ID into a project name, the inverse of `split_project_name`
+pub fn join_project_name(cluster_id: &str, project_id: &str) -> String {
+    format!("{}:{}", cluster_id, project_id)
+}
+
+/// A fluent builder of `ProjectRoleTemplateBinding`, for code constructing desired state
+///
+/// ```ignore
+/// let binding = ProjectRoleTemplateBinding::builder()
+///     .id("prtb-alice")
+///     .user("u-abc")
+///     .role("project-member")
+///     .project("c-123:p-456")
+///     .build()?;
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct ProjectRoleTemplateBindingBuilder {
+    annotations: Option<std::collections::HashMap<String, String>>,
+    group_name: Option<String>,
+    group_principal_name: Option<String>,
+    id: Option<String>,
+    labels: Option<std::collections::HashMap<String, String>>,
+    namespace: Option<String>,
+    project_name: Option<String>,
+    role_template_name: Option<String>,
+    service_account: Option<String>,
+    user_name: Option<String>,
+    user_principal_name: Option<String>,
+}
+
+impl ProjectRoleTemplateBindingBuilder {
+    /// The name of the binding
+    pub fn id(mut self, id: impl Into<String>) -> Self {
+        self.id = Some(id.into());
+        self
+    }
+
+    /// The namespace of the binding, derived from the project when not set
+    pub fn namespace(mut self, namespace: impl Into<String>) -> Self {
+        self.namespace = Some(namespace.into());
+        self
+    }
+
+    /// The project the role is granted on, as `cluster-id:project-id`
+    pub fn project(mut self, project_name: impl Into<String>) -> Self {
+        self.project_name = Some(project_name.into());
+        self
+    }
+
+    /// The name of the role template to grant
+    pub fn role(mut self, role_template_name: impl Into<String>) -> Self {
+        self.role_template_name = Some(role_template_name.into());
+        self
+    }
+
+    /// Grant the role to a user by its ID, e.g. `u-abc12`
+    pub fn user(mut self, user_name: impl Into<String>) -> Self {
+        self.user_name = Some(user_name.into());
+        self
+    }
+
+    /// Grant the role to a user by its principal, e.g. `local://u-abc12`
+    pub fn user_principal(mut self, user_principal_name: impl Into<String>) -> Self {
+        self.user_principal_name = Some(user_principal_name.into());
+        self
+    }
+
+    /// Grant the role to a group by its name
+    pub fn group(mut self, group_name: impl Into<String>) -> Self {
+        self.group_name = Some(group_name.into());
+        self
+    }
+
+    /// Grant the role to a group by its principal, e.g. `github_team://1234`
+    pub fn group_principal(mut self, group_principal_name: impl Into<String>) -> Self {
+        self.group_principal_name = Some(group_principal_name.into());
+        self
+    }
+
+    /// Grant the role to a service account, as `namespace:name`
+    pub fn service_account(mut self, service_account: impl Into<String>) -> Self {
+        self.service_account = Some(service_account.into());
+        self
     }
-}
 
-impl PartialEq<ProjectRoleTemplateBinding> for IoCattleManagementv3ProjectRoleTemplateBinding {
-    fn eq(&self, other: &ProjectRoleTemplateBinding) -> bool {
-        let lhs = self.metadata.as_ref().and_then(|m| m.name.clone());
-        let rhs = Some(other.id.clone());
+    /// Add a label to the binding
+    pub fn label(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.labels.get_or_insert_with(Default::default).insert(key.into(), value.into());
+        self
+    }
 
-        lhs == rhs
-            && self.group_name == other.group_name
-            && self.group_principal_name == other.group_principal_name
-            && self.project_name == other.project_name
-            && self.role_template_name == other.role_template_name
-            && self.service_account == other.service_account
-            && self.user_name == other.user_name
-            && self.user_principal_name == other.user_principal_name
+    /// Add an annotation to the binding
+    pub fn annotation(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.annotations.get_or_insert_with(Default::default).insert(key.into(), value.into());
+        self
     }
-}
 
-impl PartialEq<IoCattleManagementv3ProjectRoleTemplateBinding> for ProjectRoleTemplateBinding {
-    fn eq(&self, other: &IoCattleManagementv3ProjectRoleTemplateBinding) -> bool {
-        // let lhs = Some(self.id.clone());
-        // let rhs = other.metadata.as_ref().and_then(|m| m.name.clone());
+    /// Validate the fields and build the binding
+    ///
+    /// # Returns
+    ///
+    /// * `ProjectRoleTemplateBinding` - The binding, with its namespace derived from the project when not set
+    ///
+    /// # Errors
+    ///
+    /// * `String` - The missing required field, or the error of `ProjectRoleTemplateBinding::validate`
+    pub fn build(self) -> Result<ProjectRoleTemplateBinding, String> {
+        fn required(value: Option<String>, field: &str) -> Result<String, String> {
+            value
+                .filter(|value| !value.is_empty())
+                .ok_or_else(|| format!("{} is required", field))
+        }
 
-        // lhs == rhs
-        //     && self.group_name == other.group_name
-        //     && self.group_principal_name == other.group_principal_name
-        //     && self.project_name == other.project_name
-        //     && self.role_template_name == other.role_template_name
-        //     && self.service_account == other.service_account
-        //     && self.user_name == other.user_name
-        //     && self.user_principal_name == other.user_principal_name
+        let id = required(self.id, "id")?;
+        let project_name = required(self.project_name, "project_name")?;
+        let role_template_name = required(self.role_template_name, "role_template_name")?;
 
-        other == self
+        let mut binding = ProjectRoleTemplateBinding {
+            annotations: self.annotations,
+            group_name: self.group_name,
+            group_principal_name: self.group_principal_name,
+            id,
+            labels: self.labels,
+            namespace: self.namespace.unwrap_or_default(),
+            project_name,
+            role_template_name,
+            resource_version: None,
+            service_account: self.service_account,
+            uid: None,
+            user_name: self.user_name,
+            user_principal_name: self.user_principal_name,
+        };
+
+        binding
+            .validate()
+            .map_err(|e| format!("invalid binding {}: {}", binding.id, e))?;
+
+        binding.namespace = binding.resolved_namespace();
+        if binding.namespace.is_empty() {
+            return Err(format!("binding {} has no namespace", binding.id));
+        }
+        Ok(binding)
     }
 }
 
+/// Find the bindings that reference more than one subject
+///
+/// Rancher expects exactly one subject per binding. Bindings with more than one subject, counted
+/// as `ProjectRoleTemplateBinding::validate` counts them, are the result of corrupted data and
+/// need to be cleaned up manually.
+///
+/// # Arguments
+///
+/// * `bindings` - The bindings to inspect
+///
+/// # Returns
+///
+/// * `Vec<&ProjectRoleTemplateBinding>` - The bindings with more than one subject set
+pub fn find_multi_subject_bindings(
+    bindings: &[ProjectRoleTemplateBinding],
+) -> Vec<&ProjectRoleTemplateBinding> {
+    bindings
+        .iter()
+        .filter(|binding| binding.subjects().len() > 1)
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -385,7 +1550,7 @@ mod tests {
             id: "binding-id".to_string(),
             group_name: Some("group1".to_string()),
             group_principal_name: Some("groupPrincipal".to_string()),
-            project_name: "project-id".to_string(),
+            project_name: "c-1:namespace-id".to_string(),
             role_template_name: "role-template".to_string(),
             service_account: Some("service-account".to_string()),
             user_name: Some("user1".to_string()),
@@ -398,17 +1563,28 @@ mod tests {
         }
     }
 
+    /// `sample_binding` with the user as its only subject, as Rancher accepts it
+    fn sample_user_binding() -> ProjectRoleTemplateBinding {
+        ProjectRoleTemplateBinding {
+            group_name: None,
+            group_principal_name: None,
+            service_account: None,
+            ..sample_binding()
+        }
+    }
+
     fn sample_iocattle_binding() -> IoCattleManagementv3ProjectRoleTemplateBinding {
         IoCattleManagementv3ProjectRoleTemplateBinding {
             api_version: Some("management.cattle.io/v3".to_string()),
             kind: Some("ProjectRoleTemplateBinding".to_string()),
             metadata: Some(IoK8sApimachineryPkgApisMetaV1ObjectMeta {
                 name: Some("binding-id".to_string()),
+                namespace: Some("namespace-id".to_string()),
                 ..Default::default()
             }),
             group_name: Some("group1".to_string()),
             group_principal_name: Some("groupPrincipal".to_string()),
-            project_name: "project-id".to_string(),
+            project_name: "c-1:namespace-id".to_string(),
             role_template_name: "role-template".to_string(),
             service_account: Some("service-account".to_string()),
             user_name: Some("user1".to_string()),
@@ -438,13 +1614,13 @@ mod tests {
 
     #[test]
     fn test_try_from_binding_to_iocattle() {
-        let binding = sample_binding();
+        let binding = sample_user_binding();
         let result = IoCattleManagementv3ProjectRoleTemplateBinding::try_from(binding);
         assert!(result.is_ok());
 
         let ioc = result.unwrap();
         assert_eq!(ioc.metadata.unwrap().name, Some("binding-id".to_string()));
-        assert_eq!(ioc.group_name.as_deref(), Some("group1"));
+        assert_eq!(ioc.user_name.as_deref(), Some("user1"));
     }
 
     #[test]
@@ -458,6 +1634,42 @@ mod tests {
         assert_ne!(b, a);
     }
 
+    #[test]
+    fn test_inequality_on_different_namespace() {
+        let a = sample_binding();
+        let mut b = sample_iocattle_binding();
+        b.metadata.as_mut().unwrap().namespace = Some("other-namespace".to_string());
+
+        assert_ne!(a, b);
+        assert_ne!(b, a);
+    }
+
+    #[test]
+    fn test_inequality_on_different_labels() {
+        let a = sample_binding();
+        let mut b = sample_iocattle_binding();
+        b.metadata.as_mut().unwrap().labels = Some(std::collections::HashMap::from([(
+            "team".to_string(),
+            "platform".to_string(),
+        )]));
+
+        assert_ne!(a, b);
+        assert_ne!(b, a);
+    }
+
+    #[test]
+    fn test_inequality_on_different_annotations() {
+        let a = sample_binding();
+        let mut b = sample_iocattle_binding();
+        b.metadata.as_mut().unwrap().annotations = Some(std::collections::HashMap::from([(
+            "owner".to_string(),
+            "alice".to_string(),
+        )]));
+
+        assert_ne!(a, b);
+        assert_ne!(b, a);
+    }
+
     #[test]
     fn test_missing_metadata_name() {
         let mut b = sample_iocattle_binding();
@@ -476,4 +1688,717 @@ mod tests {
         assert_ne!(a, b);
         assert_ne!(b, a);
     }
+
+    #[test]
+    fn test_find_multi_subject_bindings_clean_binding() {
+        let mut binding = sample_binding();
+        binding.group_name = None;
+        binding.group_principal_name = None;
+        binding.service_account = None;
+
+        let bindings = vec![binding];
+        assert!(find_multi_subject_bindings(&bindings).is_empty());
+    }
+
+    #[test]
+    fn test_find_multi_subject_bindings_user_and_group() {
+        let mut clean = sample_binding();
+        clean.id = "clean".to_string();
+        clean.group_name = None;
+        clean.group_principal_name = None;
+        clean.service_account = None;
+
+        let mut corrupted = sample_binding();
+        corrupted.id = "corrupted".to_string();
+        corrupted.group_principal_name = None;
+        corrupted.service_account = None;
+
+        let bindings = vec![clean, corrupted];
+        let found = find_multi_subject_bindings(&bindings);
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].id, "corrupted");
+    }
+
+    #[test]
+    fn test_find_multi_subject_bindings_counts_user_principal_name() {
+        let mut principal_only = sample_user_binding();
+        principal_only.id = "principal-only".to_string();
+        principal_only.user_name = None;
+        principal_only.user_principal_name = Some("local://u-alice".to_string());
+
+        let mut user_both_ways = principal_only.clone();
+        user_both_ways.id = "user-both-ways".to_string();
+        user_both_ways.user_name = Some("u-alice".to_string());
+
+        let mut principal_and_group = principal_only.clone();
+        principal_and_group.id = "principal-and-group".to_string();
+        principal_and_group.group_name = Some("group1".to_string());
+
+        let bindings = vec![principal_only, user_both_ways, principal_and_group];
+        let found = find_multi_subject_bindings(&bindings);
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].id, "principal-and-group");
+    }
+
+    #[test]
+    fn test_try_from_binding_with_empty_namespace_uses_project_id() {
+        let mut binding = sample_user_binding();
+        binding.namespace = String::new();
+        binding.project_name = "c-abc12:p-xyz34".to_string();
+
+        let ioc = IoCattleManagementv3ProjectRoleTemplateBinding::try_from(binding).unwrap();
+        assert_eq!(
+            ioc.metadata.unwrap().namespace.as_deref(),
+            Some("p-xyz34")
+        );
+    }
+
+    #[test]
+    fn test_resolved_namespace_prefers_explicit_namespace() {
+        let mut binding = sample_binding();
+        binding.project_name = "c-abc12:p-xyz34".to_string();
+
+        assert_eq!(binding.resolved_namespace(), "namespace-id");
+    }
+
+    #[test]
+    fn test_resolved_namespace_of_malformed_project_name_is_empty() {
+        let mut binding = sample_binding();
+        binding.namespace = String::new();
+
+        for project_name in ["p-xyz34", "c-abc12:", "c-abc12:p-xyz34:extra", ""] {
+            binding.project_name = project_name.to_string();
+            assert_eq!(binding.resolved_namespace(), "", "{}", project_name);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_create_binding_in_resolved_namespace() {
+        let rancher = crate::test_support::MockRancher::start().await;
+        let mut created = sample_iocattle_binding();
+        created.metadata.as_mut().unwrap().namespace = Some("namespace-id".to_string());
+        rancher
+            .expect_create_namespaced_project_role_template_binding("namespace-id")
+            .with_body_partial(serde_json::json!({
+                "metadata": { "name": "binding-id", "namespace": "namespace-id" }
+            }))
+            .expect_calls(1)
+            .returning_json(201, serde_json::to_value(&created).unwrap())
+            .await;
+
+        let binding = create_project_role_template_binding(&rancher.configuration(), &sample_user_binding())
+            .await
+            .unwrap();
+
+        assert_eq!(binding.id, "binding-id");
+        assert_eq!(binding.namespace, "namespace-id");
+    }
+
+    #[tokio::test]
+    async fn test_create_existing_binding_is_conflict() {
+        let rancher = crate::test_support::MockRancher::start().await;
+        rancher
+            .expect_create_namespaced_project_role_template_binding("namespace-id")
+            .returning_status(409, "AlreadyExists", "binding-id already exists")
+            .await;
+
+        let result = create_project_role_template_binding(&rancher.configuration(), &sample_user_binding()).await;
+
+        match result.map_err(WithResource::into_error) {
+            Err(RancherError::Conflict { message, .. }) => assert_eq!(message, "binding-id already exists"),
+            other => panic!("expected a conflict, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_replace_binding_sends_resource_version() {
+        let rancher = crate::test_support::MockRancher::start().await;
+        let mut updated = sample_iocattle_binding();
+        updated.role_template_name = "project-owner".to_string();
+        rancher
+            .expect(
+                "PUT",
+                &crate::ResourceKind::ProjectRoleTemplateBinding.api_path(Some("namespace-id"), "binding-id"),
+            )
+            .with_body_partial(serde_json::json!({
+                "metadata": { "resourceVersion": "resource-version" },
+                "roleTemplateName": "project-owner"
+            }))
+            .expect_calls(1)
+            .returning_json(200, serde_json::to_value(&updated).unwrap())
+            .await;
+
+        let binding = ProjectRoleTemplateBinding {
+            role_template_name: "project-owner".to_string(),
+            ..sample_user_binding()
+        };
+        let replaced = replace_project_role_template_binding(&rancher.configuration(), &binding)
+            .await
+            .unwrap();
+
+        assert_eq!(replaced.role_template_name, "project-owner");
+    }
+
+    #[tokio::test]
+    async fn test_replace_binding_conflict() {
+        let rancher = crate::test_support::MockRancher::start().await;
+        rancher
+            .expect(
+                "PUT",
+                &crate::ResourceKind::ProjectRoleTemplateBinding.api_path(Some("namespace-id"), "binding-id"),
+            )
+            .returning_status(409, "Conflict", "the object has been modified")
+            .await;
+
+        let result = replace_project_role_template_binding(&rancher.configuration(), &sample_user_binding()).await;
+
+        match result.map_err(WithResource::into_error) {
+            Err(RancherError::Conflict { message, .. }) => assert_eq!(message, "the object has been modified"),
+            other => panic!("expected a conflict, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_replace_binding_without_resource_version() {
+        let binding = ProjectRoleTemplateBinding {
+            resource_version: None,
+            ..sample_binding()
+        };
+        let configuration = Configuration::new();
+
+        let err = replace_project_role_template_binding(&configuration, &binding)
+            .await
+            .unwrap_err();
+
+        assert_eq!(err.resource.to_string(), "ProjectRoleTemplateBinding namespace-id/binding-id");
+        assert!(matches!(err.error, RancherError::Invalid(message) if message.starts_with("missing resource version")));
+    }
+
+    #[tokio::test]
+    async fn test_patch_binding_sends_merge_patch() {
+        let rancher = crate::test_support::MockRancher::start().await;
+        let mut patched = sample_iocattle_binding();
+        patched.group_principal_name = Some("local://g-new".to_string());
+        rancher
+            .expect(
+                "PATCH",
+                &ResourceKind::ProjectRoleTemplateBinding.api_path(Some("namespace-id"), "binding-id"),
+            )
+            .with_body_partial(serde_json::json!({ "groupPrincipalName": "local://g-new" }))
+            .expect_calls(1)
+            .returning_json(200, serde_json::to_value(&patched).unwrap())
+            .await;
+
+        let binding = patch_project_role_template_binding(
+            &rancher.configuration(),
+            "namespace-id",
+            "binding-id",
+            serde_json::json!({ "groupPrincipalName": "local://g-new" }),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(binding.group_principal_name.as_deref(), Some("local://g-new"));
+    }
+
+    #[tokio::test]
+    async fn test_patch_binding_rejects_immutable_field() {
+        let configuration = Configuration::new();
+
+        let result = patch_project_role_template_binding(
+            &configuration,
+            "namespace-id",
+            "binding-id",
+            serde_json::json!({ "metadata": { "resourceVersion": "42" } }),
+        )
+        .await;
+
+        match result.map_err(WithResource::into_error) {
+            Err(RancherError::Invalid(message)) => {
+                assert_eq!(message, "field metadata.resourceVersion cannot be changed")
+            }
+            other => panic!("expected an immutable field error, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_patch_binding_rejects_null_metadata() {
+        let configuration = Configuration::new();
+
+        let result = patch_project_role_template_binding(
+            &configuration,
+            "namespace-id",
+            "binding-id",
+            serde_json::json!({ "metadata": null }),
+        )
+        .await;
+
+        match result.map_err(WithResource::into_error) {
+            Err(RancherError::Invalid(message)) => {
+                assert_eq!(message, "field metadata.creationTimestamp cannot be changed")
+            }
+            other => panic!("expected an immutable field error, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_get_binding_found() {
+        let rancher = crate::test_support::MockRancher::start().await;
+        rancher
+            .expect(
+                "GET",
+                &ResourceKind::ProjectRoleTemplateBinding.api_path(Some("namespace-id"), "binding-id"),
+            )
+            .returning_json(200, serde_json::to_value(sample_iocattle_binding()).unwrap())
+            .await;
+
+        let binding = get_project_role_template_binding(&rancher.configuration(), "namespace-id", "binding-id")
+            .await
+            .unwrap();
+
+        assert_eq!(binding.map(|b| b.id).as_deref(), Some("binding-id"));
+    }
+
+    #[tokio::test]
+    async fn test_get_binding_not_found() {
+        let rancher = crate::test_support::MockRancher::start().await;
+        rancher
+            .expect(
+                "GET",
+                &ResourceKind::ProjectRoleTemplateBinding.api_path(Some("namespace-id"), "missing"),
+            )
+            .returning_status(404, "NotFound", "projectroletemplatebindings \"missing\" not found")
+            .await;
+
+        let binding = get_project_role_template_binding(&rancher.configuration(), "namespace-id", "missing")
+            .await
+            .unwrap();
+
+        assert!(binding.is_none());
+    }
+
+    fn named_iocattle_binding(name: &str) -> IoCattleManagementv3ProjectRoleTemplateBinding {
+        let mut binding = sample_iocattle_binding();
+        binding.metadata.as_mut().unwrap().name = Some(name.to_string());
+        binding
+    }
+
+    #[tokio::test]
+    async fn test_get_all_bindings_follows_continue_token() {
+        let rancher = crate::test_support::MockRancher::start().await;
+        rancher
+            .expect_list_project_role_template_bindings()
+            .without_query("continue")
+            .with_query("limit", "500")
+            .expect_calls(1)
+            .returning_page(
+                vec![named_iocattle_binding("prtb-1"), named_iocattle_binding("prtb-2")],
+                Some("page-2"),
+            )
+            .await;
+        rancher
+            .expect_list_project_role_template_bindings()
+            .with_query("continue", "page-2")
+            .expect_calls(1)
+            .returning_page(vec![named_iocattle_binding("prtb-3")], None)
+            .await;
+
+        let bindings = get_all_project_role_template_bindings(
+            &rancher.configuration(),
+            None,
+            None,
+            &RequestOptions::default(),
+        )
+        .await
+        .unwrap();
+
+        let ids: Vec<&str> = bindings.iter().map(|b| b.id.as_str()).collect();
+        assert_eq!(ids, vec!["prtb-1", "prtb-2", "prtb-3"]);
+    }
+
+    #[tokio::test]
+    async fn test_get_all_namespaced_bindings_skips_invalid_items() {
+        let rancher = crate::test_support::MockRancher::start().await;
+        let mut nameless = sample_iocattle_binding();
+        nameless.metadata.as_mut().unwrap().name = None;
+        rancher
+            .expect_list_namespaced_project_role_template_bindings("p-1")
+            .without_query("continue")
+            .expect_calls(1)
+            .returning_page(vec![named_iocattle_binding("prtb-1"), nameless], Some("page-2"))
+            .await;
+        rancher
+            .expect_list_namespaced_project_role_template_bindings("p-1")
+            .with_query("continue", "page-2")
+            .expect_calls(1)
+            .returning_page(vec![named_iocattle_binding("prtb-2")], None)
+            .await;
+
+        let (bindings, skipped) = get_all_namespaced_project_role_template_bindings(
+            &rancher.configuration(),
+            "p-1",
+            None,
+            None,
+            &RequestOptions::default(),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(bindings.len(), 2);
+        assert_eq!(skipped.len(), 1);
+        assert_eq!(skipped[0].0, None);
+    }
+
+    #[tokio::test]
+    async fn test_stream_bindings_across_pages() {
+        let rancher = crate::test_support::MockRancher::start().await;
+        rancher
+            .expect_list_project_role_template_bindings()
+            .without_query("continue")
+            .expect_calls(1)
+            .returning_page(vec![named_iocattle_binding("prtb-1")], Some("page-2"))
+            .await;
+        rancher
+            .expect_list_project_role_template_bindings()
+            .with_query("continue", "page-2")
+            .expect_calls(1)
+            .returning_page(vec![named_iocattle_binding("prtb-2"), named_iocattle_binding("prtb-3")], None)
+            .await;
+
+        let configuration = rancher.configuration();
+        let options = RequestOptions::default();
+        let count = stream_project_role_template_bindings(&configuration, &options)
+            .filter(|item| futures::future::ready(item.is_ok()))
+            .count()
+            .await;
+
+        assert_eq!(count, 3);
+    }
+
+    #[tokio::test]
+    async fn test_bindings_for_namespaces_keeps_per_namespace_errors() {
+        let rancher = crate::test_support::MockRancher::start().await;
+        rancher
+            .expect_list_namespaced_project_role_template_bindings("p-1")
+            .returning(vec![named_iocattle_binding("prtb-1")])
+            .await;
+        rancher
+            .expect_list_namespaced_project_role_template_bindings("p-2")
+            .returning_status(500, "InternalError", "boom")
+            .await;
+        rancher
+            .expect_list_namespaced_project_role_template_bindings("p-3")
+            .returning(vec![named_iocattle_binding("prtb-2"), named_iocattle_binding("prtb-3")])
+            .await;
+
+        let (bindings, errors) = get_project_role_template_bindings_for_namespaces(
+            &rancher.configuration(),
+            &["p-1", "p-2", "p-3"],
+            2,
+            &RequestOptions::default(),
+        )
+        .await;
+
+        let mut ids: Vec<&str> = bindings.iter().map(|b| b.id.as_str()).collect();
+        ids.sort();
+        assert_eq!(ids, vec!["prtb-1", "prtb-2", "prtb-3"]);
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].0, "p-2");
+    }
+
+    #[test]
+    fn test_diff_reports_changed_and_removed_fields() {
+        let actual = sample_binding();
+        let mut desired = sample_binding();
+        desired.role_template_name = "other-role".to_string();
+        desired.service_account = None;
+
+        let report = diff(&desired, &actual).unwrap();
+
+        assert_eq!(report.changed.len(), 1);
+        assert_eq!(report.changed[0].path, "roleTemplateName");
+        assert_eq!(report.removed.len(), 1);
+        assert_eq!(report.removed[0].path, "serviceAccount");
+        assert!(report.added.is_empty());
+    }
+
+    #[test]
+    fn test_json_patch_single_changed_field() {
+        let actual = sample_binding();
+        let mut desired = sample_binding();
+        desired.role_template_name = "other-role".to_string();
+        desired.resource_version = Some("other-version".to_string());
+
+        let patch = to_json_patch(&desired, &actual);
+
+        assert_eq!(
+            patch,
+            serde_json::json!([{ "op": "replace", "path": "/roleTemplateName", "value": "other-role" }])
+        );
+    }
+
+    #[test]
+    fn test_diff_equivalent_bindings() {
+        let actual = sample_binding();
+        let desired = ProjectRoleTemplateBinding {
+            resource_version: Some("other-version".to_string()),
+            uid: None,
+            ..sample_binding()
+        };
+
+        assert!(diff(&desired, &actual).is_none());
+    }
+
+    #[test]
+    fn test_diff_reports_label_and_annotation_changes() {
+        let actual = sample_binding();
+        let mut desired = sample_binding();
+        desired.labels = Some([("team".to_string(), "a".to_string())].into_iter().collect());
+        desired.annotations = Some([("owner".to_string(), "me".to_string())].into_iter().collect());
+
+        let report = diff(&desired, &actual).unwrap();
+
+        assert_eq!(report.paths(), vec!["metadata.annotations.owner", "metadata.labels.team"]);
+        assert_eq!(report.added.len(), 2);
+        assert_eq!(report.added[1].to, Some(serde_json::json!("a")));
+        assert_eq!(report.added[1].from, None);
+        assert!(report.removed.is_empty() && report.changed.is_empty());
+    }
+
+    #[test]
+    fn test_yaml_round_trip() {
+        let mut binding = sample_binding();
+        binding.annotations = Some(std::collections::HashMap::from([(
+            "owner".to_string(),
+            "team-a".to_string(),
+        )]));
+        binding.labels = None;
+        binding.resource_version = None;
+        binding.uid = None;
+
+        let yaml = binding.to_yaml().unwrap();
+
+        assert!(!yaml.contains("labels"));
+        assert_eq!(ProjectRoleTemplateBinding::from_yaml(&yaml).unwrap(), binding);
+    }
+
+    #[test]
+    fn test_group_binding_yaml_omits_user_fields() {
+        let mut binding = sample_binding();
+        binding.user_name = None;
+        binding.user_principal_name = None;
+        binding.service_account = None;
+
+        let yaml = binding.to_yaml().unwrap();
+
+        assert!(yaml.contains("group_name: group1"));
+        assert!(!yaml.contains("user_name"));
+        assert!(!yaml.contains("user_principal_name"));
+        assert!(!yaml.contains("service_account"));
+        assert!(!yaml.contains("annotations"));
+        assert!(!yaml.contains("{}"));
+    }
+
+    #[test]
+    fn test_manifest_yaml_has_kubectl_shape() {
+        let mut binding = sample_binding();
+        binding.namespace = String::new();
+        binding.project_name = "c-1:p-1".to_string();
+        binding.group_name = None;
+        binding.group_principal_name = None;
+        binding.service_account = None;
+        binding.user_principal_name = None;
+
+        let manifest: serde_json::Value =
+            serde_yaml::from_str(&binding.to_manifest_yaml().unwrap()).unwrap();
+
+        assert_eq!(
+            manifest,
+            serde_json::json!({
+                "apiVersion": "management.cattle.io/v3",
+                "kind": "ProjectRoleTemplateBinding",
+                "metadata": { "name": "binding-id", "namespace": "p-1" },
+                "projectName": "c-1:p-1",
+                "roleTemplateName": "role-template",
+                "userName": "user1",
+            })
+        );
+    }
+
+    #[test]
+    fn test_builder_builds_valid_binding() {
+        let binding = ProjectRoleTemplateBinding::builder()
+            .id("prtb-alice")
+            .user("u-abc")
+            .role("project-member")
+            .project("c-123:p-456")
+            .label("team", "platform")
+            .build()
+            .unwrap();
+
+        assert_eq!(binding.id, "prtb-alice");
+        assert_eq!(binding.namespace, "p-456");
+        assert_eq!(binding.project_name, "c-123:p-456");
+        assert_eq!(binding.role_template_name, "project-member");
+        assert_eq!(binding.user_name.as_deref(), Some("u-abc"));
+        assert_eq!(binding.labels.unwrap().get("team").map(String::as_str), Some("platform"));
+    }
+
+    #[test]
+    fn test_builder_requires_role() {
+        let result = ProjectRoleTemplateBinding::builder()
+            .id("prtb-alice")
+            .user("u-abc")
+            .project("c-123:p-456")
+            .build();
+
+        assert_eq!(result.unwrap_err(), "role_template_name is required");
+    }
+
+    #[test]
+    fn test_builder_rejects_two_subjects() {
+        let result = ProjectRoleTemplateBinding::builder()
+            .id("prtb-alice")
+            .user("u-abc")
+            .user_principal("local://u-abc")
+            .group("developers")
+            .role("project-member")
+            .project("c-123:p-456")
+            .build();
+
+        assert_eq!(
+            result.unwrap_err(),
+            "invalid binding prtb-alice: binding has more than one subject"
+        );
+    }
+
+    #[test]
+    fn test_default_serializes_cleanly() {
+        let binding = ProjectRoleTemplateBinding {
+            id: "prtb-alice".to_string(),
+            project_name: "c-123:p-456".to_string(),
+            role_template_name: "project-member".to_string(),
+            user_name: Some("u-abc".to_string()),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            serde_json::to_value(&binding).unwrap(),
+            serde_json::json!({
+                "id": "prtb-alice",
+                "namespace": "",
+                "project_name": "c-123:p-456",
+                "role_template_name": "project-member",
+                "user_name": "u-abc",
+            })
+        );
+        assert_eq!(binding.resolved_namespace(), "p-456");
+    }
+
+    #[test]
+    fn test_split_project_name() {
+        assert_eq!(split_project_name("c-123:p-456"), Ok(("c-123", "p-456")));
+        assert_eq!(join_project_name("c-123", "p-456"), "c-123:p-456");
+
+        for invalid in ["p-456", "c-123:p-456:x", ":p-456", "c-123:", ""] {
+            assert_eq!(
+                split_project_name(invalid),
+                Err(ParseError {
+                    project_name: invalid.to_string()
+                })
+            );
+        }
+    }
+
+    #[test]
+    fn test_try_from_binding_rejects_malformed_project_name() {
+        let mut binding = sample_binding();
+        binding.project_name = "namespace-id".to_string();
+
+        assert_eq!(
+            IoCattleManagementv3ProjectRoleTemplateBinding::try_from(binding),
+            Err("project_name must be cluster-id:project-id")
+        );
+    }
+
+    #[test]
+    fn test_try_from_binding_rejects_namespace_mismatch() {
+        let mut binding = sample_binding();
+        binding.namespace = "p-other".to_string();
+
+        assert_eq!(
+            IoCattleManagementv3ProjectRoleTemplateBinding::try_from(binding),
+            Err("namespace must be the project id of project_name")
+        );
+    }
+
+    #[test]
+    fn test_validate_rejects_binding_without_subject() {
+        let mut binding = sample_user_binding();
+        binding.user_name = None;
+        binding.user_principal_name = None;
+
+        assert_eq!(binding.validate(), Err("binding has no subject"));
+        assert_eq!(
+            IoCattleManagementv3ProjectRoleTemplateBinding::try_from(binding),
+            Err("binding has no subject")
+        );
+    }
+
+    #[test]
+    fn test_validate_rejects_binding_with_several_subjects() {
+        assert_eq!(sample_user_binding().validate(), Ok(()));
+
+        let mut binding = sample_user_binding();
+        binding.group_name = Some("group1".to_string());
+
+        assert_eq!(binding.validate(), Err("binding has more than one subject"));
+        assert_eq!(
+            IoCattleManagementv3ProjectRoleTemplateBinding::try_from(binding),
+            Err("binding has more than one subject")
+        );
+    }
+
+    #[test]
+    fn test_key_ignores_fields_other_than_namespace_and_id() {
+        let binding = sample_user_binding();
+        let other = ProjectRoleTemplateBinding {
+            role_template_name: "project-owner".to_string(),
+            labels: None,
+            resource_version: None,
+            ..sample_binding()
+        };
+        let keys: std::collections::HashSet<ResourceKey> =
+            [binding.key(), other.key()].into_iter().collect();
+
+        assert_eq!(binding.key(), ResourceKey::new("namespace-id", "binding-id"));
+        assert_eq!(keys.len(), 1);
+
+        let renamed = ProjectRoleTemplateBinding {
+            id: "other-id".to_string(),
+            ..sample_user_binding()
+        };
+        assert_ne!(renamed.key(), binding.key());
+    }
+
+    #[test]
+    fn test_key_orders_by_namespace_then_id() {
+        let binding = |namespace: &str, id: &str| ProjectRoleTemplateBinding {
+            id: id.to_string(),
+            namespace: namespace.to_string(),
+            project_name: format!("c-1:{}", namespace),
+            ..sample_user_binding()
+        };
+        let mut bindings = vec![
+            binding("p-2", "prtb-a"),
+            binding("p-1", "prtb-b"),
+            binding("p-2", "prtb-0"),
+            binding("p-1", "prtb-a"),
+        ];
+
+        bindings.sort_by_key(ProjectRoleTemplateBinding::key);
+
+        let keys: Vec<String> = bindings.iter().map(|b| b.key().to_string()).collect();
+        assert_eq!(keys, vec!["p-1/prtb-a", "p-1/prtb-b", "p-2/prtb-0", "p-2/prtb-a"]);
+    }
 }
@@ -0,0 +1,2283 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::future::Future;
+use std::sync::Arc;
+
+use futures::StreamExt;
+use reqwest::Method;
+use serde_json::Value;
+
+use rancher_client::apis::configuration::Configuration;
+use rancher_client::models::{
+    IoCattleManagementv3Project, IoCattleManagementv3ProjectRoleTemplateBinding,
+    IoCattleManagementv3RoleTemplate, IoCattleManagementv3RoleTemplateList,
+};
+
+use crate::config::{ClusterConfig, RancherClusterConfig};
+use crate::diff::DiffReport;
+use crate::error::{ResourceContext, WithResource, WithResourceExt};
+use crate::metadata::stored_content_hash;
+use crate::project::PROJECT_EXCLUDE_PATHS;
+use crate::prtb::{
+    binding_replace_body, create_project_role_template_binding, delete_project_role_template_binding,
+    get_all_namespaced_project_role_template_bindings, get_project_role_template_binding,
+    replace_project_role_template_binding, split_project_name, ProjectRoleTemplateBinding, PRTB_EXCLUDE_PATHS,
+};
+use crate::request::{
+    get_list, list_query, parse_response, send_request, JSON_CONTENT_TYPE, JSON_PATCH_CONTENT_TYPE,
+};
+use crate::paginate::Paginator;
+use crate::client::{RancherClient, RequestOptions};
+use crate::retry::RetryPolicy;
+use crate::rt::{
+    create_role_template, delete_role_template, detect_cycles, normalize_rules, replace_role_template,
+    role_template_replace_body, RoleTemplate, ROLE_TEMPLATES_PATH, RT_EXCLUDE_PATHS,
+};
+use crate::{
+    checked_limit, clean_up_value, continue_token, create_json_patch, try_load_configuration_from_rancher,
+    RancherError, ResourceKind, DEFAULT_PAGE_SIZE,
+};
+
+/// How `reconcile` treats the computed plan
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    /// Compute the plan and apply it
+    Apply,
+    /// Compute the plan only, guaranteed to make no API mutations. Use `Plan::has_changes` to fail CI on drift
+    Check,
+}
+
+/// A single change needed to bring an object to its desired state
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlannedChange {
+    pub kind: ResourceKind,
+    /// The namespace of the object, `None` for cluster scoped kinds
+    pub namespace: Option<String>,
+    pub name: String,
+    /// The JSON patch (RFC 6902) to apply to the object
+    pub patch: Value,
+}
+
+/// The changes needed to bring a cluster to its desired state
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Plan {
+    pub changes: Vec<PlannedChange>,
+}
+
+impl Plan {
+    /// Whether the current state has drifted from the desired state
+    pub fn has_changes(&self) -> bool {
+        !self.changes.is_empty()
+    }
+}
+
+fn object_name(metadata: Option<&rancher_client::models::IoK8sApimachineryPkgApisMetaV1ObjectMeta>) -> Option<&str> {
+    metadata.and_then(|m| m.name.as_deref())
+}
+
+/// The JSON form of an object to diff, without its `exclude_paths`
+fn diff_value<T: serde::Serialize>(object: &T, exclude_paths: &[&str]) -> Result<Value, RancherError> {
+    let mut value = serde_json::to_value(object).map_err(RancherError::Serde)?;
+    clean_up_value(&mut value, exclude_paths);
+    Ok(value)
+}
+
+/// Compute the plan to bring the current cluster state to the desired state
+///
+/// Only objects present in both states are compared, the exclude paths of each kind are pruned
+/// before diffing and role template rules are compared in their canonical order.
+///
+/// # Arguments
+/// * `current_state` - The current state of the cluster
+/// * `desired_state` - The desired state of the cluster
+/// # Returns
+/// * `Plan` - The changes needed, one JSON patch per drifted object
+/// # Errors
+/// * `WithResource<RancherError>` - `Serde` naming the object that could not be serialized
+pub fn plan_cluster(
+    current_state: &RancherClusterConfig,
+    desired_state: &RancherClusterConfig,
+) -> Result<Plan, WithResource<RancherError>> {
+    let mut changes = Vec::new();
+
+    // loop through the role templates and compare them
+    for crt in &current_state.role_templates {
+        let name = match object_name(crt.metadata.as_ref()) {
+            Some(name) => name,
+            None => continue,
+        };
+        // check if the role template exists in the desired state
+        if let Some(desired_rt) = desired_state
+            .role_templates
+            .iter()
+            .find(|drt| object_name(drt.metadata.as_ref()) == Some(name))
+        {
+            // normalize the rules so reordered but equivalent rules do not show up as drift
+            let mut crt = crt.clone();
+            crt.rules = crt.rules.as_deref().map(normalize_rules);
+            let mut desired_rt = desired_rt.clone();
+            desired_rt.rules = desired_rt.rules.as_deref().map(normalize_rules);
+
+            let crtv = diff_value(&crt, RT_EXCLUDE_PATHS).with_resource(ResourceKind::RoleTemplate, None, name)?;
+            let drtv =
+                diff_value(&desired_rt, RT_EXCLUDE_PATHS).with_resource(ResourceKind::RoleTemplate, None, name)?;
+            if let Some(patch) = create_json_patch::<IoCattleManagementv3RoleTemplate>(&crtv, &drtv) {
+                changes.push(PlannedChange {
+                    kind: ResourceKind::RoleTemplate,
+                    namespace: None,
+                    name: name.to_string(),
+                    patch,
+                });
+            }
+        }
+    }
+
+    // loop through the projects and compare them
+    for (c_project_id, (c_project, cprtbs)) in &current_state.projects {
+        // check if the project exists in the desired state
+        if let Some((d_project, dprtbs)) = desired_state.projects.get(c_project_id) {
+            let project_namespace = c_project.metadata.as_ref().and_then(|m| m.namespace.clone());
+            let cpv = diff_value(c_project, PROJECT_EXCLUDE_PATHS).with_resource(
+                ResourceKind::Project,
+                project_namespace.as_deref(),
+                c_project_id,
+            )?;
+            let dpv = diff_value(d_project, PROJECT_EXCLUDE_PATHS).with_resource(
+                ResourceKind::Project,
+                project_namespace.as_deref(),
+                c_project_id,
+            )?;
+            if let Some(patch) = create_json_patch::<IoCattleManagementv3Project>(&cpv, &dpv) {
+                changes.push(PlannedChange {
+                    kind: ResourceKind::Project,
+                    namespace: project_namespace,
+                    name: c_project_id.clone(),
+                    patch,
+                });
+            }
+
+            // loop through the project role template bindings and compare them
+            for cprtb in cprtbs {
+                let name = match object_name(cprtb.metadata.as_ref()) {
+                    Some(name) => name,
+                    None => continue,
+                };
+                if let Some(desired_prtb) = dprtbs
+                    .iter()
+                    .find(|dprtb| object_name(dprtb.metadata.as_ref()) == Some(name))
+                {
+                    let namespace = cprtb.metadata.as_ref().and_then(|m| m.namespace.clone());
+                    let cprtbv = diff_value(cprtb, PRTB_EXCLUDE_PATHS).with_resource(
+                        ResourceKind::ProjectRoleTemplateBinding,
+                        namespace.as_deref(),
+                        name,
+                    )?;
+                    let dprtbv = diff_value(desired_prtb, PRTB_EXCLUDE_PATHS).with_resource(
+                        ResourceKind::ProjectRoleTemplateBinding,
+                        namespace.as_deref(),
+                        name,
+                    )?;
+                    if let Some(patch) = create_json_patch::<IoCattleManagementv3ProjectRoleTemplateBinding>(&cprtbv, &dprtbv) {
+                        changes.push(PlannedChange {
+                            kind: ResourceKind::ProjectRoleTemplateBinding,
+                            namespace,
+                            name: name.to_string(),
+                            patch,
+                        });
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(Plan { changes })
+}
+
+/// Apply a plan to the cluster
+///
+/// In `Mode::Check` this returns immediately without touching the API.
+///
+/// # Arguments
+/// * `client` - The client to send the requests with
+/// * `plan` - The plan to apply
+/// * `mode` - Whether to apply the plan or only check it
+/// # Errors
+/// * `WithResource<RancherError>` - The error of the first patch that failed, naming its object
+#[async_backtrace::framed]
+pub async fn apply_plan(client: &RancherClient, plan: &Plan, mode: Mode) -> Result<(), WithResource<RancherError>> {
+    if mode == Mode::Check {
+        return Ok(());
+    }
+
+    for change in &plan.changes {
+        let path = &change.kind.api_path(change.namespace.as_deref(), &change.name);
+        client
+            .call(|configuration| async move {
+                send_request(&configuration, Method::PATCH, path, JSON_PATCH_CONTENT_TYPE, Some(&change.patch))
+                    .await
+                    .and_then(parse_response)
+            })
+            .await
+            .with_resource(change.kind, change.namespace.as_deref(), &change.name)?;
+    }
+    Ok(())
+}
+
+/// Check that every project referenced by a desired binding exists in the cluster
+///
+/// All offending bindings are reported at once so a plan fails before any object is written
+/// instead of part way through the apply. A binding whose `project_name` names another cluster
+/// references a missing project too.
+///
+/// # Arguments
+/// * `desired` - The desired configuration of the cluster
+/// * `cluster_id` - The ID of the cluster
+/// * `existing_projects` - The IDs of the projects that exist in the cluster
+/// # Errors
+/// * `RancherError` - `Invalid` listing every binding that references a missing project
+pub fn validate_project_references(
+    desired: &ClusterConfig,
+    cluster_id: &str,
+    existing_projects: &HashSet<&str>,
+) -> Result<(), RancherError> {
+    let mut offenders: Vec<String> = desired
+        .projects
+        .iter()
+        .flat_map(|(project_id, (_, bindings))| {
+            bindings.iter().filter_map(move |binding| {
+                let (referenced_cluster, referenced) = match split_project_name(&binding.project_name) {
+                    Ok(ids) => ids,
+                    Err(_) => (cluster_id, project_id.as_str()),
+                };
+                if referenced_cluster != cluster_id {
+                    Some(format!("{} (project {})", binding.id, binding.project_name))
+                } else if !existing_projects.contains(referenced) {
+                    Some(format!("{} (project {})", binding.id, referenced))
+                } else {
+                    None
+                }
+            })
+        })
+        .collect();
+
+    if offenders.is_empty() {
+        return Ok(());
+    }
+    offenders.sort();
+    Err(RancherError::Invalid(format!(
+        "bindings reference missing projects: {}",
+        offenders.join(", ")
+    )))
+}
+
+/// Check that the desired role templates do not inherit each other in a cycle
+///
+/// # Arguments
+/// * `role_templates` - The desired role templates
+/// # Errors
+/// * `RancherError` - `Invalid` listing every inheritance cycle
+pub fn validate_inheritance(role_templates: &[RoleTemplate]) -> Result<(), RancherError> {
+    let cycles = detect_cycles(role_templates);
+    if cycles.is_empty() {
+        return Ok(());
+    }
+    let cycles: Vec<String> = cycles
+        .iter()
+        .map(|cycle| format!("{} -> {}", cycle.join(" -> "), cycle[0]))
+        .collect();
+    Err(RancherError::Invalid(format!(
+        "role templates inherit each other in a cycle: {}",
+        cycles.join(", ")
+    )))
+}
+
+/// Reconcile a cluster against its desired configuration
+///
+/// The current state is loaded from the cluster `cluster_id`, which `desired` must describe.
+///
+/// # Arguments
+/// * `client` - The client to send the requests with
+/// * `cluster_id` - The ID of the cluster to reconcile
+/// * `desired` - The desired configuration of the cluster
+/// * `mode` - `Mode::Apply` to apply the changes, `Mode::Check` to only compute them
+/// # Returns
+/// * `Plan` - The changes that were (or in check mode, would be) applied
+/// # Errors
+/// * `WithResource<RancherError>` - Naming the cluster, `Invalid` when `desired` describes another
+///   cluster, for inheritance cycles among the desired role templates or for bindings that
+///   reference a project missing from the cluster, or the
+///   error that occurred while converting the desired state or loading the current state. Naming
+///   the object, the error of the first patch that failed
+#[async_backtrace::framed]
+pub async fn reconcile(
+    client: &RancherClient,
+    cluster_id: &str,
+    desired: &ClusterConfig,
+    mode: Mode,
+) -> Result<Plan, WithResource<RancherError>> {
+    if desired.cluster.id != cluster_id {
+        return Err(WithResource {
+            resource: ResourceContext::new(ResourceKind::Cluster, None, cluster_id),
+            error: RancherError::Invalid(format!(
+                "the desired configuration is for cluster {}",
+                desired.cluster.id
+            )),
+        });
+    }
+    validate_inheritance(&desired.role_templates).with_resource(ResourceKind::Cluster, None, cluster_id)?;
+    let desired_state = RancherClusterConfig::try_from(desired.clone())
+        .map_err(RancherError::conversion)
+        .with_resource(ResourceKind::Cluster, None, cluster_id)?;
+    let current = client
+        .call(|configuration| async move { try_load_configuration_from_rancher(&configuration, cluster_id).await })
+        .await
+        .with_resource(ResourceKind::Cluster, None, cluster_id)?;
+
+    let existing_projects: HashSet<&str> = current.projects.keys().map(String::as_str).collect();
+    validate_project_references(desired, cluster_id, &existing_projects).with_resource(
+        ResourceKind::Cluster,
+        None,
+        cluster_id,
+    )?;
+
+    let plan = plan_cluster(&current, &desired_state)?;
+    apply_plan(client, &plan, mode).await?;
+    Ok(plan)
+}
+
+/// The names of the objects of a `ReconcileReport`, grouped by outcome
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ReconcileSummary {
+    pub created: Vec<String>,
+    pub updated: Vec<String>,
+    pub deleted: Vec<String>,
+    pub unchanged: Vec<String>,
+    /// Objects that differ from the desired state but were left alone, e.g. locked role templates
+    pub skipped: Vec<String>,
+    /// Objects whose create, update or delete failed, with the error
+    pub failed: Vec<(String, String)>,
+    /// Objects changed then restored by the rollback of a transactional apply
+    pub reverted: Vec<String>,
+}
+
+/// What reconciling did to a single object
+#[derive(Debug, Clone, PartialEq)]
+pub enum Outcome {
+    Created,
+    /// The object was replaced, with the fields that differed from the server
+    Updated(DiffReport),
+    Deleted,
+    Unchanged,
+    /// The object differs from the desired state but was left alone, e.g. a locked role template
+    Skipped,
+    /// The create, update or delete failed, with the error
+    Failed(String),
+    /// The change was applied, then undone when a later change of a transactional apply failed
+    Reverted,
+}
+
+impl Outcome {
+    fn label(&self) -> &'static str {
+        match self {
+            Outcome::Created => "created",
+            Outcome::Updated(_) => "updated",
+            Outcome::Deleted => "deleted",
+            Outcome::Unchanged => "unchanged",
+            Outcome::Skipped => "skipped",
+            Outcome::Failed(_) => "failed",
+            Outcome::Reverted => "reverted",
+        }
+    }
+}
+
+/// The outcome of a single object of a `ReconcileReport`
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResourceOutcome {
+    pub name: String,
+    pub outcome: Outcome,
+}
+
+/// The outcome of reconciling a set of objects, object by object
+///
+/// The objects acted on come first, in plan order, followed by the unchanged then the skipped
+/// objects. Its `Display` renders one row per object, with the changed paths of updates and the
+/// error of failures.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ReconcileReport {
+    pub outcomes: Vec<ResourceOutcome>,
+}
+
+impl ReconcileReport {
+    fn push(&mut self, name: &str, outcome: Outcome) {
+        self.outcomes.push(ResourceOutcome {
+            name: name.to_string(),
+            outcome,
+        });
+    }
+
+    /// The outcome of the object with this name, if the report has one
+    pub fn outcome(&self, name: &str) -> Option<&Outcome> {
+        self.outcomes.iter().find(|o| o.name == name).map(|o| &o.outcome)
+    }
+
+    /// Whether any create, update or delete failed
+    pub fn has_failures(&self) -> bool {
+        self.outcomes.iter().any(|o| matches!(o.outcome, Outcome::Failed(_)))
+    }
+
+    /// The object names grouped by outcome
+    pub fn summary(&self) -> ReconcileSummary {
+        let mut summary = ReconcileSummary::default();
+        for o in &self.outcomes {
+            let name = o.name.clone();
+            match &o.outcome {
+                Outcome::Created => summary.created.push(name),
+                Outcome::Updated(_) => summary.updated.push(name),
+                Outcome::Deleted => summary.deleted.push(name),
+                Outcome::Unchanged => summary.unchanged.push(name),
+                Outcome::Skipped => summary.skipped.push(name),
+                Outcome::Failed(e) => summary.failed.push((name, e.clone())),
+                Outcome::Reverted => summary.reverted.push(name),
+            }
+        }
+        summary
+    }
+}
+
+impl fmt::Display for ReconcileReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let width = self
+            .outcomes
+            .iter()
+            .map(|o| o.name.len())
+            .chain(std::iter::once("RESOURCE".len()))
+            .max()
+            .unwrap_or_default();
+        writeln!(f, "{:<width$}  {:<9}  DETAILS", "RESOURCE", "OUTCOME", width = width)?;
+        for o in &self.outcomes {
+            let details = match &o.outcome {
+                Outcome::Updated(report) => report.paths().join(", "),
+                Outcome::Failed(e) => e.clone(),
+                _ => String::new(),
+            };
+            let row = format!("{:<width$}  {:<9}  {}", o.name, o.outcome.label(), details, width = width);
+            writeln!(f, "{}", row.trim_end())?;
+        }
+        Ok(())
+    }
+}
+
+/// The label key marking objects owned by this crate
+pub const MANAGED_BY_LABEL: &str = "managed-by";
+
+/// The value of `MANAGED_BY_LABEL` on objects owned by this crate
+pub const MANAGED_BY_VALUE: &str = "rancher-cac";
+
+/// Options of the reconcile functions
+#[derive(Debug, Clone)]
+pub struct ReconcileOptions {
+    /// Delete objects that exist on the server but not in the desired state. Only objects carrying
+    /// the `owner_label` are deleted, so hand-created objects survive
+    pub prune: bool,
+    /// The label key and value marking the objects that may be pruned, added to every object
+    /// created or replaced. Its absence on the server is not a change by itself
+    pub owner_label: (String, String),
+    /// The maximum number of create, update and delete requests in flight, at least 1
+    pub concurrency: usize,
+    /// What to do when a replace fails with a conflict (409), applied through `replace_object`
+    pub conflict_strategy: ConflictStrategy,
+    /// Store the content hash of the desired state in `CONTENT_HASH_ANNOTATION` on every object
+    /// written, and treat objects whose stored hash matches the desired one as unchanged without
+    /// diffing them. Changes made on the server since the last apply go unnoticed for those objects
+    pub track_content_hash: bool,
+    /// Apply the actions one at a time and, when one fails, restore the objects already changed to
+    /// their prior state, in reverse order. `concurrency` is ignored. The rollback is best effort:
+    /// restoring can fail in turn, a deleted object comes back with a new UID, and side effects on
+    /// the server, such as the RBAC objects Rancher derives from a binding, are not undone
+    pub transactional: bool,
+    /// How the list requests rate limited by the server (429) are retried
+    pub rate_limit: RetryPolicy,
+    /// The timeout of every list request and of every create, update and delete
+    pub request: RequestOptions,
+}
+
+impl Default for ReconcileOptions {
+    /// Never prune, with `managed-by=rancher-cac` as the ownership label, one request at a time,
+    /// failing on conflict, no content hash, no rollback, and the default `RetryPolicy` and
+    /// `RequestOptions`
+    fn default() -> Self {
+        ReconcileOptions {
+            prune: false,
+            owner_label: (MANAGED_BY_LABEL.to_string(), MANAGED_BY_VALUE.to_string()),
+            concurrency: 1,
+            conflict_strategy: ConflictStrategy::Fail,
+            track_content_hash: false,
+            transactional: false,
+            rate_limit: RetryPolicy::default(),
+            request: RequestOptions::default(),
+        }
+    }
+}
+
+impl ReconcileOptions {
+    /// Options that prune the orphans carrying the default ownership label
+    pub fn pruning() -> Self {
+        ReconcileOptions {
+            prune: true,
+            ..Default::default()
+        }
+    }
+
+    /// Add the `owner_label` to the labels of an object
+    fn add_owner_label(&self, labels: &mut Option<HashMap<String, String>>) {
+        let (key, value) = &self.owner_label;
+        labels.get_or_insert_with(HashMap::new).insert(key.clone(), value.clone());
+    }
+
+    /// Whether an object with these labels, absent from the desired state, should be deleted
+    fn should_prune(&self, labels: Option<&HashMap<String, String>>) -> bool {
+        let (key, value) = &self.owner_label;
+        self.prune && labels.and_then(|labels| labels.get(key)) == Some(value)
+    }
+}
+
+/// What reconciling will do to a single object
+#[derive(Debug, Clone, PartialEq)]
+pub enum Action {
+    Create,
+    /// Replace the object, with the fields that differ from the server
+    Update(DiffReport),
+    Delete,
+}
+
+/// A single action of a `ResourcePlan`
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlannedAction<T> {
+    pub name: String,
+    pub action: Action,
+    /// The desired object, `None` for `Action::Delete`
+    pub object: Option<T>,
+    /// The resource version the object was read at, `None` for `Action::Create`
+    pub resource_version: Option<String>,
+}
+
+/// The actions needed to reconcile a set of objects of one kind
+///
+/// Unlike `Plan`, which patches the objects of a whole cluster, a `ResourcePlan` creates,
+/// replaces and deletes whole objects. Computing it never mutates anything, so it doubles as a
+/// dry run.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResourcePlan<T> {
+    pub actions: Vec<PlannedAction<T>>,
+    /// Objects that already match the desired state
+    pub unchanged: Vec<String>,
+    /// Objects that differ from the desired state but will be left alone
+    pub skipped: Vec<String>,
+}
+
+impl<T> Default for ResourcePlan<T> {
+    fn default() -> Self {
+        ResourcePlan {
+            actions: Vec::new(),
+            unchanged: Vec::new(),
+            skipped: Vec::new(),
+        }
+    }
+}
+
+impl<T> ResourcePlan<T> {
+    /// Whether applying the plan would change nothing
+    pub fn is_empty(&self) -> bool {
+        self.actions.is_empty()
+    }
+
+    fn push(&mut self, name: &str, action: Action, object: Option<T>, resource_version: Option<String>) {
+        self.actions.push(PlannedAction {
+            name: name.to_string(),
+            action,
+            object,
+            resource_version,
+        });
+    }
+
+    /// The report of the plan given the result of each of its actions, in order
+    fn report(&self, results: Vec<Result<(), String>>) -> ReconcileReport {
+        let mut report = ReconcileReport::default();
+        for (planned, result) in self.actions.iter().zip(results) {
+            let outcome = match (result, &planned.action) {
+                (Err(e), _) => Outcome::Failed(e),
+                (Ok(()), Action::Create) => Outcome::Created,
+                (Ok(()), Action::Update(diff)) => Outcome::Updated(diff.clone()),
+                (Ok(()), Action::Delete) => Outcome::Deleted,
+            };
+            report.push(&planned.name, outcome);
+        }
+        for name in &self.unchanged {
+            report.push(name, Outcome::Unchanged);
+        }
+        for name in &self.skipped {
+            report.push(name, Outcome::Skipped);
+        }
+        report
+    }
+
+    /// Run every action of the plan, at most `concurrency` at a time
+    ///
+    /// A failed action does not stop the others, every result ends up in the report.
+    async fn run<'a, F, Fut>(&'a self, concurrency: usize, action: F) -> ReconcileReport
+    where
+        F: Fn(&'a PlannedAction<T>) -> Fut,
+        Fut: Future<Output = Result<(), String>>,
+    {
+        let mut results: Vec<(usize, Result<(), String>)> = futures::stream::iter(self.actions.iter().enumerate())
+            .map(|(index, planned)| {
+                let result = action(planned);
+                async move { (index, result.await) }
+            })
+            .buffer_unordered(concurrency.max(1))
+            .collect()
+            .await;
+        results.sort_by_key(|(index, _)| *index);
+        self.report(results.into_iter().map(|(_, result)| result).collect())
+    }
+
+    /// Run the actions of the plan one at a time, rolling back the applied ones when one fails
+    ///
+    /// `snapshot` reads the object an update or delete is about to change. When an action fails,
+    /// `restore` is called for every action already applied, in reverse order, with the object as
+    /// it was before, `None` for a create. Restored objects are reported as `Outcome::Reverted`,
+    /// or as `Outcome::Failed` when restoring them failed, and the actions not run as
+    /// `Outcome::Skipped`.
+    async fn run_transactional<'a, S, SFut, A, AFut, R, RFut>(
+        &'a self,
+        snapshot: S,
+        action: A,
+        restore: R,
+    ) -> ReconcileReport
+    where
+        S: Fn(&'a PlannedAction<T>) -> SFut,
+        SFut: Future<Output = Result<Option<T>, String>>,
+        A: Fn(&'a PlannedAction<T>) -> AFut,
+        AFut: Future<Output = Result<(), String>>,
+        R: Fn(&'a PlannedAction<T>, Option<T>) -> RFut,
+        RFut: Future<Output = Result<(), String>>,
+    {
+        let mut applied: Vec<(usize, Option<T>)> = Vec::new();
+        let mut failure = None;
+        for (index, planned) in self.actions.iter().enumerate() {
+            let prior = match planned.action {
+                Action::Create => Ok(None),
+                _ => snapshot(planned).await,
+            };
+            let result = match prior {
+                Ok(prior) => action(planned).await.map(|()| prior),
+                Err(e) => Err(e),
+            };
+            match result {
+                Ok(prior) => applied.push((index, prior)),
+                Err(e) => {
+                    failure = Some((index, e));
+                    break;
+                }
+            }
+        }
+
+        let (failed_index, error) = match failure {
+            Some(failure) => failure,
+            None => return self.report(vec![Ok(()); self.actions.len()]),
+        };
+        let mut outcomes: Vec<Outcome> = vec![Outcome::Skipped; self.actions.len()];
+        outcomes[failed_index] = Outcome::Failed(error);
+        for (index, prior) in applied.into_iter().rev() {
+            let planned = &self.actions[index];
+            outcomes[index] = match restore(planned, prior).await {
+                Ok(()) => Outcome::Reverted,
+                Err(e) => Outcome::Failed(format!("Rollback failed: {}", e)),
+            };
+        }
+
+        let mut report = ReconcileReport::default();
+        for (planned, outcome) in self.actions.iter().zip(outcomes) {
+            report.push(&planned.name, outcome);
+        }
+        for name in &self.unchanged {
+            report.push(name, Outcome::Unchanged);
+        }
+        for name in &self.skipped {
+            report.push(name, Outcome::Skipped);
+        }
+        report
+    }
+}
+
+/// Whether both objects carry the same content hash annotation
+fn same_content_hash(
+    desired: Option<&HashMap<String, String>>,
+    actual: Option<&HashMap<String, String>>,
+) -> bool {
+    match stored_content_hash(desired) {
+        Some(hash) => stored_content_hash(actual) == Some(hash),
+        None => false,
+    }
+}
+
+/// Compute the actions to bring the current bindings of a project to the desired set
+///
+/// Bindings are matched by `id`. Missing bindings are created, bindings that `prtb::diff` reports
+/// as changed are replaced at the resource version read from the server, and bindings that only
+/// exist on the server are deleted when `options` prunes them. Created and replaced bindings carry
+/// the `owner_label` of `options`.
+fn binding_plan(
+    current: &[ProjectRoleTemplateBinding],
+    desired: &[ProjectRoleTemplateBinding],
+    options: &ReconcileOptions,
+) -> ResourcePlan<ProjectRoleTemplateBinding> {
+    let mut plan = ResourcePlan::default();
+    for binding in desired {
+        let mut binding = if options.track_content_hash {
+            crate::prtb::with_content_hash(binding)
+        } else {
+            binding.clone()
+        };
+        options.add_owner_label(&mut binding.labels);
+        let binding = &binding;
+        match current.iter().find(|actual| actual.id == binding.id) {
+            None => plan.push(&binding.id, Action::Create, Some(binding.clone()), None),
+            Some(actual)
+                if options.track_content_hash
+                    && same_content_hash(binding.annotations.as_ref(), actual.annotations.as_ref()) =>
+            {
+                plan.unchanged.push(binding.id.clone())
+            }
+            Some(actual) => {
+                // the missing owner label alone is not a change
+                let mut owned_actual = actual.clone();
+                options.add_owner_label(&mut owned_actual.labels);
+                match crate::prtb::diff(binding, &owned_actual) {
+                    Some(report) => {
+                        let mut binding = binding.clone();
+                        binding.resource_version = actual.resource_version.clone();
+                        let resource_version = binding.resource_version.clone();
+                        plan.push(&actual.id, Action::Update(report), Some(binding), resource_version)
+                    }
+                    None => plan.unchanged.push(binding.id.clone()),
+                }
+            }
+        }
+    }
+
+    for actual in current {
+        if options.should_prune(actual.labels.as_ref()) && !desired.iter().any(|binding| binding.id == actual.id) {
+            plan.push(&actual.id, Action::Delete, None, actual.resource_version.clone());
+        }
+    }
+    plan
+}
+
+/// Compute the actions to bring the current role templates to the desired set
+///
+/// Role templates are matched by `id`. Missing role templates are created, role templates that
+/// `rt::diff` reports as changed are replaced, and role templates that only exist on the server
+/// are deleted when `options` prunes them. Created and replaced role templates carry the
+/// `owner_label` of `options`. Templates that are builtin on the server are managed
+/// by Rancher and never touched. Locked templates are never modified or deleted, when they differ
+/// from the desired state or would be pruned they are reported as skipped.
+fn role_template_plan(
+    current: &[(RoleTemplate, Option<String>)],
+    desired: &[RoleTemplate],
+    options: &ReconcileOptions,
+) -> ResourcePlan<RoleTemplate> {
+    let mut plan = ResourcePlan::default();
+    for rt in desired {
+        let mut rt = if options.track_content_hash {
+            crate::rt::with_content_hash(rt)
+        } else {
+            rt.clone()
+        };
+        options.add_owner_label(&mut rt.labels);
+        let rt = &rt;
+        let (actual, resource_version) = match current.iter().find(|(actual, _)| actual.id == rt.id) {
+            Some(found) => found,
+            None => {
+                plan.push(&rt.id, Action::Create, Some(rt.clone()), None);
+                continue;
+            }
+        };
+
+        if options.track_content_hash && same_content_hash(rt.annotations.as_ref(), actual.annotations.as_ref()) {
+            plan.unchanged.push(rt.id.clone());
+            continue;
+        }
+        // the missing owner label alone is not a change
+        let mut owned_actual = actual.clone();
+        options.add_owner_label(&mut owned_actual.labels);
+        match crate::rt::diff(rt, &owned_actual) {
+            None => plan.unchanged.push(rt.id.clone()),
+            Some(_) if actual.builtin == Some(true) || actual.locked == Some(true) => {
+                plan.skipped.push(rt.id.clone())
+            }
+            Some(report) => plan.push(&rt.id, Action::Update(report), Some(rt.clone()), resource_version.clone()),
+        }
+    }
+
+    for (actual, resource_version) in current {
+        if actual.builtin == Some(true)
+            || !options.should_prune(actual.labels.as_ref())
+            || desired.iter().any(|rt| rt.id == actual.id)
+        {
+            continue;
+        }
+        if actual.locked == Some(true) {
+            plan.skipped.push(actual.id.clone());
+        } else {
+            plan.push(&actual.id, Action::Delete, None, resource_version.clone());
+        }
+    }
+    plan
+}
+
+/// Plan the reconciliation of the project role template bindings of a project, without applying it
+///
+/// # Arguments
+/// * `client` - The client to send the requests with
+/// * `project_id` - The ID of the project (namespace) to reconcile
+/// * `desired` - Every binding the project should have
+/// * `options` - Whether to delete owned bindings missing from `desired`
+/// # Returns
+/// * `ResourcePlan<ProjectRoleTemplateBinding>` - The bindings to create, update and delete
+/// # Errors
+/// * `RancherError` - `Invalid` naming a desired binding that belongs to another project, or the
+///   error that occurred while listing the bindings
+#[async_backtrace::framed]
+pub async fn plan_project_role_template_bindings(
+    client: &RancherClient,
+    project_id: &str,
+    desired: &[ProjectRoleTemplateBinding],
+    options: &ReconcileOptions,
+) -> Result<ResourcePlan<ProjectRoleTemplateBinding>, RancherError> {
+    if let Some(binding) = desired.iter().find(|b| b.resolved_namespace() != project_id) {
+        return Err(RancherError::Invalid(format!(
+            "binding {} belongs to namespace {}, not project {}",
+            binding.id,
+            binding.resolved_namespace(),
+            project_id
+        )));
+    }
+
+    let (current, _) = client
+        .call(|configuration| async move {
+            get_all_namespaced_project_role_template_bindings(
+                &configuration,
+                project_id,
+                None,
+                None,
+                &options.request,
+            )
+            .await
+        })
+        .await?;
+    Ok(binding_plan(&current, desired, options))
+}
+
+/// The message of an action that failed, naming the object it failed on
+fn action_error<E: fmt::Display>(verb: &str, error: WithResource<E>) -> String {
+    format!("Failed to {} {}", verb, error)
+}
+
+/// Create, replace or delete a single binding of a plan
+async fn apply_binding_action(
+    client: &RancherClient,
+    project_id: &str,
+    planned: &PlannedAction<ProjectRoleTemplateBinding>,
+    conflict_strategy: &ConflictStrategy,
+) -> Result<(), String> {
+    let name = planned.name.as_str();
+    match (&planned.action, &planned.object) {
+        (Action::Create, Some(binding)) => client
+            .call(|configuration| async move {
+                create_project_role_template_binding(&configuration, binding)
+                    .await
+                    .map_err(WithResource::into_error)
+            })
+            .await
+            .map(|_| ())
+            .with_resource(ResourceKind::ProjectRoleTemplateBinding, Some(project_id), &planned.name)
+            .map_err(|e| action_error("create", e)),
+        (Action::Update(_), Some(binding)) => {
+            let namespace = &binding.resolved_namespace();
+            let options = &UpdateOptions {
+                conflict_strategy: conflict_strategy.clone(),
+            };
+            let result = async {
+                let body = &serde_json::to_value(binding_replace_body(binding)?).map_err(RancherError::Serde)?;
+                client
+                    .call(|configuration| async move {
+                        replace_object(
+                            &configuration,
+                            ResourceKind::ProjectRoleTemplateBinding,
+                            Some(namespace),
+                            name,
+                            body,
+                            options,
+                        )
+                        .await
+                    })
+                    .await
+            };
+            result
+                .await
+                .map(|_| ())
+                .with_resource(ResourceKind::ProjectRoleTemplateBinding, Some(project_id), &planned.name)
+                .map_err(|e| action_error("replace", e))
+        }
+        (Action::Delete, _) => match client
+            .call(|configuration| async move {
+                delete_project_role_template_binding(&configuration, project_id, name)
+                    .await
+                    .map_err(WithResource::into_error)
+            })
+            .await
+        {
+            // already gone, nothing left to do
+            Ok(()) | Err(RancherError::NotFound) => Ok(()),
+            result => result
+                .with_resource(ResourceKind::ProjectRoleTemplateBinding, Some(project_id), &planned.name)
+                .map_err(|e| action_error("delete", e)),
+        },
+        (_, None) => Err(format!("No desired object planned for binding {}", planned.name)),
+    }
+}
+
+/// Read a binding before a transactional apply changes it
+async fn snapshot_binding(
+    client: &RancherClient,
+    project_id: &str,
+    planned: &PlannedAction<ProjectRoleTemplateBinding>,
+) -> Result<Option<ProjectRoleTemplateBinding>, String> {
+    let name = planned.name.as_str();
+    client
+        .call(|configuration| async move { get_project_role_template_binding(&configuration, project_id, name).await })
+        .await
+        .with_resource(ResourceKind::ProjectRoleTemplateBinding, Some(project_id), &planned.name)
+        .map_err(|e| action_error("read", e))
+}
+
+/// Undo an action of a transactional apply, given the binding as it was before
+async fn restore_binding(
+    client: &RancherClient,
+    project_id: &str,
+    planned: &PlannedAction<ProjectRoleTemplateBinding>,
+    prior: Option<ProjectRoleTemplateBinding>,
+) -> Result<(), String> {
+    let name = planned.name.as_str();
+    match (&planned.action, prior) {
+        (Action::Create, _) => match client
+            .call(|configuration| async move {
+                delete_project_role_template_binding(&configuration, project_id, name)
+                    .await
+                    .map_err(WithResource::into_error)
+            })
+            .await
+        {
+            Ok(()) | Err(RancherError::NotFound) => Ok(()),
+            result => result
+                .with_resource(ResourceKind::ProjectRoleTemplateBinding, Some(project_id), &planned.name)
+                .map_err(|e| action_error("delete", e)),
+        },
+        (Action::Update(_), Some(mut prior)) => {
+            prior.resource_version = snapshot_binding(client, project_id, planned)
+                .await?
+                .and_then(|latest| latest.resource_version);
+            let prior = &prior;
+            client
+                .call(|configuration| async move {
+                    replace_project_role_template_binding(&configuration, prior)
+                        .await
+                        .map_err(WithResource::into_error)
+                })
+                .await
+                .map(|_| ())
+                .with_resource(ResourceKind::ProjectRoleTemplateBinding, Some(project_id), &planned.name)
+                .map_err(|e| action_error("replace", e))
+        }
+        (Action::Delete, Some(mut prior)) => {
+            prior.resource_version = None;
+            let prior = &prior;
+            client
+                .call(|configuration| async move {
+                    create_project_role_template_binding(&configuration, prior)
+                        .await
+                        .map_err(WithResource::into_error)
+                })
+                .await
+                .map(|_| ())
+                .with_resource(ResourceKind::ProjectRoleTemplateBinding, Some(project_id), &planned.name)
+                .map_err(|e| action_error("create", e))
+        }
+        (_, None) => Err(format!("No prior state recorded for binding {}", planned.name)),
+    }
+}
+
+/// Apply a plan computed by `plan_project_role_template_bindings`
+///
+/// Up to `options.concurrency` requests are in flight at once. A failing binding does not stop the
+/// others, it is reported as `Outcome::Failed`. Bindings already deleted on the server
+/// count as deleted. With `options.transactional`, the bindings are changed one at a time and the
+/// first failure rolls back the bindings already changed.
+///
+/// # Arguments
+/// * `client` - The client to send the requests with
+/// * `project_id` - The ID of the project (namespace) the plan was computed for
+/// * `plan` - The plan to apply
+/// * `options` - How many requests to send concurrently
+/// # Returns
+/// * `ReconcileReport` - The outcome of every binding
+#[async_backtrace::framed]
+pub async fn apply_project_role_template_binding_plan(
+    client: &RancherClient,
+    project_id: &str,
+    plan: &ResourcePlan<ProjectRoleTemplateBinding>,
+    options: &ReconcileOptions,
+) -> ReconcileReport {
+    let action = |planned| {
+        with_action_timeout(
+            &options.request,
+            apply_binding_action(client, project_id, planned, &options.conflict_strategy),
+        )
+    };
+    if options.transactional {
+        plan.run_transactional(
+            |planned| snapshot_binding(client, project_id, planned),
+            action,
+            |planned, prior| restore_binding(client, project_id, planned, prior),
+        )
+        .await
+    } else {
+        plan.run(options.concurrency, action).await
+    }
+}
+
+/// Reconcile the project role template bindings of a project against a desired set
+///
+/// Computes the plan with `plan_project_role_template_bindings` and applies it. Unchanged bindings
+/// are not written, so their resource version is left alone.
+///
+/// # Arguments
+/// * `client` - The client to send the requests with
+/// * `project_id` - The ID of the project (namespace) to reconcile
+/// * `desired` - Every binding the project should have
+/// * `options` - Whether to delete owned bindings missing from `desired`, and how many requests to
+///   send concurrently
+/// # Returns
+/// * `ReconcileReport` - The outcome of every binding
+/// # Errors
+/// * `RancherError` - `Invalid` naming a desired binding that belongs to another project, or the
+///   error that occurred while listing the bindings
+#[async_backtrace::framed]
+pub async fn reconcile_project_role_template_bindings(
+    client: &RancherClient,
+    project_id: &str,
+    desired: &[ProjectRoleTemplateBinding],
+    options: &ReconcileOptions,
+) -> Result<ReconcileReport, RancherError> {
+    let plan = plan_project_role_template_bindings(client, project_id, desired, options).await?;
+    Ok(apply_project_role_template_binding_plan(client, project_id, &plan, options).await)
+}
+
+/// Fail an action with the message of `RancherError::Timeout` when it outlasts the timeout of
+/// `request`
+async fn with_action_timeout<Fut>(request: &RequestOptions, action: Fut) -> Result<(), String>
+where
+    Fut: Future<Output = Result<(), String>>,
+{
+    match request.timeout {
+        Some(timeout) => tokio::time::timeout(timeout, action)
+            .await
+            .unwrap_or_else(|_| Err(RancherError::Timeout(timeout).to_string())),
+        None => action.await,
+    }
+}
+
+/// Get every role template along with the resource version it was read at, retrying the pages
+/// rate limited by the server or failing transiently
+async fn get_role_templates_with_versions(
+    client: &RancherClient,
+    options: &ReconcileOptions,
+) -> Result<Vec<(RoleTemplate, Option<String>)>, RancherError> {
+    let limit = checked_limit(Some(DEFAULT_PAGE_SIZE), &options.request)?;
+    let items = Paginator::new(move |continue_: Option<String>| async move {
+        let query = &list_query(None, None, limit, continue_.as_deref());
+        let list: IoCattleManagementv3RoleTemplateList = client
+            .call(|configuration| async move {
+                get_list(&configuration, ROLE_TEMPLATES_PATH, query, &options.rate_limit, &options.request).await
+            })
+            .await?;
+        Ok::<_, RancherError>((list.items, continue_token(list.metadata.as_deref())))
+    })
+    .collect_all()
+    .await?;
+
+    items
+        .into_iter()
+        .map(|item| {
+            let resource_version = item.metadata.as_ref().and_then(|m| m.resource_version.clone());
+            RoleTemplate::try_from(item)
+                .map(|rt| (rt, resource_version))
+                .map_err(RancherError::conversion)
+        })
+        .collect()
+}
+
+/// Plan the reconciliation of the role templates of the cluster, without applying it
+///
+/// Builtin templates are never touched and locked templates that differ from the desired state
+/// are reported as skipped.
+///
+/// # Arguments
+/// * `client` - The client to send the requests with
+/// * `desired` - Every role template the cluster should have
+/// * `options` - Whether to delete owned role templates missing from `desired`
+/// # Returns
+/// * `ResourcePlan<RoleTemplate>` - The role templates to create, update and delete
+/// # Errors
+/// * `RancherError` - `Invalid` listing the inheritance cycles among `desired`, or the error that
+///   occurred while listing the role templates
+#[async_backtrace::framed]
+pub async fn plan_role_templates(
+    client: &RancherClient,
+    desired: &[RoleTemplate],
+    options: &ReconcileOptions,
+) -> Result<ResourcePlan<RoleTemplate>, RancherError> {
+    validate_inheritance(desired)?;
+    let current = get_role_templates_with_versions(client, options).await?;
+    Ok(role_template_plan(&current, desired, options))
+}
+
+/// Get a single role template along with the resource version it was read at
+async fn get_role_template_with_version(
+    client: &RancherClient,
+    name: &str,
+) -> Result<(RoleTemplate, Option<String>), RancherError> {
+    let path = &ResourceKind::RoleTemplate.api_path(None, name);
+    let latest = client
+        .call(|configuration| async move { object_request(&configuration, Method::GET, path, None).await })
+        .await?;
+    let latest: IoCattleManagementv3RoleTemplate = serde_json::from_value(latest).map_err(RancherError::Serde)?;
+    let resource_version = latest.metadata.as_ref().and_then(|m| m.resource_version.clone());
+    RoleTemplate::try_from(latest)
+        .map(|rt| (rt, resource_version))
+        .map_err(RancherError::conversion)
+}
+
+/// Create, replace or delete a single role template of a plan
+async fn apply_role_template_action(
+    client: &RancherClient,
+    planned: &PlannedAction<RoleTemplate>,
+    conflict_strategy: &ConflictStrategy,
+) -> Result<(), String> {
+    let name = planned.name.as_str();
+    match (&planned.action, &planned.object) {
+        (Action::Create, Some(rt)) => client
+            .call(|configuration| async move {
+                create_role_template(&configuration, rt)
+                    .await
+                    .map_err(WithResource::into_error)
+            })
+            .await
+            .map(|_| ())
+            .with_resource(ResourceKind::RoleTemplate, None, &planned.name)
+            .map_err(|e| action_error("create", e)),
+        (Action::Update(_), Some(rt)) => {
+            let options = &UpdateOptions {
+                conflict_strategy: conflict_strategy.clone(),
+            };
+            let result = async {
+                let body = role_template_replace_body(rt, planned.resource_version.as_deref())?;
+                let body = &serde_json::to_value(body).map_err(RancherError::Serde)?;
+                client
+                    .call(|configuration| async move {
+                        replace_object(&configuration, ResourceKind::RoleTemplate, None, name, body, options).await
+                    })
+                    .await
+            };
+            result
+                .await
+                .map(|_| ())
+                .with_resource(ResourceKind::RoleTemplate, None, &planned.name)
+                .map_err(|e| action_error("replace", e))
+        }
+        (Action::Delete, _) => match client
+            .call(|configuration| async move {
+                delete_role_template(&configuration, name)
+                    .await
+                    .map_err(WithResource::into_error)
+            })
+            .await
+        {
+            // already gone, nothing left to do
+            Ok(()) | Err(RancherError::NotFound) => Ok(()),
+            result => result
+                .with_resource(ResourceKind::RoleTemplate, None, &planned.name)
+                .map_err(|e| action_error("delete", e)),
+        },
+        (_, None) => Err(format!("No desired object planned for role template {}", planned.name)),
+    }
+}
+
+/// Read a role template before a transactional apply changes it
+async fn snapshot_role_template(
+    client: &RancherClient,
+    planned: &PlannedAction<RoleTemplate>,
+) -> Result<Option<RoleTemplate>, String> {
+    get_role_template_with_version(client, &planned.name)
+        .await
+        .map(|(rt, _)| Some(rt))
+        .with_resource(ResourceKind::RoleTemplate, None, &planned.name)
+        .map_err(|e| action_error("read", e))
+}
+
+/// Undo an action of a transactional apply, given the role template as it was before
+async fn restore_role_template(
+    client: &RancherClient,
+    planned: &PlannedAction<RoleTemplate>,
+    prior: Option<RoleTemplate>,
+) -> Result<(), String> {
+    let name = planned.name.as_str();
+    match (&planned.action, prior) {
+        (Action::Create, _) => match client
+            .call(|configuration| async move {
+                delete_role_template(&configuration, name)
+                    .await
+                    .map_err(WithResource::into_error)
+            })
+            .await
+        {
+            Ok(()) | Err(RancherError::NotFound) => Ok(()),
+            result => result
+                .with_resource(ResourceKind::RoleTemplate, None, &planned.name)
+                .map_err(|e| action_error("delete", e)),
+        },
+        (Action::Update(_), Some(prior)) => {
+            let (_, resource_version) = get_role_template_with_version(client, name)
+                .await
+                .with_resource(ResourceKind::RoleTemplate, None, &planned.name)
+                .map_err(|e| action_error("read", e))?;
+            let (prior, resource_version) = (&prior, resource_version.as_deref());
+            client
+                .call(|configuration| async move {
+                    replace_role_template(&configuration, prior, resource_version)
+                        .await
+                        .map_err(WithResource::into_error)
+                })
+                .await
+                .map(|_| ())
+                .with_resource(ResourceKind::RoleTemplate, None, &planned.name)
+                .map_err(|e| action_error("replace", e))
+        }
+        (Action::Delete, Some(prior)) => {
+            let prior = &prior;
+            client
+                .call(|configuration| async move {
+                    create_role_template(&configuration, prior)
+                        .await
+                        .map_err(WithResource::into_error)
+                })
+                .await
+                .map(|_| ())
+                .with_resource(ResourceKind::RoleTemplate, None, &planned.name)
+                .map_err(|e| action_error("create", e))
+        }
+        (_, None) => Err(format!("No prior state recorded for role template {}", planned.name)),
+    }
+}
+
+/// Apply a plan computed by `plan_role_templates`
+///
+/// Up to `options.concurrency` requests are in flight at once. A failing role template does not
+/// stop the others, it is reported as `Outcome::Failed`. Role templates already deleted
+/// on the server count as deleted. With `options.transactional`, the role templates are changed
+/// one at a time and the first failure rolls back the role templates already changed.
+///
+/// # Arguments
+/// * `client` - The client to send the requests with
+/// * `plan` - The plan to apply
+/// * `options` - How many requests to send concurrently
+/// # Returns
+/// * `ReconcileReport` - The outcome of every role template
+#[async_backtrace::framed]
+pub async fn apply_role_template_plan(
+    client: &RancherClient,
+    plan: &ResourcePlan<RoleTemplate>,
+    options: &ReconcileOptions,
+) -> ReconcileReport {
+    let action = |planned| {
+        with_action_timeout(
+            &options.request,
+            apply_role_template_action(client, planned, &options.conflict_strategy),
+        )
+    };
+    if options.transactional {
+        plan.run_transactional(
+            |planned| snapshot_role_template(client, planned),
+            action,
+            |planned, prior| restore_role_template(client, planned, prior),
+        )
+        .await
+    } else {
+        plan.run(options.concurrency, action).await
+    }
+}
+
+/// Reconcile the role templates of the cluster against a desired set
+///
+/// Computes the plan with `plan_role_templates` and applies it, see `plan_role_templates` for how
+/// builtin and locked templates are handled.
+///
+/// # Arguments
+/// * `client` - The client to send the requests with
+/// * `desired` - Every role template the cluster should have
+/// * `options` - Whether to delete owned role templates missing from `desired`, and how many
+///   requests to send concurrently
+/// # Returns
+/// * `ReconcileReport` - The outcome of every role template
+/// # Errors
+/// * `RancherError` - `Invalid` listing the inheritance cycles among `desired`, or the error that
+///   occurred while listing the role templates
+#[async_backtrace::framed]
+pub async fn reconcile_role_templates(
+    client: &RancherClient,
+    desired: &[RoleTemplate],
+    options: &ReconcileOptions,
+) -> Result<ReconcileReport, RancherError> {
+    let plan = plan_role_templates(client, desired, options).await?;
+    Ok(apply_role_template_plan(client, &plan, options).await)
+}
+
+/// Resolves a write conflict by combining the desired object with the latest object on the server
+pub trait Merger: Send + Sync {
+    /// Return the object to write, given the desired object and the latest version on the server
+    fn merge(&self, desired: &Value, latest: &Value) -> Value;
+}
+
+impl<F> Merger for F
+where
+    F: Fn(&Value, &Value) -> Value + Send + Sync,
+{
+    fn merge(&self, desired: &Value, latest: &Value) -> Value {
+        self(desired, latest)
+    }
+}
+
+/// What to do when a replace is rejected because the object changed on the server (409 Conflict)
+#[derive(Clone, Default)]
+pub enum ConflictStrategy {
+    /// Return the conflict error to the caller
+    #[default]
+    Fail,
+    /// Re-fetch the object and re-apply the desired state on top of the latest resource version
+    RetryWithLatest,
+    /// Re-fetch the object and write whatever the merger returns
+    Merge(Arc<dyn Merger>),
+}
+
+impl std::fmt::Debug for ConflictStrategy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConflictStrategy::Fail => write!(f, "Fail"),
+            ConflictStrategy::RetryWithLatest => write!(f, "RetryWithLatest"),
+            ConflictStrategy::Merge(_) => write!(f, "Merge(..)"),
+        }
+    }
+}
+
+/// Options for updates issued during apply
+#[derive(Debug, Clone, Default)]
+pub struct UpdateOptions {
+    pub conflict_strategy: ConflictStrategy,
+}
+
+/// Copy the resource version of `latest` onto `object`
+fn with_resource_version_of(mut object: Value, latest: &Value) -> Value {
+    let resource_version = latest
+        .pointer("/metadata/resourceVersion")
+        .cloned()
+        .unwrap_or(Value::Null);
+    if let Some(metadata) = object
+        .as_object_mut()
+        .map(|o| o.entry("metadata").or_insert_with(|| Value::Object(Default::default())))
+        .and_then(Value::as_object_mut)
+    {
+        metadata.insert("resourceVersion".to_string(), resource_version);
+    }
+    object
+}
+
+/// Send a request for an object at `path` and parse the JSON body of the response
+async fn object_request(
+    configuration: &Configuration,
+    method: Method,
+    path: &str,
+    body: Option<&Value>,
+) -> Result<Value, RancherError> {
+    let response_content = send_request(configuration, method, path, JSON_CONTENT_TYPE, body).await?;
+    Ok(parse_response(response_content)?)
+}
+
+/// Replace an object, resolving a resource version conflict with the given strategy
+///
+/// The conflict is resolved at most once, a second conflict is returned to the caller.
+///
+/// # Arguments
+/// * `configuration` - The configuration to use for the requests
+/// * `kind` - The kind of the object
+/// * `namespace` - The namespace of the object, `None` for cluster scoped kinds
+/// * `name` - The name of the object
+/// * `desired` - The full desired object, including `metadata.resourceVersion`
+/// * `options` - The update options
+/// # Returns
+/// * `Value` - The object as written by the server
+/// # Errors
+/// * `RancherError` - `Conflict` when the object changed on the server and the strategy is
+///   `ConflictStrategy::Fail` or the retry conflicted again, or the error that occurred while
+///   replacing or re-fetching the object
+#[async_backtrace::framed]
+pub async fn replace_object(
+    configuration: &Configuration,
+    kind: ResourceKind,
+    namespace: Option<&str>,
+    name: &str,
+    desired: &Value,
+    options: &UpdateOptions,
+) -> Result<Value, RancherError> {
+    let path = kind.api_path(namespace, name);
+
+    match object_request(configuration, Method::PUT, &path, Some(desired)).await {
+        Err(conflict @ RancherError::Conflict { .. }) => {
+            let retry = match &options.conflict_strategy {
+                ConflictStrategy::Fail => return Err(conflict),
+                ConflictStrategy::RetryWithLatest => {
+                    let latest = object_request(configuration, Method::GET, &path, None).await?;
+                    with_resource_version_of(desired.clone(), &latest)
+                }
+                ConflictStrategy::Merge(merger) => {
+                    let latest = object_request(configuration, Method::GET, &path, None).await?;
+                    with_resource_version_of(merger.merge(desired, &latest), &latest)
+                }
+            };
+            object_request(configuration, Method::PUT, &path, Some(&retry)).await
+        }
+        other => other,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    use rancher_client::models::{IoCattleManagementv3Cluster, IoK8sApimachineryPkgApisMetaV1ObjectMeta};
+
+    use crate::cluster::Cluster;
+    use crate::project::Project;
+    use crate::test_support::{role_template_json, MockRancher};
+
+    fn sample_role_template(description: &str) -> IoCattleManagementv3RoleTemplate {
+        IoCattleManagementv3RoleTemplate {
+            metadata: Some(IoK8sApimachineryPkgApisMetaV1ObjectMeta {
+                name: Some("rt-1".to_string()),
+                ..Default::default()
+            }),
+            description: Some(description.to_string()),
+            ..Default::default()
+        }
+    }
+
+    fn sample_config(description: &str) -> RancherClusterConfig {
+        RancherClusterConfig {
+            cluster: IoCattleManagementv3Cluster::default(),
+            role_templates: vec![sample_role_template(description)],
+            projects: HashMap::new(),
+        }
+    }
+
+    #[test]
+    fn test_has_changes_reflects_drift() {
+        let no_drift = plan_cluster(&sample_config("a"), &sample_config("a")).unwrap();
+        assert!(!no_drift.has_changes());
+
+        let drift = plan_cluster(&sample_config("a"), &sample_config("b")).unwrap();
+        assert!(drift.has_changes());
+        assert_eq!(drift.changes[0].kind, ResourceKind::RoleTemplate);
+        assert_eq!(drift.changes[0].name, "rt-1");
+    }
+
+    #[tokio::test]
+    async fn test_check_mode_makes_no_mutations() {
+        let rancher = MockRancher::start().await;
+        rancher
+            .expect("PATCH", &ResourceKind::RoleTemplate.api_path(None, "rt-1"))
+            .expect_calls(0)
+            .returning_json(200, serde_json::json!({}))
+            .await;
+
+        let plan = plan_cluster(&sample_config("a"), &sample_config("b")).unwrap();
+        apply_plan(&rancher.client(), &plan, Mode::Check)
+            .await
+            .unwrap();
+        assert!(plan.has_changes());
+    }
+
+    fn raw_role_template(resource_version: &str, description: &str) -> Value {
+        serde_json::json!({
+            "apiVersion": "management.cattle.io/v3",
+            "kind": "RoleTemplate",
+            "metadata": { "name": "rt-1", "resourceVersion": resource_version },
+            "description": description,
+        })
+    }
+
+    #[tokio::test]
+    async fn test_conflict_with_fail_strategy_returns_error() {
+        let rancher = MockRancher::start().await;
+        let path = ResourceKind::RoleTemplate.api_path(None, "rt-1");
+        rancher
+            .expect("PUT", &path)
+            .returning_status(409, "Conflict", "the object has been modified")
+            .await;
+        rancher
+            .expect("GET", &path)
+            .expect_calls(0)
+            .returning_json(200, raw_role_template("2", "remote"))
+            .await;
+
+        let result = replace_object(
+            &rancher.configuration(),
+            ResourceKind::RoleTemplate,
+            None,
+            "rt-1",
+            &raw_role_template("1", "local"),
+            &UpdateOptions::default(),
+        )
+        .await;
+
+        match result {
+            Err(RancherError::Conflict { message, .. }) => assert_eq!(message, "the object has been modified"),
+            other => panic!("expected a conflict, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_conflict_with_retry_with_latest_succeeds() {
+        let rancher = MockRancher::start().await;
+        let path = ResourceKind::RoleTemplate.api_path(None, "rt-1");
+        rancher
+            .expect("PUT", &path)
+            .with_body_partial(serde_json::json!({ "metadata": { "resourceVersion": "1" } }))
+            .returning_status(409, "Conflict", "the object has been modified")
+            .await;
+        rancher
+            .expect("GET", &path)
+            .returning_json(200, raw_role_template("2", "remote"))
+            .await;
+        rancher
+            .expect("PUT", &path)
+            .with_body_partial(serde_json::json!({ "metadata": { "resourceVersion": "2" }, "description": "local" }))
+            .expect_calls(1)
+            .returning_json(200, raw_role_template("3", "local"))
+            .await;
+
+        let options = UpdateOptions {
+            conflict_strategy: ConflictStrategy::RetryWithLatest,
+        };
+        let written = replace_object(
+            &rancher.configuration(),
+            ResourceKind::RoleTemplate,
+            None,
+            "rt-1",
+            &raw_role_template("1", "local"),
+            &options,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(written["metadata"]["resourceVersion"], "3");
+        assert_eq!(written["description"], "local");
+    }
+
+    fn sample_binding(id: &str, project_name: &str) -> ProjectRoleTemplateBinding {
+        ProjectRoleTemplateBinding::new(
+            None,
+            None,
+            None,
+            id.to_string(),
+            None,
+            String::new(),
+            project_name.to_string(),
+            None,
+            "project-member".to_string(),
+            None,
+            None,
+            Some("u-alice".to_string()),
+            None,
+        )
+    }
+
+    #[test]
+    fn test_validate_inheritance_refuses_cycles() {
+        let inheriting = |id: &str, name: &str| RoleTemplate {
+            id: id.to_string(),
+            role_template_names: Some(vec![name.to_string()]),
+            ..Default::default()
+        };
+
+        assert!(validate_inheritance(&[inheriting("rt-a", "view")]).is_ok());
+        let err = validate_inheritance(&[inheriting("rt-a", "rt-b"), inheriting("rt-b", "rt-a")]).unwrap_err();
+        assert!(
+            matches!(&err, RancherError::Invalid(message) if message == "role templates inherit each other in a cycle: rt-a -> rt-b -> rt-a")
+        );
+    }
+
+    #[test]
+    fn test_validate_project_references_lists_all_missing_projects() {
+        let project = Project::new(
+            None,
+            "c-1".to_string(),
+            None,
+            String::new(),
+            "Project One".to_string(),
+            None,
+            "p-1".to_string(),
+            None,
+            None,
+            "c-1".to_string(),
+            None,
+            None,
+            None,
+        );
+        let mut projects = HashMap::new();
+        projects.insert(
+            "p-1".to_string(),
+            (
+                project,
+                vec![
+                    sample_binding("prtb-ok", "c-1:p-1"),
+                    sample_binding("prtb-a", "c-1:p-missing-a"),
+                    sample_binding("prtb-b", "c-1:p-missing-b"),
+                    sample_binding("prtb-c", "c-2:p-1"),
+                ],
+            ),
+        );
+        let desired = ClusterConfig {
+            cluster: Cluster::new("c-1".to_string(), "Cluster One".to_string(), None),
+            role_templates: Vec::new(),
+            projects,
+        };
+
+        let existing: HashSet<&str> = ["p-1"].into_iter().collect();
+        let err = validate_project_references(&desired, "c-1", &existing).unwrap_err().to_string();
+
+        assert!(err.contains("prtb-a (project p-missing-a)"));
+        assert!(err.contains("prtb-b (project p-missing-b)"));
+        assert!(err.contains("prtb-c (project c-2:p-1)"));
+        assert!(!err.contains("prtb-ok"));
+
+        let existing: HashSet<&str> = ["p-1", "p-missing-a", "p-missing-b"].into_iter().collect();
+        let err = validate_project_references(&desired, "c-1", &existing).unwrap_err().to_string();
+        assert!(err.contains("prtb-c (project c-2:p-1)"));
+        assert!(!err.contains("prtb-a"));
+    }
+
+    #[tokio::test]
+    async fn test_reconcile_of_unknown_cluster_returns_error() {
+        let rancher = MockRancher::start().await;
+        rancher
+            .expect("GET", "/apis/management.cattle.io/v3/clusters")
+            .returning(Vec::<Value>::new())
+            .await;
+        let desired = ClusterConfig {
+            cluster: Cluster::new("c-1".to_string(), "Cluster One".to_string(), None),
+            role_templates: Vec::new(),
+            projects: HashMap::new(),
+        };
+
+        let err = reconcile(&rancher.client(), "c-1", &desired, Mode::Check)
+            .await
+            .unwrap_err();
+
+        assert!(matches!(err.error, RancherError::NotFound));
+        assert_eq!(err.to_string(), "Cluster c-1: not found");
+    }
+
+    #[tokio::test]
+    async fn test_reconcile_refuses_configuration_of_another_cluster() {
+        let rancher = MockRancher::start().await;
+        rancher
+            .expect("GET", "/apis/management.cattle.io/v3/clusters")
+            .expect_calls(0)
+            .returning(Vec::<Value>::new())
+            .await;
+        let desired = ClusterConfig {
+            cluster: Cluster::new("c-1".to_string(), "Cluster One".to_string(), None),
+            role_templates: Vec::new(),
+            projects: HashMap::new(),
+        };
+
+        let err = reconcile(&rancher.client(), "c-2", &desired, Mode::Check)
+            .await
+            .unwrap_err();
+
+        assert_eq!(err.resource, ResourceContext::new(ResourceKind::Cluster, None, "c-2"));
+        assert!(matches!(&err.error, RancherError::Invalid(message) if message.contains("c-1")));
+    }
+
+    fn owner_labels() -> HashMap<String, String> {
+        HashMap::from([(MANAGED_BY_LABEL.to_string(), MANAGED_BY_VALUE.to_string())])
+    }
+
+    fn api_binding(binding: &ProjectRoleTemplateBinding, resource_version: &str) -> Value {
+        let mut binding = binding.clone();
+        binding.resource_version = Some(resource_version.to_string());
+        serde_json::to_value(IoCattleManagementv3ProjectRoleTemplateBinding::try_from(binding).unwrap()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_reconcile_bindings_creates_updates_and_deletes() {
+        let rancher = MockRancher::start().await;
+        let keep = sample_binding("prtb-keep", "c-1:p-1");
+        let stale = sample_binding("prtb-stale", "c-1:p-1");
+        let mut orphan = sample_binding("prtb-orphan", "c-1:p-1");
+        orphan.labels = Some(owner_labels());
+        let new = sample_binding("prtb-new", "c-1:p-1");
+        let mut updated = stale.clone();
+        updated.role_template_name = "project-owner".to_string();
+
+        rancher
+            .expect_list_namespaced_project_role_template_bindings("p-1")
+            .returning(vec![
+                api_binding(&keep, "1"),
+                api_binding(&stale, "2"),
+                api_binding(&orphan, "3"),
+            ])
+            .await;
+        rancher
+            .expect_create_namespaced_project_role_template_binding("p-1")
+            .with_body_partial(serde_json::json!({ "metadata": { "name": "prtb-new" } }))
+            .expect_calls(1)
+            .returning_json(201, api_binding(&new, "4"))
+            .await;
+        rancher
+            .expect("PUT", &ResourceKind::ProjectRoleTemplateBinding.api_path(Some("p-1"), "prtb-stale"))
+            .with_body_partial(serde_json::json!({
+                "metadata": { "resourceVersion": "2" },
+                "roleTemplateName": "project-owner",
+            }))
+            .expect_calls(1)
+            .returning_json(200, api_binding(&updated, "5"))
+            .await;
+        rancher
+            .expect("PUT", &ResourceKind::ProjectRoleTemplateBinding.api_path(Some("p-1"), "prtb-keep"))
+            .expect_calls(0)
+            .returning_json(200, api_binding(&keep, "6"))
+            .await;
+        rancher
+            .expect("DELETE", &ResourceKind::ProjectRoleTemplateBinding.api_path(Some("p-1"), "prtb-orphan"))
+            .expect_calls(1)
+            .returning_json(200, serde_json::json!({ "kind": "Status", "status": "Success" }))
+            .await;
+
+        let summary = reconcile_project_role_template_bindings(
+            &rancher.client(),
+            "p-1",
+            &[keep, updated, new],
+            &ReconcileOptions::pruning(),
+        )
+        .await
+        .unwrap()
+        .summary();
+
+        assert_eq!(
+            summary,
+            ReconcileSummary {
+                created: vec!["prtb-new".to_string()],
+                updated: vec!["prtb-stale".to_string()],
+                deleted: vec!["prtb-orphan".to_string()],
+                unchanged: vec!["prtb-keep".to_string()],
+                skipped: Vec::new(),
+                failed: Vec::new(),
+                reverted: Vec::new(),
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_reconcile_bindings_rejects_binding_of_another_project() {
+        let rancher = MockRancher::start().await;
+        rancher
+            .expect_list_namespaced_project_role_template_bindings("p-1")
+            .expect_calls(0)
+            .returning(Vec::<Value>::new())
+            .await;
+
+        let err = reconcile_project_role_template_bindings(
+            &rancher.client(),
+            "p-1",
+            &[sample_binding("prtb-a", "c-1:p-2")],
+            &ReconcileOptions::default(),
+        )
+        .await
+        .unwrap_err();
+
+        assert!(matches!(&err, RancherError::Invalid(message) if message.contains("prtb-a") && message.contains("p-2")));
+    }
+
+    fn role_template_from_json(value: Value) -> RoleTemplate {
+        let rt: IoCattleManagementv3RoleTemplate = serde_json::from_value(value).unwrap();
+        RoleTemplate::try_from(rt).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_reconcile_role_templates_creates_updates_and_skips() {
+        let rancher = MockRancher::start().await;
+        let mut builtin = role_template_json("project-owner");
+        builtin["builtin"] = serde_json::json!(true);
+        let mut locked = role_template_json("locked-rt");
+        locked["locked"] = serde_json::json!(true);
+        rancher
+            .expect_list_role_templates()
+            .returning(vec![
+                role_template_json("rt-keep"),
+                role_template_json("rt-update"),
+                builtin.clone(),
+                locked.clone(),
+            ])
+            .await;
+
+        let mut updated = role_template_json("rt-update");
+        updated["description"] = serde_json::json!("updated");
+        rancher
+            .expect("PUT", &ResourceKind::RoleTemplate.api_path(None, "rt-update"))
+            .with_body_partial(serde_json::json!({
+                "metadata": { "resourceVersion": "1" },
+                "description": "updated",
+            }))
+            .expect_calls(1)
+            .returning_json(200, updated.clone())
+            .await;
+        rancher
+            .expect_create_role_template()
+            .with_body_partial(serde_json::json!({ "metadata": { "name": "rt-new" } }))
+            .expect_calls(1)
+            .returning_json(201, role_template_json("rt-new"))
+            .await;
+        for name in ["rt-keep", "project-owner", "locked-rt"] {
+            rancher
+                .expect("PUT", &ResourceKind::RoleTemplate.api_path(None, name))
+                .expect_calls(0)
+                .returning_json(200, role_template_json(name))
+                .await;
+        }
+
+        builtin["description"] = serde_json::json!("changed");
+        locked["description"] = serde_json::json!("changed");
+        let desired = vec![
+            role_template_from_json(role_template_json("rt-keep")),
+            role_template_from_json(updated),
+            role_template_from_json(builtin),
+            role_template_from_json(locked),
+            role_template_from_json(role_template_json("rt-new")),
+        ];
+        let summary = reconcile_role_templates(&rancher.client(), &desired, &ReconcileOptions::default())
+            .await
+            .unwrap()
+            .summary();
+
+        assert_eq!(summary.created, vec!["rt-new".to_string()]);
+        assert_eq!(summary.updated, vec!["rt-update".to_string()]);
+        assert_eq!(summary.unchanged, vec!["rt-keep".to_string()]);
+        assert_eq!(summary.skipped, vec!["project-owner".to_string(), "locked-rt".to_string()]);
+        assert!(summary.deleted.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_plan_for_matching_state_is_empty() {
+        let rancher = MockRancher::start().await;
+        rancher
+            .expect_list_role_templates()
+            .returning(vec![role_template_json("rt-keep")])
+            .await;
+        let binding = sample_binding("prtb-keep", "c-1:p-1");
+        rancher
+            .expect_list_namespaced_project_role_template_bindings("p-1")
+            .returning(vec![api_binding(&binding, "1")])
+            .await;
+        rancher
+            .expect_create_role_template()
+            .expect_calls(0)
+            .returning_json(201, role_template_json("rt-keep"))
+            .await;
+
+        let client = rancher.client();
+        let rt_plan = plan_role_templates(
+            &client,
+            &[role_template_from_json(role_template_json("rt-keep"))],
+            &ReconcileOptions::default(),
+        )
+        .await
+        .unwrap();
+        assert!(rt_plan.is_empty());
+        assert_eq!(rt_plan.unchanged, vec!["rt-keep".to_string()]);
+
+        let binding_plan = plan_project_role_template_bindings(&client, "p-1", &[binding], &ReconcileOptions::default())
+            .await
+            .unwrap();
+        assert!(binding_plan.is_empty());
+    }
+
+    #[test]
+    fn test_role_template_plan_lists_actions() {
+        let keep = role_template_from_json(role_template_json("rt-keep"));
+        let mut orphan = role_template_from_json(role_template_json("rt-orphan"));
+        orphan.labels = Some(owner_labels());
+        let mut changed = keep.clone();
+        changed.description = Some("changed".to_string());
+        let new = role_template_from_json(role_template_json("rt-new"));
+
+        let plan = role_template_plan(
+            &[(keep, Some("1".to_string())), (orphan, Some("2".to_string()))],
+            &[changed, new],
+            &ReconcileOptions::pruning(),
+        );
+
+        let actions: Vec<(&str, &Action)> = plan.actions.iter().map(|a| (a.name.as_str(), &a.action)).collect();
+        assert_eq!(actions.len(), 3);
+        assert!(matches!(actions[0], ("rt-keep", Action::Update(report)) if report.paths() == vec!["description"]));
+        assert_eq!(plan.actions[0].resource_version.as_deref(), Some("1"));
+        assert_eq!(actions[1], ("rt-new", &Action::Create));
+        assert_eq!(actions[2], ("rt-orphan", &Action::Delete));
+    }
+
+    #[test]
+    fn test_orphans_are_kept_without_prune() {
+        let mut owned = sample_binding("prtb-owned", "c-1:p-1");
+        owned.labels = Some(owner_labels());
+        let current = vec![owned, sample_binding("prtb-manual", "c-1:p-1")];
+
+        let plan = binding_plan(&current, &[], &ReconcileOptions::default());
+
+        assert!(plan.is_empty());
+    }
+
+    #[test]
+    fn test_prune_only_deletes_owned_orphans() {
+        let mut owned = sample_binding("prtb-owned", "c-1:p-1");
+        owned.labels = Some(owner_labels());
+        let current = vec![owned, sample_binding("prtb-manual", "c-1:p-1")];
+
+        let plan = binding_plan(&current, &[], &ReconcileOptions::pruning());
+
+        assert_eq!(plan.actions.len(), 1);
+        assert_eq!(plan.actions[0].name, "prtb-owned");
+        assert_eq!(plan.actions[0].action, Action::Delete);
+
+        let mut owned_rt = role_template_from_json(role_template_json("rt-owned"));
+        owned_rt.labels = Some(owner_labels());
+        let manual_rt = role_template_from_json(role_template_json("rt-manual"));
+        let current = vec![(owned_rt, None), (manual_rt, None)];
+
+        let plan = role_template_plan(&current, &[], &ReconcileOptions::pruning());
+        assert_eq!(plan.actions.len(), 1);
+        assert_eq!(plan.actions[0].name, "rt-owned");
+        assert!(role_template_plan(&current, &[], &ReconcileOptions::default()).is_empty());
+    }
+
+    #[test]
+    fn test_created_objects_can_be_pruned() {
+        let options = ReconcileOptions::pruning();
+
+        let plan = binding_plan(&[], &[sample_binding("prtb-new", "c-1:p-1")], &options);
+        let created = plan.actions[0].object.clone().unwrap();
+        assert_eq!(created.labels, Some(owner_labels()));
+        let plan = binding_plan(&[created], &[], &options);
+        assert_eq!(plan.actions.len(), 1);
+        assert_eq!(plan.actions[0].action, Action::Delete);
+
+        let plan = role_template_plan(&[], &[role_template_from_json(role_template_json("rt-new"))], &options);
+        let created = plan.actions[0].object.clone().unwrap();
+        let plan = role_template_plan(&[(created, Some("1".to_string()))], &[], &options);
+        assert_eq!(plan.actions.len(), 1);
+        assert_eq!(plan.actions[0].action, Action::Delete);
+    }
+
+    #[tokio::test]
+    async fn test_report_reflects_mixed_batch() {
+        let rancher = MockRancher::start().await;
+        let keep = sample_binding("prtb-keep", "c-1:p-1");
+        let stale = sample_binding("prtb-stale", "c-1:p-1");
+        let mut orphan = sample_binding("prtb-orphan", "c-1:p-1");
+        orphan.labels = Some(owner_labels());
+        let new = sample_binding("prtb-new", "c-1:p-1");
+        let broken = sample_binding("prtb-broken", "c-1:p-1");
+        let mut updated = stale.clone();
+        updated.role_template_name = "project-owner".to_string();
+
+        rancher
+            .expect_list_namespaced_project_role_template_bindings("p-1")
+            .returning(vec![
+                api_binding(&keep, "1"),
+                api_binding(&stale, "2"),
+                api_binding(&orphan, "3"),
+            ])
+            .await;
+        rancher
+            .expect_create_namespaced_project_role_template_binding("p-1")
+            .with_body_partial(serde_json::json!({ "metadata": { "name": "prtb-new" } }))
+            .returning_json(201, api_binding(&new, "4"))
+            .await;
+        rancher
+            .expect_create_namespaced_project_role_template_binding("p-1")
+            .with_body_partial(serde_json::json!({ "metadata": { "name": "prtb-broken" } }))
+            .returning_status(500, "InternalError", "boom")
+            .await;
+        rancher
+            .expect("PUT", &ResourceKind::ProjectRoleTemplateBinding.api_path(Some("p-1"), "prtb-stale"))
+            .returning_json(200, api_binding(&updated, "5"))
+            .await;
+        rancher
+            .expect("DELETE", &ResourceKind::ProjectRoleTemplateBinding.api_path(Some("p-1"), "prtb-orphan"))
+            .returning_json(200, serde_json::json!({ "kind": "Status", "status": "Success" }))
+            .await;
+
+        let report = reconcile_project_role_template_bindings(
+            &rancher.client(),
+            "p-1",
+            &[keep, updated, new, broken],
+            &ReconcileOptions::pruning(),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(report.outcomes.len(), 5);
+        assert_eq!(report.outcome("prtb-new"), Some(&Outcome::Created));
+        assert_eq!(report.outcome("prtb-orphan"), Some(&Outcome::Deleted));
+        assert_eq!(report.outcome("prtb-keep"), Some(&Outcome::Unchanged));
+        match report.outcome("prtb-stale") {
+            Some(Outcome::Updated(diff)) => assert_eq!(diff.paths(), vec!["roleTemplateName"]),
+            other => panic!("expected an update, got {:?}", other),
+        }
+        match report.outcome("prtb-broken") {
+            Some(Outcome::Failed(e)) => {
+                assert!(e.contains("Failed to create ProjectRoleTemplateBinding p-1/prtb-broken"))
+            }
+            other => panic!("expected a failure, got {:?}", other),
+        }
+        assert!(report.has_failures());
+
+        let table = report.to_string();
+        let lines: Vec<&str> = table.lines().collect();
+        assert_eq!(lines.len(), 6);
+        assert!(lines[0].starts_with("RESOURCE     OUTCOME    DETAILS"));
+        assert!(lines.contains(&"prtb-stale   updated    roleTemplateName"));
+        assert!(lines.contains(&"prtb-keep    unchanged"));
+        assert!(lines.iter().any(|line| line
+            .starts_with("prtb-broken  failed     Failed to create ProjectRoleTemplateBinding p-1/prtb-broken")));
+    }
+
+    #[tokio::test]
+    async fn test_transactional_failure_rolls_back_earlier_creates() {
+        let rancher = MockRancher::start().await;
+        rancher
+            .expect_list_namespaced_project_role_template_bindings("p-1")
+            .returning(Vec::<Value>::new())
+            .await;
+        let bindings: Vec<ProjectRoleTemplateBinding> = (0..3)
+            .map(|i| sample_binding(&format!("prtb-{}", i), "c-1:p-1"))
+            .collect();
+        for binding in &bindings[..2] {
+            rancher
+                .expect_create_namespaced_project_role_template_binding("p-1")
+                .with_body_partial(serde_json::json!({ "metadata": { "name": binding.id } }))
+                .expect_calls(1)
+                .returning_json(201, api_binding(binding, "1"))
+                .await;
+            rancher
+                .expect("DELETE", &ResourceKind::ProjectRoleTemplateBinding.api_path(Some("p-1"), &binding.id))
+                .expect_calls(1)
+                .returning_json(200, serde_json::json!({ "kind": "Status", "status": "Success" }))
+                .await;
+        }
+        rancher
+            .expect_create_namespaced_project_role_template_binding("p-1")
+            .with_body_partial(serde_json::json!({ "metadata": { "name": "prtb-2" } }))
+            .expect_calls(1)
+            .returning_status(500, "InternalError", "boom")
+            .await;
+
+        let options = ReconcileOptions {
+            transactional: true,
+            ..Default::default()
+        };
+        let report = reconcile_project_role_template_bindings(&rancher.client(), "p-1", &bindings, &options)
+            .await
+            .unwrap();
+
+        assert_eq!(report.outcome("prtb-0"), Some(&Outcome::Reverted));
+        assert_eq!(report.outcome("prtb-1"), Some(&Outcome::Reverted));
+        assert!(matches!(report.outcome("prtb-2"), Some(Outcome::Failed(e)) if e.contains("prtb-2")));
+        assert_eq!(
+            report.summary().reverted,
+            vec!["prtb-0".to_string(), "prtb-1".to_string()]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_apply_reports_failures_without_aborting() {
+        let rancher = MockRancher::start().await;
+        rancher
+            .expect_list_namespaced_project_role_template_bindings("p-1")
+            .returning(Vec::<Value>::new())
+            .await;
+        rancher
+            .expect_create_namespaced_project_role_template_binding("p-1")
+            .with_body_partial(serde_json::json!({ "metadata": { "name": "prtb-broken" } }))
+            .returning_status(500, "InternalError", "boom")
+            .await;
+        let bindings: Vec<ProjectRoleTemplateBinding> = (0..5)
+            .map(|i| sample_binding(&format!("prtb-{}", i), "c-1:p-1"))
+            .chain(std::iter::once(sample_binding("prtb-broken", "c-1:p-1")))
+            .collect();
+        for binding in &bindings[..5] {
+            rancher
+                .expect_create_namespaced_project_role_template_binding("p-1")
+                .with_body_partial(serde_json::json!({ "metadata": { "name": binding.id } }))
+                .expect_calls(1)
+                .returning_json(201, api_binding(binding, "1"))
+                .await;
+        }
+
+        let options = ReconcileOptions {
+            concurrency: 4,
+            ..Default::default()
+        };
+        let summary = reconcile_project_role_template_bindings(&rancher.client(), "p-1", &bindings, &options)
+            .await
+            .unwrap()
+            .summary();
+
+        assert_eq!(
+            summary.created,
+            (0..5).map(|i| format!("prtb-{}", i)).collect::<Vec<_>>()
+        );
+        assert_eq!(summary.failed.len(), 1);
+        assert_eq!(summary.failed[0].0, "prtb-broken");
+        assert!(summary.failed[0].1.contains("Failed to create ProjectRoleTemplateBinding p-1/prtb-broken"));
+    }
+
+    async fn mount_role_template_conflict(rancher: &MockRancher) {
+        rancher
+            .expect_list_role_templates()
+            .returning(vec![role_template_json("rt-1")])
+            .await;
+        rancher
+            .expect("PUT", &ResourceKind::RoleTemplate.api_path(None, "rt-1"))
+            .with_body_partial(serde_json::json!({ "metadata": { "resourceVersion": "1" } }))
+            .returning_status(409, "Conflict", "the object has been modified")
+            .await;
+        let mut latest = role_template_json("rt-1");
+        latest["metadata"]["resourceVersion"] = serde_json::json!("2");
+        rancher
+            .expect_get_role_template("rt-1")
+            .returning_json(200, latest.clone())
+            .await;
+        latest["description"] = serde_json::json!("updated");
+        rancher
+            .expect("PUT", &ResourceKind::RoleTemplate.api_path(None, "rt-1"))
+            .with_body_partial(serde_json::json!({
+                "metadata": { "resourceVersion": "2" },
+                "description": "updated",
+            }))
+            .returning_json(200, latest)
+            .await;
+    }
+
+    fn updated_role_template() -> RoleTemplate {
+        let mut rt = role_template_from_json(role_template_json("rt-1"));
+        rt.description = Some("updated".to_string());
+        rt
+    }
+
+    #[tokio::test]
+    async fn test_conflict_is_retried_with_latest() {
+        let rancher = MockRancher::start().await;
+        mount_role_template_conflict(&rancher).await;
+
+        let options = ReconcileOptions {
+            conflict_strategy: ConflictStrategy::RetryWithLatest,
+            ..Default::default()
+        };
+        let summary = reconcile_role_templates(&rancher.client(), &[updated_role_template()], &options)
+            .await
+            .unwrap()
+            .summary();
+
+        assert_eq!(summary.updated, vec!["rt-1".to_string()]);
+        assert!(summary.failed.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_conflict_fails_without_retry() {
+        let rancher = MockRancher::start().await;
+        mount_role_template_conflict(&rancher).await;
+
+        let summary = reconcile_role_templates(
+            &rancher.client(),
+            &[updated_role_template()],
+            &ReconcileOptions::default(),
+        )
+        .await
+        .unwrap()
+        .summary();
+
+        assert!(summary.updated.is_empty());
+        assert_eq!(summary.failed.len(), 1);
+        assert!(summary.failed[0].1.contains("conflict: the object has been modified"));
+    }
+
+    #[test]
+    fn test_matching_content_hash_skips_diff() {
+        let options = ReconcileOptions {
+            track_content_hash: true,
+            ..Default::default()
+        };
+        let desired = role_template_from_json(role_template_json("rt-1"));
+        // same stored hash, but drifted on the server: trusted as unchanged
+        let mut drifted = crate::rt::with_content_hash(&desired);
+        drifted.description = Some("edited by hand".to_string());
+
+        let plan = role_template_plan(&[(drifted, Some("1".to_string()))], &[desired.clone()], &options);
+        assert!(plan.is_empty());
+        assert_eq!(plan.unchanged, vec!["rt-1".to_string()]);
+
+        // no stored hash yet: the object is updated to store it
+        let plan = role_template_plan(&[(desired.clone(), Some("1".to_string()))], &[desired], &options);
+        assert_eq!(plan.actions.len(), 1);
+        let stored = plan.actions[0].object.as_ref().unwrap().annotations.as_ref();
+        assert!(stored_content_hash(stored).is_some());
+    }
+
+    #[test]
+    fn test_created_bindings_carry_content_hash() {
+        let options = ReconcileOptions {
+            track_content_hash: true,
+            ..Default::default()
+        };
+        let binding = sample_binding("prtb-new", "c-1:p-1");
+
+        let plan = binding_plan(&[], &[binding.clone()], &options);
+
+        let created = plan.actions[0].object.as_ref().unwrap();
+        assert_eq!(
+            stored_content_hash(created.annotations.as_ref()),
+            Some(crate::metadata::format_content_hash(crate::prtb::content_hash(&binding)).as_str())
+        );
+    }
+}
@@ -0,0 +1,279 @@
+use ring::hmac;
+use serde::Serialize;
+
+use crate::config::ClusterConfig;
+use crate::graph::{subject_refs, SubjectKind};
+use crate::prtb::ProjectRoleTemplateBinding;
+
+/// A subject with its identity replaced by a token
+#[derive(Serialize, Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct RedactedSubject {
+    pub kind: SubjectKind,
+    pub token: String,
+}
+
+/// A binding with its name, project and subjects replaced by tokens
+#[derive(Serialize, Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct RedactedBinding {
+    pub token: String,
+    pub project: String,
+    /// Role template names are kept as is, they describe permissions rather than identities
+    pub role_template: String,
+    pub subjects: Vec<RedactedSubject>,
+}
+
+/// An RBAC report safe for sharing outside the organization
+///
+/// Subject, binding and project identifiers are replaced by tokens keyed by a secret salt. The
+/// same identifier always maps to the same token for a given salt, so relationships can still be
+/// analyzed.
+#[derive(Serialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct RedactedReport {
+    pub bindings: Vec<RedactedBinding>,
+}
+
+/// Pseudonymize an identifier with HMAC-SHA256 keyed by the salt, truncated to 128 bits
+///
+/// Unlike a plain hash, the token cannot be reversed by hashing candidate identifiers without
+/// knowing the salt, so the salt must stay secret.
+fn token(prefix: &str, salt: &str, value: &str) -> String {
+    let key = hmac::Key::new(hmac::HMAC_SHA256, salt.as_bytes());
+    let tag = hmac::sign(&key, value.as_bytes());
+    let hex: String = tag.as_ref()[..16].iter().map(|byte| format!("{:02x}", byte)).collect();
+    format!("{}-{}", prefix, hex)
+}
+
+/// Build a redacted RBAC report of a cluster
+///
+/// # Arguments
+///
+/// * `snapshot` - The cluster configuration to report on
+/// * `salt` - The key of every token, it must stay secret since anyone knowing it can confirm a
+///   guessed identifier, reuse it to compare reports
+///
+/// # Returns
+///
+/// * `RedactedReport` - The report, with bindings in a deterministic order
+pub fn redacted(snapshot: &ClusterConfig, salt: &str) -> RedactedReport {
+    let mut bindings: Vec<RedactedBinding> = snapshot
+        .projects
+        .iter()
+        .flat_map(|(project_id, (_, bindings))| {
+            bindings.iter().map(move |binding| {
+                let mut subjects: Vec<RedactedSubject> = subject_refs(binding)
+                    .into_iter()
+                    .map(|(kind, name)| RedactedSubject {
+                        kind,
+                        token: token(kind.as_str(), salt, &name),
+                    })
+                    .collect();
+                subjects.sort();
+                RedactedBinding {
+                    token: token(
+                        "binding",
+                        salt,
+                        &format!("{}/{}", binding.namespace, binding.id),
+                    ),
+                    project: token("project", salt, project_id),
+                    role_template: binding.role_template_name.clone(),
+                    subjects,
+                }
+            })
+        })
+        .collect();
+    bindings.sort();
+
+    RedactedReport { bindings }
+}
+
+/// The header row of `bindings_to_csv`
+pub const BINDINGS_CSV_HEADER: &str = "id,namespace,project_name,role_template_name,subject_kind,subject_name";
+
+/// Quote a CSV field when it contains a separator, a quote or a line break (RFC 4180)
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// Export bindings as CSV, one row per subject, for audits in a spreadsheet
+///
+/// The subject kind is `user`, `group` or `serviceaccount`, and principal names are preferred
+/// over plain names. A binding with several subjects gets one row for each of them, a binding
+/// without any subject a single row with empty subject columns.
+///
+/// # Arguments
+///
+/// * `bindings` - The bindings to export
+///
+/// # Returns
+///
+/// * `String` - The CSV, starting with `BINDINGS_CSV_HEADER`, each row ending with a line break
+pub fn bindings_to_csv(bindings: &[ProjectRoleTemplateBinding]) -> String {
+    let mut csv = format!("{}\n", BINDINGS_CSV_HEADER);
+    for binding in bindings {
+        let mut subjects: Vec<(&str, String)> = subject_refs(binding)
+            .into_iter()
+            .map(|(kind, name)| (kind.as_str(), name))
+            .collect();
+        if subjects.is_empty() {
+            subjects.push(("", String::new()));
+        }
+        for (kind, name) in subjects {
+            let row = [
+                binding.id.as_str(),
+                binding.namespace.as_str(),
+                binding.project_name.as_str(),
+                binding.role_template_name.as_str(),
+                kind,
+                name.as_str(),
+            ];
+            let row: Vec<String> = row.iter().map(|field| csv_field(field)).collect();
+            csv.push_str(&row.join(","));
+            csv.push('\n');
+        }
+    }
+    csv
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    use crate::cluster::Cluster;
+    use crate::project::Project;
+
+    fn sample_binding(id: &str, user: &str) -> ProjectRoleTemplateBinding {
+        ProjectRoleTemplateBinding::new(
+            None,
+            None,
+            None,
+            id.to_string(),
+            None,
+            "p-1".to_string(),
+            "c-1:p-1".to_string(),
+            None,
+            "project-member".to_string(),
+            None,
+            None,
+            Some(user.to_string()),
+            None,
+        )
+    }
+
+    fn sample_snapshot() -> ClusterConfig {
+        let project = Project::new(
+            None,
+            "c-1".to_string(),
+            None,
+            String::new(),
+            "Project One".to_string(),
+            None,
+            "p-1".to_string(),
+            None,
+            None,
+            "c-1".to_string(),
+            None,
+            None,
+            None,
+        );
+        let mut projects = HashMap::new();
+        projects.insert(
+            "p-1".to_string(),
+            (
+                project,
+                vec![
+                    sample_binding("prtb-1", "u-alice"),
+                    sample_binding("prtb-2", "u-alice"),
+                    sample_binding("prtb-3", "u-bob"),
+                ],
+            ),
+        );
+        ClusterConfig {
+            cluster: Cluster::new("c-1".to_string(), "Cluster One".to_string(), None),
+            role_templates: Vec::new(),
+            projects,
+        }
+    }
+
+    fn subject_token_of(report: &RedactedReport, salt: &str, binding_id: &str) -> String {
+        let binding_token = token("binding", salt, &format!("p-1/{}", binding_id));
+        report
+            .bindings
+            .iter()
+            .find(|b| b.token == binding_token)
+            .map(|b| b.subjects[0].token.clone())
+            .unwrap()
+    }
+
+    #[test]
+    fn test_token_is_truncated_hmac_sha256() {
+        // RFC 4231 test case 2
+        assert_eq!(
+            token("user", "Jefe", "what do ya want for nothing?"),
+            "user-5bdcc146bf60754e6a042426089575c7"
+        );
+    }
+
+    #[test]
+    fn test_same_subject_maps_to_same_token() {
+        let report = redacted(&sample_snapshot(), "salt");
+
+        assert_eq!(
+            subject_token_of(&report, "salt", "prtb-1"),
+            subject_token_of(&report, "salt", "prtb-2")
+        );
+        assert_eq!(report, redacted(&sample_snapshot(), "salt"));
+    }
+
+    #[test]
+    fn test_different_subjects_map_to_different_tokens() {
+        let report = redacted(&sample_snapshot(), "salt");
+
+        assert_ne!(
+            subject_token_of(&report, "salt", "prtb-1"),
+            subject_token_of(&report, "salt", "prtb-3")
+        );
+    }
+
+    #[test]
+    fn test_tokens_hide_identifiers_and_depend_on_salt() {
+        let report = redacted(&sample_snapshot(), "salt");
+        let serialized = serde_json::to_string(&report).unwrap();
+
+        assert!(!serialized.contains("u-alice"));
+        assert!(!serialized.contains("prtb-1"));
+        assert!(!serialized.contains("\"p-1\""));
+        assert!(serialized.contains("project-member"));
+        assert_ne!(
+            subject_token_of(&report, "salt", "prtb-1"),
+            subject_token_of(&redacted(&sample_snapshot(), "other"), "other", "prtb-1")
+        );
+    }
+
+    #[test]
+    fn test_bindings_to_csv() {
+        let mut group = sample_binding("prtb-2", "u-alice");
+        group.user_name = None;
+        group.group_principal_name = Some("okta_group://admins, ops".to_string());
+        let mut both = sample_binding("prtb-3", "u-bob");
+        both.service_account = Some("ci".to_string());
+
+        let csv = bindings_to_csv(&[sample_binding("prtb-1", "u-alice"), group, both]);
+        let lines: Vec<&str> = csv.lines().collect();
+
+        assert_eq!(
+            lines,
+            vec![
+                BINDINGS_CSV_HEADER,
+                "prtb-1,p-1,c-1:p-1,project-member,user,u-alice",
+                "prtb-2,p-1,c-1:p-1,project-member,group,\"okta_group://admins, ops\"",
+                "prtb-3,p-1,c-1:p-1,project-member,user,u-bob",
+                "prtb-3,p-1,c-1:p-1,project-member,serviceaccount,ci",
+            ]
+        );
+    }
+}
@@ -0,0 +1,392 @@
+use bytes::Bytes;
+use chrono::Utc;
+use reqwest::header::{AUTHORIZATION, CONTENT_TYPE, RETRY_AFTER, USER_AGENT};
+use reqwest::{Method, RequestBuilder, StatusCode};
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+use rancher_client::apis::{configuration::Configuration, Error, ResponseContent};
+
+use crate::client::{with_timeout, RequestOptions};
+use crate::retry::{
+    parse_retry_after, retry_rate_limited, retry_with_backoff, RetryPolicy, DEFAULT_BACKOFF_ATTEMPTS,
+    DEFAULT_BACKOFF_BASE_DELAY,
+};
+use crate::RancherError;
+
+/// Content type for a JSON merge patch (RFC 7386)
+pub const MERGE_PATCH_CONTENT_TYPE: &str = "application/merge-patch+json";
+
+/// Content type for a JSON patch (RFC 6902)
+pub const JSON_PATCH_CONTENT_TYPE: &str = "application/json-patch+json";
+
+/// Content type for a plain JSON body
+pub const JSON_CONTENT_TYPE: &str = "application/json";
+
+/// Build a request for `path` with the user agent and credentials of the configuration
+fn request_builder(configuration: &Configuration, method: Method, path: &str) -> RequestBuilder {
+    let uri = format!("{}{}", configuration.base_path, path);
+    let mut request = configuration.client.request(method, &uri);
+
+    if let Some(ref user_agent) = configuration.user_agent {
+        request = request.header(USER_AGENT, user_agent.clone());
+    }
+    if let Some(ref api_key) = configuration.api_key {
+        let value = match api_key.prefix {
+            Some(ref prefix) => format!("{} {}", prefix, api_key.key),
+            None => api_key.key.clone(),
+        };
+        request = request.header(AUTHORIZATION, value);
+    }
+    if let Some(ref token) = configuration.bearer_access_token {
+        request = request.bearer_auth(token.to_owned());
+    }
+    request
+}
+
+/// Send a GET request and return the raw body bytes
+///
+/// Unlike `send_request` the body is not decoded into a `String`, so it can be parsed with
+/// `serde_json::from_slice` without an intermediate copy.
+///
+/// # Arguments
+///
+/// * `configuration` - The configuration to use for the request
+/// * `path` - The API path, appended to `configuration.base_path`
+/// * `query` - The query parameters
+///
+/// # Returns
+///
+/// * `(StatusCode, Bytes)` - The status and raw body of the response
+///
+/// # Errors
+///
+/// * `Error<E>` - The error that occurred while sending the request or reading the body
+pub(crate) async fn get_bytes<E>(
+    configuration: &Configuration,
+    path: &str,
+    query: &[(&str, String)],
+) -> Result<(StatusCode, Bytes), Error<E>> {
+    let response = request_builder(configuration, Method::GET, path)
+        .query(query)
+        .send()
+        .await?;
+    let status = response.status();
+    let body = response.bytes().await?;
+    Ok((status, body))
+}
+
+/// The query parameters of a list request, the parameters left to `None` are not sent
+pub(crate) fn list_query(
+    field_selector: Option<&str>,
+    label_selector: Option<&str>,
+    limit: Option<i32>,
+    continue_: Option<&str>,
+) -> Vec<(&'static str, String)> {
+    let mut query = Vec::new();
+    if let Some(continue_) = continue_ {
+        query.push(("continue", continue_.to_string()));
+    }
+    if let Some(field_selector) = field_selector {
+        query.push(("fieldSelector", field_selector.to_string()));
+    }
+    if let Some(label_selector) = label_selector {
+        query.push(("labelSelector", label_selector.to_string()));
+    }
+    if let Some(limit) = limit {
+        query.push(("limit", limit.to_string()));
+    }
+    query
+}
+
+/// Send a GET request and decode its JSON body, keeping the `Retry-After` delay of a 429
+///
+/// The generated API functions drop the response headers, this is used where the delay asked by a
+/// rate limiting server matters, typically together with `retry_rate_limited`.
+///
+/// # Arguments
+///
+/// * `configuration` - The configuration to use for the request
+/// * `path` - The API path, appended to `configuration.base_path`
+/// * `query` - The query parameters
+///
+/// # Returns
+///
+/// * `T` - The decoded body of a successful response
+///
+/// # Errors
+///
+/// * `RancherError` - `RateLimited` with the delay of the `Retry-After` header on a 429, the error matching any other failed status, or the error that occurred while sending the request or decoding the body
+pub(crate) async fn get_json<T: DeserializeOwned>(
+    configuration: &Configuration,
+    path: &str,
+    query: &[(&str, String)],
+) -> Result<T, RancherError> {
+    let response = request_builder(configuration, Method::GET, path)
+        .query(query)
+        .send()
+        .await
+        .map_err(RancherError::Transport)?;
+    let status = response.status();
+    if status == StatusCode::TOO_MANY_REQUESTS {
+        let retry_after = response
+            .headers()
+            .get(RETRY_AFTER)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| parse_retry_after(value, Utc::now()));
+        return Err(RancherError::RateLimited { retry_after });
+    }
+
+    let content = response.text().await.map_err(RancherError::Transport)?;
+    if !status.is_success() {
+        let error: Error<Value> = Error::ResponseError(ResponseContent {
+            status,
+            content,
+            entity: None,
+        });
+        return Err(error.into());
+    }
+    serde_json::from_str(&content).map_err(RancherError::Serde)
+}
+
+/// Get a list page with `get_json`, retrying it while it is rate limited or fails transiently
+///
+/// Rate limited attempts follow `rate_limit`, transient failures (dropped connections, timeouts,
+/// 502, 503, 504) are retried with `retry_with_backoff`. Every attempt is bounded by the timeout
+/// of `request`.
+///
+/// # Arguments
+///
+/// * `configuration` - The configuration to use for the request
+/// * `path` - The API path of the list, appended to `configuration.base_path`
+/// * `query` - The query parameters
+/// * `rate_limit` - How rate limited attempts are retried
+/// * `request` - The timeout of every attempt
+///
+/// # Returns
+///
+/// * `T` - The decoded list
+///
+/// # Errors
+///
+/// * `RancherError` - The error of the last attempt
+pub(crate) async fn get_list<T: DeserializeOwned>(
+    configuration: &Configuration,
+    path: &str,
+    query: &[(&str, String)],
+    rate_limit: &RetryPolicy,
+    request: &RequestOptions,
+) -> Result<T, RancherError> {
+    retry_with_backoff(DEFAULT_BACKOFF_ATTEMPTS, DEFAULT_BACKOFF_BASE_DELAY, || {
+        retry_rate_limited(rate_limit, || {
+            with_timeout(request, get_json(configuration, path, query))
+        })
+    })
+    .await
+}
+
+/// Send a request directly through the configured client
+///
+/// The generated API functions always send patches as `application/json-patch+json`, this is used
+/// for the requests they cannot express (merge patches, generic object access by path).
+///
+/// # Arguments
+///
+/// * `configuration` - The configuration to use for the request
+/// * `method` - The HTTP method
+/// * `path` - The API path, appended to `configuration.base_path`
+/// * `content_type` - The content type of the body
+/// * `body` - The optional JSON body
+///
+/// # Returns
+///
+/// * `ResponseContent<Value>` - The raw status and content of the response
+///
+/// # Errors
+///
+/// * `Error<Value>` - The error that occurred while sending the request
+pub(crate) async fn send_request(
+    configuration: &Configuration,
+    method: Method,
+    path: &str,
+    content_type: &str,
+    body: Option<&Value>,
+) -> Result<ResponseContent<Value>, Error<Value>> {
+    let mut request = request_builder(configuration, method, path);
+    if let Some(body) = body {
+        request = request
+            .header(CONTENT_TYPE, content_type)
+            .body(serde_json::to_vec(body)?);
+    }
+
+    let response = request.send().await?;
+    let status = response.status();
+    let content = response.text().await?;
+
+    Ok(ResponseContent {
+        status,
+        content,
+        entity: None,
+    })
+}
+
+/// Turn a raw response into its JSON body, or a `ResponseError` when the status is not a success
+pub(crate) fn parse_response(response_content: ResponseContent<Value>) -> Result<Value, Error<Value>> {
+    if response_content.status.is_success() {
+        return serde_json::from_str(&response_content.content).map_err(Error::Serde);
+    }
+    let entity = serde_json::from_str::<Value>(&response_content.content).ok();
+    Err(Error::ResponseError(ResponseContent {
+        status: response_content.status,
+        content: response_content.content,
+        entity,
+    }))
+}
+
+/// Whether a create, replace or delete succeeded: 200 OK, 201 Created or 202 Accepted
+pub(crate) fn is_write_success(status: StatusCode) -> bool {
+    matches!(status, StatusCode::OK | StatusCode::CREATED | StatusCode::ACCEPTED)
+}
+
+/// Decode the body of a successful write, `None` when it does not hold the object
+///
+/// A 202 may come with an empty body, or with a `Status` describing the accepted operation
+/// instead of the object.
+///
+/// # Arguments
+///
+/// * `content` - The raw body of the response
+///
+/// # Returns
+///
+/// * `Option<T>` - The object written, `None` for an empty or `Status` body
+///
+/// # Errors
+///
+/// * `serde_json::Error` - The body is neither empty, a `Status`, nor a valid object
+pub(crate) fn written_object<T: DeserializeOwned>(content: &str) -> Result<Option<T>, serde_json::Error> {
+    if content.trim().is_empty() {
+        return Ok(None);
+    }
+    let value: Value = serde_json::from_str(content)?;
+    if value.get("kind").and_then(Value::as_str) == Some("Status") {
+        return Ok(None);
+    }
+    serde_json::from_value(value).map(Some)
+}
+
+/// Parse the response of a get-by-name request, mapping a 404 to `Ok(None)`
+///
+/// A 404 is accepted both as an `Ok` response and as an `Error::ResponseError`, so callers can
+/// tell a missing object from a failure without parsing the body. Any other status than 200
+/// becomes a `ResponseError` whose entity is the body wrapped by `unknown_value`.
+///
+/// # Arguments
+///
+/// * `result` - The result of a generated read function
+/// * `unknown_value` - The `UnknownValue` variant of the generated error type
+///
+/// # Returns
+///
+/// * `Option<T>` - The object, or `None` when it does not exist
+///
+/// # Errors
+///
+/// * `Error<E>` - The error of the request, an unexpected status, or a body that could not be parsed
+pub(crate) fn optional_object<T, E>(
+    result: Result<ResponseContent<E>, Error<E>>,
+    unknown_value: impl FnOnce(Value) -> E,
+) -> Result<Option<T>, Error<E>>
+where
+    T: DeserializeOwned,
+{
+    let response_content = match result {
+        Err(Error::ResponseError(response)) if response.status == StatusCode::NOT_FOUND => return Ok(None),
+        Err(e) => return Err(e),
+        Ok(response_content) => response_content,
+    };
+    match response_content.status {
+        StatusCode::OK => serde_json::from_str(&response_content.content)
+            .map(Some)
+            .map_err(Error::Serde),
+        StatusCode::NOT_FOUND => Ok(None),
+        status => {
+            let unknown_data =
+                serde_json::from_str::<Value>(&response_content.content).map_err(Error::Serde)?;
+            Err(Error::ResponseError(ResponseContent {
+                status,
+                content: response_content.content,
+                entity: Some(unknown_value(unknown_data)),
+            }))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn response(status: StatusCode, content: &str) -> ResponseContent<Value> {
+        ResponseContent {
+            status,
+            content: content.to_string(),
+            entity: None,
+        }
+    }
+
+    const NOT_FOUND_BODY: &str =
+        r#"{"kind":"Status","status":"Failure","reason":"NotFound","code":404,"message":"not found"}"#;
+
+    #[test]
+    fn test_written_object_without_object_is_none() {
+        let empty: Option<Value> = written_object("").unwrap();
+        let status: Option<Value> =
+            written_object(r#"{"kind":"Status","status":"Success","code":202}"#).unwrap();
+        let object: Option<Value> = written_object(r#"{"kind":"RoleTemplate"}"#).unwrap();
+
+        assert!(empty.is_none());
+        assert!(status.is_none());
+        assert_eq!(object.unwrap()["kind"], "RoleTemplate");
+        assert!(written_object::<Value>("not json").is_err());
+    }
+
+    #[test]
+    fn test_not_found_is_none() {
+        let ok: Result<Option<Value>, Error<Value>> =
+            optional_object(Ok(response(StatusCode::NOT_FOUND, NOT_FOUND_BODY)), |value| value);
+        let err: Result<Option<Value>, Error<Value>> = optional_object(
+            Err(Error::ResponseError(response(StatusCode::NOT_FOUND, NOT_FOUND_BODY))),
+            |value| value,
+        );
+
+        assert!(ok.unwrap().is_none());
+        assert!(err.unwrap().is_none());
+    }
+
+    #[test]
+    fn test_found_object_is_parsed() {
+        let found: Option<Value> =
+            optional_object(Ok(response(StatusCode::OK, r#"{"id":"rt-1"}"#)), |value| value).unwrap();
+
+        assert_eq!(found, Some(serde_json::json!({ "id": "rt-1" })));
+    }
+
+    #[test]
+    fn test_other_status_keeps_unknown_value() {
+        let result: Result<Option<Value>, Error<Value>> = optional_object(
+            Ok(response(StatusCode::INTERNAL_SERVER_ERROR, r#"{"message":"boom"}"#)),
+            |value| serde_json::json!({ "unknown": value }),
+        );
+
+        match result {
+            Err(Error::ResponseError(response)) => {
+                assert_eq!(response.status, StatusCode::INTERNAL_SERVER_ERROR);
+                assert_eq!(
+                    response.entity,
+                    Some(serde_json::json!({ "unknown": { "message": "boom" } }))
+                );
+            }
+            other => panic!("expected a response error, got {:?}", other.map(|_| ())),
+        }
+    }
+}
@@ -0,0 +1,281 @@
+use std::future::Future;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use rand::Rng;
+use reqwest::StatusCode;
+
+use rancher_client::apis::Error;
+
+use crate::RancherError;
+
+/// The number of attempts of a list request failing with a transient error, the first one included
+pub const DEFAULT_BACKOFF_ATTEMPTS: u32 = 3;
+
+/// The base delay of the exponential backoff between two attempts of a list request
+pub const DEFAULT_BACKOFF_BASE_DELAY: Duration = Duration::from_millis(200);
+
+/// The longest backoff waited between two attempts, however many attempts failed
+pub const MAX_BACKOFF_DELAY: Duration = Duration::from_secs(30);
+
+/// An error that may not happen again when the request is retried
+pub trait Transient {
+    /// Whether retrying the request may succeed: a dropped connection, a timeout, or a 502, 503 or 504
+    fn is_transient(&self) -> bool;
+}
+
+fn is_transient_status(status: StatusCode) -> bool {
+    matches!(
+        status,
+        StatusCode::BAD_GATEWAY | StatusCode::SERVICE_UNAVAILABLE | StatusCode::GATEWAY_TIMEOUT
+    )
+}
+
+fn is_transient_transport(error: &reqwest::Error) -> bool {
+    error.is_connect() || error.is_timeout() || error.is_request()
+}
+
+impl Transient for RancherError {
+    fn is_transient(&self) -> bool {
+        match self {
+            RancherError::Transport(e) => is_transient_transport(e),
+            RancherError::Api(status, _) => is_transient_status(*status),
+            RancherError::Timeout(_) => true,
+            _ => false,
+        }
+    }
+}
+
+impl<E> Transient for Error<E> {
+    fn is_transient(&self) -> bool {
+        match self {
+            Error::Reqwest(e) => is_transient_transport(e),
+            Error::ResponseError(response) => is_transient_status(response.status),
+            _ => false,
+        }
+    }
+}
+
+/// How requests rate limited by the server (429) are retried
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// The maximum number of attempts, the first one included
+    pub max_attempts: u32,
+    /// The delay before retrying when the server did not say how long to wait
+    pub default_delay: Duration,
+    /// The longest delay waited between two attempts, whatever the server asked for
+    pub max_delay: Duration,
+}
+
+impl Default for RetryPolicy {
+    /// Five attempts, waiting one second when the server gives no delay and never more than a minute
+    fn default() -> Self {
+        RetryPolicy {
+            max_attempts: 5,
+            default_delay: Duration::from_secs(1),
+            max_delay: Duration::from_secs(60),
+        }
+    }
+}
+
+/// Parse the value of a `Retry-After` header, either a number of seconds or an HTTP-date
+///
+/// # Arguments
+///
+/// * `value` - The header value
+/// * `now` - The current time, a date is turned into the delay until it
+///
+/// # Returns
+///
+/// * `Option<Duration>` - The delay to wait, zero for a date in the past, `None` when the value is not valid
+pub fn parse_retry_after(value: &str, now: DateTime<Utc>) -> Option<Duration> {
+    let value = value.trim();
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+    let date = DateTime::parse_from_rfc2822(value).ok()?;
+    Some((date.with_timezone(&Utc) - now).to_std().unwrap_or(Duration::ZERO))
+}
+
+/// Run `op` until it is no longer rate limited, waiting the delay asked by the server in between
+///
+/// Only `RancherError::RateLimited` is retried, any other result is returned as is.
+///
+/// # Arguments
+///
+/// * `policy` - The number of attempts and the bounds of the delay
+/// * `op` - The request to send, called once per attempt
+///
+/// # Returns
+///
+/// * `T` - The result of the first attempt that was not rate limited
+///
+/// # Errors
+///
+/// * `RancherError` - The error of the last attempt, `RateLimited` with the suggested delay when every attempt was rate limited
+pub async fn retry_rate_limited<T, F, Fut>(
+    policy: &RetryPolicy,
+    mut op: F,
+) -> Result<T, RancherError>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, RancherError>>,
+{
+    let mut attempt = 1;
+    loop {
+        match op().await {
+            Err(RancherError::RateLimited { retry_after }) if attempt < policy.max_attempts => {
+                let delay = retry_after.unwrap_or(policy.default_delay).min(policy.max_delay);
+                tokio::time::sleep(delay).await;
+                attempt += 1;
+            }
+            result => return result,
+        }
+    }
+}
+
+/// The delay before the next attempt, drawn uniformly between zero and the exponential backoff
+///
+/// Drawing the whole delay at random ("full jitter") spreads the retries of clients that failed
+/// at the same time, instead of having them hit the server again together.
+fn full_jitter(base_delay: Duration, failed_attempts: u32) -> Duration {
+    let backoff = base_delay
+        .saturating_mul(2u32.saturating_pow(failed_attempts.saturating_sub(1)))
+        .min(MAX_BACKOFF_DELAY);
+    let millis = u64::try_from(backoff.as_millis()).unwrap_or(u64::MAX);
+    Duration::from_millis(rand::thread_rng().gen_range(0..=millis))
+}
+
+/// Run `op` until it succeeds or fails with an error that is not transient, waiting an
+/// exponential backoff with full jitter in between
+///
+/// # Arguments
+///
+/// * `attempts` - The maximum number of attempts, the first one included
+/// * `base_delay` - The upper bound of the first delay, doubled after every failed attempt
+/// * `op` - The request to send, called once per attempt
+///
+/// # Returns
+///
+/// * `T` - The result of the first successful attempt
+///
+/// # Errors
+///
+/// * `E` - The first error that is not transient, or the error of the last attempt
+pub async fn retry_with_backoff<T, E, F, Fut>(
+    attempts: u32,
+    base_delay: Duration,
+    mut op: F,
+) -> Result<T, E>
+where
+    E: Transient,
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, E>>,
+{
+    let mut attempt = 1;
+    loop {
+        match op().await {
+            Err(e) if e.is_transient() && attempt < attempts => {
+                tokio::time::sleep(full_jitter(base_delay, attempt)).await;
+                attempt += 1;
+            }
+            result => return result,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::ApiStatus;
+
+    #[test]
+    fn test_parse_retry_after() {
+        let now = DateTime::parse_from_rfc3339("2024-01-01T00:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+
+        assert_eq!(parse_retry_after("120", now), Some(Duration::from_secs(120)));
+        assert_eq!(
+            parse_retry_after("Mon, 01 Jan 2024 00:00:30 GMT", now),
+            Some(Duration::from_secs(30))
+        );
+        assert_eq!(parse_retry_after("Sun, 31 Dec 2023 23:00:00 GMT", now), Some(Duration::ZERO));
+        assert_eq!(parse_retry_after("soon", now), None);
+    }
+
+    #[tokio::test]
+    async fn test_exhausted_attempts_return_rate_limited() {
+        let policy = RetryPolicy {
+            max_attempts: 3,
+            default_delay: Duration::ZERO,
+            max_delay: Duration::ZERO,
+        };
+        let mut calls = 0;
+
+        let result: Result<(), RancherError> = retry_rate_limited(&policy, || {
+            calls += 1;
+            async {
+                Err(RancherError::RateLimited {
+                    retry_after: Some(Duration::from_secs(7)),
+                })
+            }
+        })
+        .await;
+
+        assert_eq!(calls, 3);
+        assert!(matches!(
+            result,
+            Err(RancherError::RateLimited { retry_after: Some(delay) })
+                if delay == Duration::from_secs(7)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_backoff_retries_transient_failures() {
+        let mut calls = 0;
+
+        let result = retry_with_backoff(5, Duration::ZERO, || {
+            calls += 1;
+            let attempt = calls;
+            async move {
+                if attempt <= 2 {
+                    Err(RancherError::Api(
+                        StatusCode::SERVICE_UNAVAILABLE,
+                        ApiStatus::parse("unavailable"),
+                    ))
+                } else {
+                    Ok(attempt)
+                }
+            }
+        })
+        .await;
+
+        assert_eq!(result.unwrap(), 3);
+        assert_eq!(calls, 3);
+    }
+
+    #[tokio::test]
+    async fn test_backoff_returns_permanent_failures_immediately() {
+        let mut calls = 0;
+
+        let result: Result<(), RancherError> = retry_with_backoff(5, Duration::ZERO, || {
+            calls += 1;
+            async { Err(RancherError::Forbidden(ApiStatus::parse("denied"))) }
+        })
+        .await;
+
+        assert!(matches!(result, Err(RancherError::Forbidden(_))));
+        assert_eq!(calls, 1);
+    }
+
+    #[test]
+    fn test_full_jitter_stays_below_backoff() {
+        let base = Duration::from_millis(100);
+        for _ in 0..100 {
+            assert!(full_jitter(base, 1) <= base);
+            assert!(full_jitter(base, 3) <= base * 4);
+            assert!(full_jitter(base, 40) <= MAX_BACKOFF_DELAY);
+        }
+    }
+}
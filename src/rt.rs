@@ -1,5 +1,7 @@
 use std::collections::HashMap;
 
+use futures::{Stream, StreamExt};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 use rancher_client::apis::{configuration::Configuration, Error, ResponseContent};
@@ -7,7 +9,11 @@ use reqwest::StatusCode;
 
 use rancher_client::{
     apis::management_cattle_io_v3_api::{
-        list_management_cattle_io_v3_role_template, ListManagementCattleIoV3RoleTemplateError,
+        create_management_cattle_io_v3_role_template, delete_management_cattle_io_v3_role_template,
+        list_management_cattle_io_v3_role_template, read_management_cattle_io_v3_role_template,
+        replace_management_cattle_io_v3_role_template, CreateManagementCattleIoV3RoleTemplateError,
+        DeleteManagementCattleIoV3RoleTemplateError, ListManagementCattleIoV3RoleTemplateError,
+        ReadManagementCattleIoV3RoleTemplateError, ReplaceManagementCattleIoV3RoleTemplateError,
     },
     models::io_cattle_managementv3_role_template::Context,
     models::{
@@ -16,6 +22,28 @@ use rancher_client::{
     },
 };
 
+use crate::diff::{hash_value, json_patch_between, prune_paths, DiffReport};
+use crate::error::{WithResource, WithResourceExt};
+use crate::metadata::{
+    exportable_metadata, format_content_hash, strip_for_content_hash, user_metadata_eq,
+    validate_metadata, validate_name, CONTENT_HASH_ANNOTATION,
+};
+use crate::paginate::{ContinueToken, ListPage, Paginator};
+use crate::request::{
+    get_bytes, get_list, is_write_success, list_query, optional_object, parse_response, send_request,
+    written_object, MERGE_PATCH_CONTENT_TYPE,
+};
+use crate::client::RequestOptions;
+use crate::retry::RetryPolicy;
+use crate::selector::FieldSelector;
+use crate::{
+    checked_limit, continue_token, merge_patch_touches, status_message, ApiStatus, RancherError, ResourceKey,
+    ResourceKind, DEFAULT_PAGE_SIZE,
+};
+
+/// The API path of the role template list
+pub(crate) const ROLE_TEMPLATES_PATH: &str = "/apis/management.cattle.io/v3/roletemplates";
+
 pub const RT_EXCLUDE_PATHS: &[&str] = &[
     "metadata.creationTimestamp",
     "metadata.finalizers",
@@ -27,7 +55,8 @@ pub const RT_EXCLUDE_PATHS: &[&str] = &[
     "metadata.uid",
 ];
 
-
+/// Default upper bound for the serialized size of a single role template sent to Rancher (1MB)
+pub const DEFAULT_MAX_REQUEST_SIZE: usize = 1024 * 1024;
 
 /// Get all role templates from an endpoint using the provided configuration
 ///
@@ -52,13 +81,81 @@ pub async fn get_role_templates(
     resource_version: Option<&str>,
     resource_version_match: Option<&str>,
     continue_: Option<&str>,
-) -> Result<IoCattleManagementv3RoleTemplateList, Error<ListManagementCattleIoV3RoleTemplateError>>
+) -> Result<IoCattleManagementv3RoleTemplateList, Error<ListManagementCattleIoV3RoleTemplateError>> {
+    let limit = checked_limit(limit, &RequestOptions::default())?;
+    let continue_ = continue_.map(|token| ContinueToken::new(token.to_string()));
+    list_role_templates(
+        configuration,
+        field_selector,
+        label_selector,
+        limit,
+        resource_version,
+        resource_version_match,
+        continue_.as_ref(),
+    )
+    .await
+    .map(|(list, _)| list)
+}
+
+/// Get a page of role templates along with the token of the next page
+///
+/// # Arguments
+///
+/// * `configuration` - The configuration to use for the request
+/// * `continue_` - The token returned with the previous page, `None` for the first page
+/// * `options` - The options of the request, `limit` is clamped to their largest page size
+///
+/// # Returns
+///
+/// * `IoCattleManagementv3RoleTemplateList` - The list of role templates
+/// * `Option<ContinueToken>` - The token to fetch the next page, `None` on the last page
+///
+/// # Errors
+///
+/// * `RancherError` - `Invalid` when the limit is zero or negative, or the error that occurred while trying to get the role templates
+///
+#[async_backtrace::framed]
+pub async fn get_role_templates_with_token(
+    configuration: &Configuration,
+    field_selector: Option<&str>,
+    label_selector: Option<&str>,
+    limit: Option<i32>,
+    resource_version: Option<&str>,
+    resource_version_match: Option<&str>,
+    continue_: Option<&ContinueToken>,
+    options: &RequestOptions,
+) -> Result<(IoCattleManagementv3RoleTemplateList, Option<ContinueToken>), RancherError> {
+    let limit = checked_limit(limit, options)?;
+    list_role_templates(
+        configuration,
+        field_selector,
+        label_selector,
+        limit,
+        resource_version,
+        resource_version_match,
+        continue_,
+    )
+    .await
+    .map_err(RancherError::from)
+}
+
+/// List role templates with an already checked limit, returning the token of the next page
+#[async_backtrace::framed]
+async fn list_role_templates(
+    configuration: &Configuration,
+    field_selector: Option<&str>,
+    label_selector: Option<&str>,
+    limit: Option<i32>,
+    resource_version: Option<&str>,
+    resource_version_match: Option<&str>,
+    continue_: Option<&ContinueToken>,
+) -> Result<(IoCattleManagementv3RoleTemplateList, Option<ContinueToken>), Error<ListManagementCattleIoV3RoleTemplateError>>
 {
     let result = list_management_cattle_io_v3_role_template(
         configuration,
         None,
         None,
-        continue_,
+        continue_.map(ContinueToken::as_str),
         field_selector,
         label_selector,
         limit,
@@ -80,8 +177,11 @@ pub async fn get_role_templates(
             match response_content.status {
                 StatusCode::OK => {
                     // Try to deserialize the content into IoCattleManagementv3RoleTemplateList (Status200 case)
-                    match serde_json::from_str(&response_content.content) {
-                        Ok(data) => Ok(data),
+                    match serde_json::from_str::<IoCattleManagementv3RoleTemplateList>(&response_content.content) {
+                        Ok(data) => {
+                            let token = continue_token(data.metadata.as_deref()).map(ContinueToken::new);
+                            Ok((data, token))
+                        }
                         Err(deserialize_err) => Err(Error::Serde(deserialize_err)),
                     }
                 }
@@ -108,353 +208,2863 @@ pub async fn get_role_templates(
     }
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
-pub struct RoleTemplate {
-
-    /// Administrative if true, this RoleTemplate is used to grant administrative privileges. Default to false.
-    /// This field is not set in the API, but is used to determine if the role template is administrative
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub administrative: Option<bool>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub annotations: Option<HashMap<String, String>>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub builtin: Option<bool>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub cluster_creator_default: Option<bool>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub context: Option<Context>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub description: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub display_name: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub external: Option<bool>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub hidden: Option<bool>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub labels: Option<HashMap<String, String>>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub locked: Option<bool>,
-    pub id: String,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub project_creator_default: Option<bool>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub role_template_names: Option<Vec<String>>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub rules: Option<Vec<IoCattleManagementv3GlobalRoleRulesInner>>,
+/// Get a single page of role templates
+///
+/// # Arguments
+///
+/// * `configuration` - The configuration to use for the request
+/// * `field_selector` - A selector to restrict the role templates by their fields
+/// * `label_selector` - A selector to restrict the role templates by their labels
+/// * `limit` - The maximum number of role templates to return
+/// * `continue_` - The continue token of the previous page
+///
+/// # Returns
+///
+/// * `ListPage<IoCattleManagementv3RoleTemplate>` - The role templates, continue token and remaining item count
+///
+/// # Errors
+///
+/// * `Error<ListManagementCattleIoV3RoleTemplateError>` - The error that occurred while trying to get the role templates
+///
+#[async_backtrace::framed]
+pub async fn get_role_templates_page(
+    configuration: &Configuration,
+    field_selector: Option<&str>,
+    label_selector: Option<&str>,
+    limit: Option<i32>,
+    continue_: Option<&str>,
+) -> Result<ListPage<IoCattleManagementv3RoleTemplate>, Error<ListManagementCattleIoV3RoleTemplateError>> {
+    let list = get_role_templates(
+        configuration,
+        field_selector,
+        label_selector,
+        limit,
+        None,
+        None,
+        continue_,
+    )
+    .await?;
+    Ok(ListPage::from_list(list.items, list.metadata.as_deref()))
 }
 
-impl RoleTemplate {
-    pub fn new(
-        administrative: Option<bool>,
-        annotations: Option<HashMap<String, String>>,
-        builtin: Option<bool>,
-        cluster_creator_default: Option<bool>,
-        context: Option<Context>,
-        description: Option<String>,
-        display_name: Option<String>,
-        external: Option<bool>,
-        hidden: Option<bool>,
-        labels: Option<HashMap<String, String>>,
-        locked: Option<bool>,
-        id: String,
-        project_creator_default: Option<bool>,
-        role_template_names: Option<Vec<String>>,
-        rules: Option<Vec<IoCattleManagementv3GlobalRoleRulesInner>>,
-    ) -> Self {
-        RoleTemplate {
-            annotations,
-            administrative,
-            builtin,
-            cluster_creator_default,
-            context,
-            description,
-            display_name,
-            external,
-            hidden,
-            labels,
-            locked,
-            id,
-            project_creator_default,
-            role_template_names,
-            rules,
+/// Get role templates, deserializing the list straight from the response bytes
+///
+/// `get_role_templates` goes through the generated client, which decodes the whole body into a
+/// `String` before parsing it. This variant parses the raw bytes with `serde_json::from_slice`,
+/// skipping the decoded copy of the body, which matters for large lists. Non-200 bodies are still
+/// decoded as text for the `UnknownValue` error.
+///
+/// # Arguments
+///
+/// * `configuration` - The configuration to use for the request
+/// * `field_selector` - A selector to restrict the role templates by their fields
+/// * `label_selector` - A selector to restrict the role templates by their labels
+/// * `limit` - The maximum number of role templates to return
+/// * `continue_` - The continue token of the previous page
+///
+/// # Returns
+///
+/// * `IoCattleManagementv3RoleTemplateList` - The list of role templates
+///
+/// # Errors
+///
+/// * `Error<ListManagementCattleIoV3RoleTemplateError>` - The error that occurred while trying to get the role templates
+///
+#[async_backtrace::framed]
+pub async fn get_role_templates_from_bytes(
+    configuration: &Configuration,
+    field_selector: Option<&str>,
+    label_selector: Option<&str>,
+    limit: Option<i32>,
+    continue_: Option<&str>,
+) -> Result<IoCattleManagementv3RoleTemplateList, Error<ListManagementCattleIoV3RoleTemplateError>>
+{
+    let limit = checked_limit(limit, &RequestOptions::default())?;
+    let query = list_query(field_selector, label_selector, limit, continue_);
+
+    let (status, body) = get_bytes(configuration, ROLE_TEMPLATES_PATH, &query).await?;
+    match status {
+        StatusCode::OK => serde_json::from_slice(&body).map_err(Error::Serde),
+        _ => {
+            // If not status 200, treat as UnknownValue
+            let content = String::from_utf8_lossy(&body).into_owned();
+            match serde_json::from_str::<serde_json::Value>(&content) {
+                Ok(unknown_data) => Err(Error::ResponseError(ResponseContent {
+                    status,
+                    content,
+                    entity: Some(ListManagementCattleIoV3RoleTemplateError::UnknownValue(unknown_data)),
+                })),
+                Err(deserialize_err) => Err(Error::Serde(deserialize_err)),
+            }
         }
     }
 }
 
-impl TryFrom<IoCattleManagementv3RoleTemplate> for RoleTemplate {
-    type Error = &'static str;
-
-    fn try_from(value: IoCattleManagementv3RoleTemplate) -> Result<Self, Self::Error> {
-        let metadata: IoK8sApimachineryPkgApisMetaV1ObjectMeta = value.metadata.ok_or("missing metadata")?;
+/// Get every role template, following the continue token
+///
+/// The continue token pins every page to the resource version of the first page, so the result is
+/// a consistent snapshot. The API server rejects an explicit `resourceVersion` alongside a
+/// continue token, so none is sent on the following pages. A page rate limited by the server is
+/// retried with the default `RetryPolicy`, one failing transiently with an exponential backoff, and
+/// every request times out after the timeout of `options`.
+///
+/// # Arguments
+///
+/// * `configuration` - The configuration to use for the requests
+/// * `field_selector` - A selector to restrict the role templates by their fields
+/// * `label_selector` - A selector to restrict the role templates by their labels
+/// * `limit` - The page size, defaults to `DEFAULT_PAGE_SIZE` and is clamped to the largest page size of `options`
+/// * `options` - The timeout and largest page size of the requests
+///
+/// # Returns
+///
+/// * `Vec<RoleTemplate>` - The role templates of every page
+///
+/// # Errors
+///
+/// * `RancherError` - The first error that occurred while fetching a page or converting a role template
+///
+#[async_backtrace::framed]
+pub async fn get_all_role_templates(
+    configuration: &Configuration,
+    field_selector: Option<&str>,
+    label_selector: Option<&str>,
+    limit: Option<i32>,
+    options: &RequestOptions,
+) -> Result<Vec<RoleTemplate>, RancherError> {
+    let limit = checked_limit(limit.or(Some(DEFAULT_PAGE_SIZE)), options)?;
+    let items = Paginator::new(move |continue_: Option<String>| async move {
+        let query = list_query(field_selector, label_selector, limit, continue_.as_deref());
+        let list: IoCattleManagementv3RoleTemplateList = get_list(
+            configuration,
+            ROLE_TEMPLATES_PATH,
+            &query,
+            &RetryPolicy::default(),
+            options,
+        )
+        .await?;
+        Ok::<_, RancherError>((list.items, continue_token(list.metadata.as_deref())))
+    })
+    .collect_all()
+    .await?;
 
-        let administrative: Option<bool> = value.administrative;
-        let annotations: Option<HashMap<String, String>> = metadata.annotations;
-        let builtin: Option<bool> = value.builtin;
-        let cluster_creator_default: Option<bool> = value.cluster_creator_default;
-        let context = value.context;
-        let description: Option<String> = value.description;
-        let display_name: Option<String> = value.display_name;
-        let external: Option<bool> = value.external;
-        let hidden: Option<bool> = value.hidden;
-        let labels: Option<HashMap<String, String>> = metadata.labels;
-        let locked: Option<bool> = value.locked;
-        let project_creator_default: Option<bool> = value.project_creator_default;
-        let role_template_names: Option<Vec<String>> = value.role_template_names;
-        let rules: Option<Vec<IoCattleManagementv3GlobalRoleRulesInner>> = value.rules;
+    items
+        .into_iter()
+        .map(|item| RoleTemplate::try_from(item).map_err(|e| RancherError::Invalid(e.to_string())))
+        .collect()
+}
 
-        Ok(RoleTemplate {
-            administrative,
-            annotations,
-            builtin,
-            cluster_creator_default,
-            context,
-            description,
-            display_name,
-            external,
-            hidden,
-            id: metadata.name.ok_or("missing metadata.name")?,
-            labels,
-            locked,
-            project_creator_default,
-            role_template_names,
-            rules,
-        })
-    }
+/// Stream every role template, fetching the next page when the current one is drained
+///
+/// Only one page is held in memory at a time, pages are `DEFAULT_PAGE_SIZE` items clamped to the
+/// largest page size of `options`.
+///
+/// # Arguments
+///
+/// * `configuration` - The configuration to use for the requests
+/// * `options` - The timeout and largest page size of the requests
+///
+/// # Returns
+///
+/// * `impl Stream<Item = Result<RoleTemplate, RancherError>>` - The role templates, the stream ends after the first failed request
+pub fn stream_role_templates<'a>(
+    configuration: &'a Configuration,
+    options: &'a RequestOptions,
+) -> impl Stream<Item = Result<RoleTemplate, RancherError>> + 'a {
+    Paginator::new(move |continue_: Option<String>| async move {
+        let continue_ = continue_.map(ContinueToken::new);
+        get_role_templates_with_token(
+            configuration,
+            None,
+            None,
+            Some(DEFAULT_PAGE_SIZE),
+            None,
+            None,
+            continue_.as_ref(),
+            options,
+        )
+        .await
+        .map(|(page, next)| (page.items, next.map(ContinueToken::into_inner)))
+    })
+    .into_stream()
+    .map(|item| {
+        item.and_then(|item| RoleTemplate::try_from(item).map_err(|e| RancherError::Invalid(e.to_string())))
+    })
 }
 
-impl TryFrom<RoleTemplate> for IoCattleManagementv3RoleTemplate {
-    type Error = &'static str;
+/// Above this many names a single list call is cheaper than one request per name
+pub const BATCH_LIST_THRESHOLD: usize = 5;
 
-    fn try_from(value: RoleTemplate) -> Result<Self, Self::Error> {
-        let metadata = IoK8sApimachineryPkgApisMetaV1ObjectMeta {
-            annotations: value.annotations,
-            labels: value.labels,
-            name: Some(value.id.clone()),
-            ..Default::default()
-        };
+/// How `get_role_templates_by_names` fetches the requested role templates
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FetchStrategy {
+    /// List every role template once and filter client-side
+    SingleList,
+    /// Fetch each name individually using a `metadata.name` field selector
+    PerName,
+}
 
-        let context = value.context;
-        let administrative: Option<bool> = value.administrative;
-        let builtin: Option<bool> = value.builtin;
-        let cluster_creator_default: Option<bool> = value.cluster_creator_default;
-        let description: Option<String> = value.description;
-        let display_name: Option<String> = value.display_name;
-        let external: Option<bool> = value.external;
-        let hidden: Option<bool> = value.hidden;
-        let locked: Option<bool> = value.locked;
-        let project_creator_default: Option<bool> = value.project_creator_default;
-        let role_template_names: Option<Vec<String>> = value.role_template_names;
-        let rules: Option<Vec<IoCattleManagementv3GlobalRoleRulesInner>> = value.rules;
+/// Pick the fetch strategy for the given number of names
+pub fn fetch_strategy(name_count: usize) -> FetchStrategy {
+    if name_count > BATCH_LIST_THRESHOLD {
+        FetchStrategy::SingleList
+    } else {
+        FetchStrategy::PerName
+    }
+}
 
-        Ok(IoCattleManagementv3RoleTemplate {
-            administrative,
-            api_version: Some("management.cattle.io/v3".to_string()),
-            builtin,
-            cluster_creator_default,
-            context,
-            description,
-            display_name,
-            external,
-            hidden,
-            kind: Some("RoleTemplate".to_string()),
-            locked,
-            metadata: Some(metadata),
-            project_creator_default,
-            role_template_names,
-            rules,
-        })
+/// Get several role templates by name, coalescing the reads where possible
+///
+/// For large name sets a single list call is made and filtered client-side, for small sets each
+/// name is fetched individually. Every name is checked with `validate_name` before any request.
+///
+/// # Arguments
+///
+/// * `configuration` - The configuration to use for the request
+/// * `names` - The names of the role templates to get
+///
+/// # Returns
+///
+/// * `HashMap<String, Option<RoleTemplate>>` - Every requested name mapped to its role template, `None` when it does not exist
+///
+/// # Errors
+///
+/// * `RancherError` - `Invalid` when a name is not a valid object name, or the error that occurred while trying to get the role templates
+#[async_backtrace::framed]
+pub async fn get_role_templates_by_names(
+    configuration: &Configuration,
+    names: &[String],
+) -> Result<HashMap<String, Option<RoleTemplate>>, RancherError> {
+    for name in names {
+        validate_name(name).map_err(|e| RancherError::Invalid(format!("{}: {}", name, e)))?;
     }
+
+    let items = match fetch_strategy(names.len()) {
+        FetchStrategy::SingleList => {
+            get_role_templates(configuration, None, None, None, None, None, None)
+                .await?
+                .items
+        }
+        FetchStrategy::PerName => {
+            let mut items = Vec::new();
+            for name in names {
+                let field_selector = FieldSelector::new().eq("metadata.name", name).to_string();
+                let list = get_role_templates(
+                    configuration,
+                    Some(&field_selector),
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                )
+                .await?;
+                items.extend(list.items);
+            }
+            items
+        }
+    };
+
+    Ok(resolve_role_template_names(names, items))
 }
 
-impl PartialEq<RoleTemplate> for IoCattleManagementv3RoleTemplate {
-    fn eq(&self, other: &RoleTemplate) -> bool {
-        let lhs = self.metadata.as_ref().and_then(|m| m.name.clone());
-        let rhs = Some(other.id.clone());
+/// Map every requested name to the matching role template in `items`
+fn resolve_role_template_names(
+    names: &[String],
+    items: Vec<IoCattleManagementv3RoleTemplate>,
+) -> HashMap<String, Option<RoleTemplate>> {
+    let mut found: HashMap<String, RoleTemplate> = items
+        .into_iter()
+        .filter_map(|item| RoleTemplate::try_from(item).ok())
+        .map(|rt| (rt.id.clone(), rt))
+        .collect();
 
-        lhs == rhs
-            && self.administrative == other.administrative
-            && self.builtin == other.builtin
-            && self.cluster_creator_default == other.cluster_creator_default
-            && self.context == other.context
-            && self.description == other.description
-            && self.display_name == other.display_name
-            && self.external == other.external
-            && self.hidden == other.hidden
-            && self.locked == other.locked
-            && self.project_creator_default == other.project_creator_default
-            && self.role_template_names == other.role_template_names
-            && self.rules == other.rules
+    names
+        .iter()
+        .map(|name| (name.clone(), found.remove(name)))
+        .collect()
+}
+
+/// Get a role template by name
+///
+/// # Arguments
+///
+/// * `configuration` - The configuration to use for the request
+/// * `name` - The name of the role template
+///
+/// # Returns
+///
+/// * `Option<RoleTemplate>` - The role template, or `None` when it does not exist
+///
+/// # Errors
+///
+/// * `RancherError` - The error that occurred while reading or converting the role template
+///
+#[async_backtrace::framed]
+pub async fn get_role_template(
+    configuration: &Configuration,
+    name: &str,
+) -> Result<Option<RoleTemplate>, RancherError> {
+    let result = read_management_cattle_io_v3_role_template(configuration, name, None, None).await;
+    optional_object::<IoCattleManagementv3RoleTemplate, _>(
+        result,
+        ReadManagementCattleIoV3RoleTemplateError::UnknownValue,
+    )?
+    .map(RoleTemplate::try_from)
+    .transpose()
+    .map_err(RancherError::conversion)
+}
+
+/// Create a role template
+///
+/// Builtin role templates are managed by Rancher and are rejected before sending.
+///
+/// # Arguments
+///
+/// * `configuration` - The configuration to use for the request
+/// * `rt` - The role template to create
+///
+/// # Returns
+///
+/// * `RoleTemplate` - The role template as created by the server
+///
+/// # Errors
+///
+/// * `WithResource<RancherError>` - The role template the error is about, along with the error: `Invalid` when the role template is builtin, too large or has invalid labels, annotations or rules, or the error that occurred while converting or creating the role template
+///
+#[async_backtrace::framed]
+pub async fn create_role_template(
+    configuration: &Configuration,
+    rt: &RoleTemplate,
+) -> Result<RoleTemplate, WithResource<RancherError>> {
+    send_create_role_template(configuration, rt)
+        .await
+        .with_resource(ResourceKind::RoleTemplate, None, &rt.id)
+}
+
+/// Send the request of `create_role_template`, the error is wrapped with the role template by the caller
+async fn send_create_role_template(
+    configuration: &Configuration,
+    rt: &RoleTemplate,
+) -> Result<RoleTemplate, RancherError> {
+    if rt.builtin == Some(true) {
+        return Err(RancherError::Invalid(format!(
+            "role template {} is builtin and managed by Rancher",
+            rt.id
+        )));
+    }
+    validate_metadata(rt.annotations.as_ref(), rt.labels.as_ref())
+        .map_err(|e| RancherError::Invalid(e.to_string()))?;
+    check_rules(rt).map_err(RancherError::Invalid)?;
+    check_request_size(rt, None).map_err(RancherError::Invalid)?;
+    let body = IoCattleManagementv3RoleTemplate::try_from(rt.clone()).map_err(RancherError::conversion)?;
+
+    let result = create_management_cattle_io_v3_role_template(configuration, body, None, None, None, None).await;
+    match result {
+        Err(e) => Err(e.into()),
+        Ok(response_content) => {
+            // Match on the status code and deserialize accordingly
+            match response_content.status {
+                status if is_write_success(status) => {
+                    // Deserialize the created IoCattleManagementv3RoleTemplate when the body holds it
+                    match written_object::<IoCattleManagementv3RoleTemplate>(&response_content.content) {
+                        Ok(Some(data)) => RoleTemplate::try_from(data).map_err(RancherError::conversion),
+                        // accepted without the object, it is created as sent
+                        Ok(None) => Ok(rt.clone()),
+                        Err(deserialize_err) => Err(RancherError::Serde(deserialize_err)),
+                    }
+                }
+                _ => {
+                    // If not a success, treat as UnknownValue
+                    match serde_json::from_str::<serde_json::Value>(&response_content.content) {
+                        Ok(unknown_data) => {
+                            // Handle the unknown response
+                            Err(Error::ResponseError(ResponseContent {
+                                status: response_content.status,
+                                content: response_content.content,
+                                entity: Some(CreateManagementCattleIoV3RoleTemplateError::UnknownValue(
+                                    unknown_data,
+                                )),
+                            })
+                            .into())
+                        }
+                        Err(deserialize_err) => Err(RancherError::Serde(deserialize_err)),
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Replace an existing role template
+///
+/// `RoleTemplate` does not carry a resource version, so the version the template was read at
+/// must be passed in, otherwise Rancher rejects the update.
+///
+/// # Arguments
+///
+/// * `configuration` - The configuration to use for the request
+/// * `rt` - The desired role template, `id` names the object to replace
+/// * `resource_version` - The resource version the role template was read at
+///
+/// # Returns
+///
+/// * `RoleTemplate` - The role template as updated by the server
+///
+/// # Errors
+///
+/// * `WithResource<RancherError>` - The role template the error is about, along with the error: `Invalid` when no resource version is given or the role template has invalid labels, annotations or rules, `Conflict` when the template changed on the server, or the error that occurred while converting or replacing the role template
+///
+#[async_backtrace::framed]
+pub async fn replace_role_template(
+    configuration: &Configuration,
+    rt: &RoleTemplate,
+    resource_version: Option<&str>,
+) -> Result<RoleTemplate, WithResource<RancherError>> {
+    send_replace_role_template(configuration, rt, resource_version)
+        .await
+        .with_resource(ResourceKind::RoleTemplate, None, &rt.id)
+}
+
+/// The body replacing a role template at `resource_version`, checked like `replace_role_template` does
+///
+/// # Errors
+///
+/// * `RancherError` - `Invalid` when no resource version is given or the role template has invalid labels, annotations or rules, or the error that occurred while converting the role template
+pub(crate) fn role_template_replace_body(
+    rt: &RoleTemplate,
+    resource_version: Option<&str>,
+) -> Result<IoCattleManagementv3RoleTemplate, RancherError> {
+    let resource_version = resource_version.ok_or_else(RancherError::missing_resource_version)?;
+    validate_metadata(rt.annotations.as_ref(), rt.labels.as_ref())
+        .map_err(|e| RancherError::Invalid(e.to_string()))?;
+    check_rules(rt).map_err(RancherError::Invalid)?;
+    check_request_size(rt, None).map_err(RancherError::Invalid)?;
+    let mut body = IoCattleManagementv3RoleTemplate::try_from(rt.clone()).map_err(RancherError::conversion)?;
+    if let Some(metadata) = body.metadata.as_mut() {
+        metadata.resource_version = Some(resource_version.to_string());
+    }
+    Ok(body)
+}
+
+/// Send the request of `replace_role_template`, the error is wrapped with the role template by the caller
+async fn send_replace_role_template(
+    configuration: &Configuration,
+    rt: &RoleTemplate,
+    resource_version: Option<&str>,
+) -> Result<RoleTemplate, RancherError> {
+    let body = role_template_replace_body(rt, resource_version)?;
+
+    let result = replace_management_cattle_io_v3_role_template(configuration, &rt.id, body, None, None, None, None).await;
+    match result {
+        Err(e) => Err(e.into()),
+        Ok(response_content) => {
+            // Match on the status code and deserialize accordingly
+            match response_content.status {
+                status if is_write_success(status) => {
+                    // Deserialize the updated IoCattleManagementv3RoleTemplate when the body holds it
+                    match written_object::<IoCattleManagementv3RoleTemplate>(&response_content.content) {
+                        Ok(Some(data)) => RoleTemplate::try_from(data).map_err(RancherError::conversion),
+                        // accepted without the object, it is updated as sent
+                        Ok(None) => Ok(rt.clone()),
+                        Err(deserialize_err) => Err(RancherError::Serde(deserialize_err)),
+                    }
+                }
+                StatusCode::CONFLICT => {
+                    Err(RancherError::from_response(StatusCode::CONFLICT, &response_content.content))
+                }
+                _ => {
+                    // If not a success, treat as UnknownValue
+                    match serde_json::from_str::<serde_json::Value>(&response_content.content) {
+                        Ok(unknown_data) => {
+                            // Handle the unknown response
+                            Err(Error::ResponseError(ResponseContent {
+                                status: response_content.status,
+                                content: response_content.content,
+                                entity: Some(ReplaceManagementCattleIoV3RoleTemplateError::UnknownValue(
+                                    unknown_data,
+                                )),
+                            })
+                            .into())
+                        }
+                        Err(deserialize_err) => Err(RancherError::Serde(deserialize_err)),
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Delete a role template
+///
+/// Rancher refuses to delete builtin role templates, the refusal is surfaced as `Forbidden` (403)
+/// or `Invalid` (422) with the server's message.
+///
+/// # Arguments
+///
+/// * `configuration` - The configuration to use for the request
+/// * `name` - The name of the role template to delete
+///
+/// # Errors
+///
+/// * `WithResource<RancherError>` - The role template the error is about, along with the error: `NotFound` when the role template does not exist, so callers can treat it as already deleted, `Forbidden` or `Invalid` when the server refuses the deletion, or the error that occurred while deleting the role template
+///
+#[async_backtrace::framed]
+pub async fn delete_role_template(
+    configuration: &Configuration,
+    name: &str,
+) -> Result<(), WithResource<RancherError>> {
+    send_delete_role_template(configuration, name)
+        .await
+        .with_resource(ResourceKind::RoleTemplate, None, name)
+}
+
+/// Send the request of `delete_role_template`, the error is wrapped with the role template by the caller
+async fn send_delete_role_template(
+    configuration: &Configuration,
+    name: &str,
+) -> Result<(), RancherError> {
+    let result = delete_management_cattle_io_v3_role_template(
+        configuration,
+        name,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+    )
+    .await;
+    match result {
+        Err(e) => Err(e.into()),
+        Ok(response_content) => {
+            // Match on the status code
+            match response_content.status {
+                status if is_write_success(status) => Ok(()),
+                StatusCode::NOT_FOUND => Err(RancherError::NotFound),
+                StatusCode::FORBIDDEN => Err(RancherError::Forbidden(ApiStatus::parse(&response_content.content))),
+                StatusCode::UNPROCESSABLE_ENTITY => Err(RancherError::Invalid(status_message(&response_content.content))),
+                _ => {
+                    // Otherwise treat as UnknownValue
+                    match serde_json::from_str::<serde_json::Value>(&response_content.content) {
+                        Ok(unknown_data) => {
+                            // Handle the unknown response
+                            Err(Error::ResponseError(ResponseContent {
+                                status: response_content.status,
+                                content: response_content.content,
+                                entity: Some(DeleteManagementCattleIoV3RoleTemplateError::UnknownValue(
+                                    unknown_data,
+                                )),
+                            })
+                            .into())
+                        }
+                        Err(deserialize_err) => Err(RancherError::Serde(deserialize_err)),
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Apply a JSON merge patch (RFC 7386) to a role template
+///
+/// Merge patches replace arrays as a whole: a patch containing `rules` overwrites every rule of
+/// the template. Use `role_template_field_patch` to change a single scalar field.
+/// The patch is rejected before sending when it touches any of `RT_EXCLUDE_PATHS`.
+///
+/// # Arguments
+///
+/// * `configuration` - The configuration to use for the request
+/// * `name` - The name of the role template
+/// * `patch` - The merge patch, e.g. `{"description": "Read only access"}`
+///
+/// # Returns
+///
+/// * `RoleTemplate` - The role template as patched by the server
+///
+/// # Errors
+///
+/// * `WithResource<RancherError>` - The role template the error is about, along with the error: `Invalid` when the patch touches a server managed field, or the error that occurred while patching the role template
+///
+#[async_backtrace::framed]
+pub async fn patch_role_template(
+    configuration: &Configuration,
+    name: &str,
+    patch: serde_json::Value,
+) -> Result<RoleTemplate, WithResource<RancherError>> {
+    send_patch_role_template(configuration, name, patch)
+        .await
+        .with_resource(ResourceKind::RoleTemplate, None, name)
+}
+
+/// Send the request of `patch_role_template`, the error is wrapped with the role template by the caller
+async fn send_patch_role_template(
+    configuration: &Configuration,
+    name: &str,
+    patch: serde_json::Value,
+) -> Result<RoleTemplate, RancherError> {
+    if let Some(path) = RT_EXCLUDE_PATHS
+        .iter()
+        .find(|path| merge_patch_touches(&patch, path))
+    {
+        return Err(RancherError::immutable_field(path));
+    }
+
+    let path = ResourceKind::RoleTemplate.api_path(None, name);
+    let response_content = send_request(
+        configuration,
+        reqwest::Method::PATCH,
+        &path,
+        MERGE_PATCH_CONTENT_TYPE,
+        Some(&patch),
+    )
+    .await?;
+    let patched: IoCattleManagementv3RoleTemplate =
+        serde_json::from_value(parse_response(response_content)?).map_err(RancherError::Serde)?;
+    RoleTemplate::try_from(patched).map_err(RancherError::conversion)
+}
+
+/// The scalar fields of a role template that can be patched on their own
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RoleTemplateField {
+    Administrative,
+    ClusterCreatorDefault,
+    Description,
+    DisplayName,
+    External,
+    Hidden,
+    Locked,
+    ProjectCreatorDefault,
+}
+
+impl RoleTemplateField {
+    /// The name of the field in the Rancher API
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RoleTemplateField::Administrative => "administrative",
+            RoleTemplateField::ClusterCreatorDefault => "clusterCreatorDefault",
+            RoleTemplateField::Description => "description",
+            RoleTemplateField::DisplayName => "displayName",
+            RoleTemplateField::External => "external",
+            RoleTemplateField::Hidden => "hidden",
+            RoleTemplateField::Locked => "locked",
+            RoleTemplateField::ProjectCreatorDefault => "projectCreatorDefault",
+        }
+    }
+
+    fn is_string(&self) -> bool {
+        matches!(self, RoleTemplateField::Description | RoleTemplateField::DisplayName)
+    }
+}
+
+/// Build a merge patch that changes a single scalar field of a role template
+///
+/// # Arguments
+///
+/// * `field` - The field to change
+/// * `value` - The new value, a string for `Description`/`DisplayName`, a bool otherwise, or `null` to unset the field
+///
+/// # Returns
+///
+/// * `serde_json::Value` - The merge patch, to pass to `patch_role_template`
+///
+/// # Errors
+///
+/// * `String` - The value does not match the type of the field
+pub fn role_template_field_patch(
+    field: RoleTemplateField,
+    value: serde_json::Value,
+) -> Result<serde_json::Value, String> {
+    let valid = match &value {
+        serde_json::Value::Null => true,
+        serde_json::Value::String(_) => field.is_string(),
+        serde_json::Value::Bool(_) => !field.is_string(),
+        _ => false,
+    };
+    if !valid {
+        return Err(format!("Invalid value for {}: {}", field.as_str(), value));
+    }
+    Ok(serde_json::json!({ field.as_str(): value }))
+}
+
+/// A role template as managed by this crate
+///
+/// `Default` leaves every optional field unset and `id` empty, for use with struct update syntax
+/// (`RoleTemplate { id: "x".into(), ..Default::default() }`). The `id` must be set before the
+/// role template is sent to Rancher.
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq, Default)]
+pub struct RoleTemplate {
+
+    /// Administrative if true, this RoleTemplate is used to grant administrative privileges. Default to false.
+    /// This field is not set in the API, but is used to determine if the role template is administrative
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub administrative: Option<bool>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "crate::metadata::serialize_sorted"
+    )]
+    pub annotations: Option<HashMap<String, String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub builtin: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cluster_creator_default: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schemars(with = "Option<crate::schema::ContextSchema>")]
+    pub context: Option<Context>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub display_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub external: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hidden: Option<bool>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "crate::metadata::serialize_sorted"
+    )]
+    pub labels: Option<HashMap<String, String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub locked: Option<bool>,
+    pub id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub project_creator_default: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub role_template_names: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schemars(with = "Option<Vec<crate::schema::PolicyRuleSchema>>")]
+    pub rules: Option<Vec<IoCattleManagementv3GlobalRoleRulesInner>>,
+}
+
+impl RoleTemplate {
+    /// Start building a role template, see `RoleTemplateBuilder`
+    pub fn builder() -> RoleTemplateBuilder {
+        RoleTemplateBuilder::default()
+    }
+
+    /// The identity of the role template, its ID, role templates are not namespaced
+    pub fn key(&self) -> ResourceKey {
+        ResourceKey::new("", self.id.clone())
+    }
+
+    pub fn new(
+        administrative: Option<bool>,
+        annotations: Option<HashMap<String, String>>,
+        builtin: Option<bool>,
+        cluster_creator_default: Option<bool>,
+        context: Option<Context>,
+        description: Option<String>,
+        display_name: Option<String>,
+        external: Option<bool>,
+        hidden: Option<bool>,
+        labels: Option<HashMap<String, String>>,
+        locked: Option<bool>,
+        id: String,
+        project_creator_default: Option<bool>,
+        role_template_names: Option<Vec<String>>,
+        rules: Option<Vec<IoCattleManagementv3GlobalRoleRulesInner>>,
+    ) -> Self {
+        RoleTemplate {
+            annotations,
+            administrative,
+            builtin,
+            cluster_creator_default,
+            context,
+            description,
+            display_name,
+            external,
+            hidden,
+            labels,
+            locked,
+            id,
+            project_creator_default,
+            role_template_names,
+            rules,
+        }
+    }
+
+    /// Serialize the role template to YAML, suitable for committing to git
+    ///
+    /// Only the user managed labels and annotations are written, see `exportable_metadata`, and
+    /// the fields of `RT_EXCLUDE_PATHS` are left out.
+    ///
+    /// # Returns
+    ///
+    /// * `String` - The YAML document
+    ///
+    /// # Errors
+    ///
+    /// * `serde_yaml::Error` - The error that occurred while serializing
+    pub fn to_yaml(&self) -> Result<String, serde_yaml::Error> {
+        let mut rt = self.clone();
+        rt.annotations = exportable_metadata(self.annotations.as_ref());
+        rt.labels = exportable_metadata(self.labels.as_ref());
+        crate::export::to_export_yaml(&rt, RT_EXCLUDE_PATHS)
+    }
+
+    /// Deserialize a role template from a YAML document written by `to_yaml`
+    ///
+    /// # Arguments
+    ///
+    /// * `s` - The YAML document
+    ///
+    /// # Returns
+    ///
+    /// * `RoleTemplate` - The role template
+    ///
+    /// # Errors
+    ///
+    /// * `serde_yaml::Error` - The error that occurred while parsing the document
+    pub fn from_yaml(s: &str) -> Result<Self, serde_yaml::Error> {
+        serde_yaml::from_str(s)
+    }
+
+    /// Serialize the role template to a manifest that `kubectl apply -f` accepts
+    ///
+    /// Unlike `to_yaml`, the API form is written, with `apiVersion: management.cattle.io/v3`,
+    /// `kind: RoleTemplate` and the ID as `metadata.name`.
+    ///
+    /// # Returns
+    ///
+    /// * `String` - The YAML manifest
+    ///
+    /// # Errors
+    ///
+    /// * `serde_yaml::Error` - The error that occurred while serializing
+    pub fn to_manifest_yaml(&self) -> Result<String, serde_yaml::Error> {
+        let document = crate::export::role_template_document(self)
+            .map_err(<serde_yaml::Error as serde::ser::Error>::custom)?;
+        serde_yaml::to_string(&document)
+    }
+}
+
+/// A fluent builder of `RoleTemplate`, for code constructing desired state
+///
+/// Fields that are not set are left to Rancher, except `builtin` which defaults to `false` since
+/// builtin role templates cannot be created.
+///
+/// ```ignore
+/// let rt = RoleTemplate::builder()
+///     .id("read-pods")
+///     .context(Context::Project)
+///     .rule(&[""], &["pods"], &["get", "list"])
+///     .build()?;
+/// ```
+#[derive(Debug, Clone)]
+pub struct RoleTemplateBuilder {
+    template: RoleTemplate,
+}
+
+impl Default for RoleTemplateBuilder {
+    fn default() -> Self {
+        RoleTemplateBuilder {
+            template: RoleTemplate {
+                builtin: Some(false),
+                ..Default::default()
+            },
+        }
+    }
+}
+
+impl RoleTemplateBuilder {
+    /// The name of the role template
+    pub fn id(mut self, id: impl Into<String>) -> Self {
+        self.template.id = id.into();
+        self
+    }
+
+    /// Whether the role template is granted on clusters or on projects
+    pub fn context(mut self, context: Context) -> Self {
+        self.template.context = Some(context);
+        self
+    }
+
+    pub fn display_name(mut self, display_name: impl Into<String>) -> Self {
+        self.template.display_name = Some(display_name.into());
+        self
+    }
+
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.template.description = Some(description.into());
+        self
+    }
+
+    pub fn administrative(mut self, administrative: bool) -> Self {
+        self.template.administrative = Some(administrative);
+        self
+    }
+
+    pub fn builtin(mut self, builtin: bool) -> Self {
+        self.template.builtin = Some(builtin);
+        self
+    }
+
+    pub fn cluster_creator_default(mut self, cluster_creator_default: bool) -> Self {
+        self.template.cluster_creator_default = Some(cluster_creator_default);
+        self
+    }
+
+    pub fn project_creator_default(mut self, project_creator_default: bool) -> Self {
+        self.template.project_creator_default = Some(project_creator_default);
+        self
+    }
+
+    pub fn external(mut self, external: bool) -> Self {
+        self.template.external = Some(external);
+        self
+    }
+
+    pub fn hidden(mut self, hidden: bool) -> Self {
+        self.template.hidden = Some(hidden);
+        self
+    }
+
+    pub fn locked(mut self, locked: bool) -> Self {
+        self.template.locked = Some(locked);
+        self
+    }
+
+    /// Add a label to the role template
+    pub fn label(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.template.labels.get_or_insert_with(HashMap::new).insert(key.into(), value.into());
+        self
+    }
+
+    /// Add an annotation to the role template
+    pub fn annotation(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.template.annotations.get_or_insert_with(HashMap::new).insert(key.into(), value.into());
+        self
+    }
+
+    /// Inherit the rules of another role template
+    pub fn inherit(mut self, role_template_name: impl Into<String>) -> Self {
+        self.template
+            .role_template_names
+            .get_or_insert_with(Vec::new)
+            .push(role_template_name.into());
+        self
+    }
+
+    /// Append a rule granting `verbs` on `resources` of `api_groups`, `""` being the core group
+    pub fn rule(mut self, api_groups: &[&str], resources: &[&str], verbs: &[&str]) -> Self {
+        let to_strings = |values: &[&str]| values.iter().map(|v| v.to_string()).collect::<Vec<_>>();
+        self.template
+            .rules
+            .get_or_insert_with(Vec::new)
+            .push(IoCattleManagementv3GlobalRoleRulesInner {
+                api_groups: Some(to_strings(api_groups)),
+                resources: Some(to_strings(resources)),
+                verbs: to_strings(verbs),
+                ..Default::default()
+            });
+        self
+    }
+
+    /// Validate the fields and build the role template
+    ///
+    /// # Returns
+    ///
+    /// * `RoleTemplate` - The role template
+    ///
+    /// # Errors
+    ///
+    /// * `String` - When `id` is not set
+    pub fn build(self) -> Result<RoleTemplate, String> {
+        if self.template.id.is_empty() {
+            return Err("id is required".to_string());
+        }
+        Ok(self.template)
+    }
+}
+
+impl TryFrom<IoCattleManagementv3RoleTemplate> for RoleTemplate {
+    type Error = &'static str;
+
+    fn try_from(value: IoCattleManagementv3RoleTemplate) -> Result<Self, Self::Error> {
+        let metadata: IoK8sApimachineryPkgApisMetaV1ObjectMeta = value.metadata.ok_or("missing metadata")?;
+
+        let administrative: Option<bool> = value.administrative;
+        let annotations: Option<HashMap<String, String>> = metadata.annotations;
+        let builtin: Option<bool> = value.builtin;
+        let cluster_creator_default: Option<bool> = value.cluster_creator_default;
+        let context = value.context;
+        let description: Option<String> = value.description;
+        let display_name: Option<String> = value.display_name;
+        let external: Option<bool> = value.external;
+        let hidden: Option<bool> = value.hidden;
+        let labels: Option<HashMap<String, String>> = metadata.labels;
+        let locked: Option<bool> = value.locked;
+        let project_creator_default: Option<bool> = value.project_creator_default;
+        let role_template_names: Option<Vec<String>> = value.role_template_names;
+        let rules: Option<Vec<IoCattleManagementv3GlobalRoleRulesInner>> = value.rules;
+
+        Ok(RoleTemplate {
+            administrative,
+            annotations,
+            builtin,
+            cluster_creator_default,
+            context,
+            description,
+            display_name,
+            external,
+            hidden,
+            id: metadata.name.ok_or("missing metadata.name")?,
+            labels,
+            locked,
+            project_creator_default,
+            role_template_names,
+            rules,
+        })
+    }
+}
+
+/// Build the API form of a role template without validating it
+///
+/// Used where a malformed role template must still be rendered, e.g. to show it in a diff or export.
+pub(crate) fn api_form(value: RoleTemplate) -> IoCattleManagementv3RoleTemplate {
+    let metadata = IoK8sApimachineryPkgApisMetaV1ObjectMeta {
+        annotations: value.annotations,
+        labels: value.labels,
+        name: Some(value.id.clone()),
+        ..Default::default()
+    };
+
+    let context = value.context;
+    let administrative: Option<bool> = value.administrative;
+    let builtin: Option<bool> = value.builtin;
+    let cluster_creator_default: Option<bool> = value.cluster_creator_default;
+    let description: Option<String> = value.description;
+    let display_name: Option<String> = value.display_name;
+    let external: Option<bool> = value.external;
+    let hidden: Option<bool> = value.hidden;
+    let locked: Option<bool> = value.locked;
+    let project_creator_default: Option<bool> = value.project_creator_default;
+    let role_template_names: Option<Vec<String>> = value.role_template_names;
+    let rules: Option<Vec<IoCattleManagementv3GlobalRoleRulesInner>> = value.rules;
+
+    IoCattleManagementv3RoleTemplate {
+        administrative,
+        api_version: Some("management.cattle.io/v3".to_string()),
+        builtin,
+        cluster_creator_default,
+        context,
+        description,
+        display_name,
+        external,
+        hidden,
+        kind: Some("RoleTemplate".to_string()),
+        locked,
+        metadata: Some(metadata),
+        project_creator_default,
+        role_template_names,
+        rules,
+    }
+}
+
+impl TryFrom<RoleTemplate> for IoCattleManagementv3RoleTemplate {
+    type Error = &'static str;
+
+    /// Fails when `id` is not a DNS-1123 subdomain, Rancher would reject the role template
+    fn try_from(value: RoleTemplate) -> Result<Self, Self::Error> {
+        validate_name(&value.id)?;
+        Ok(api_form(value))
+    }
+}
+
+/// Normalize a list of rules into a canonical, order-insensitive form
+///
+/// RBAC rules behave like a set, so the order of the rules and of the values inside each rule
+/// carries no meaning. This sorts the inner arrays of every rule and then the rules themselves by
+/// `rule_sort_key` so that two equivalent rule lists compare equal.
+///
+/// # Arguments
+///
+/// * `rules` - The rules to normalize
+///
+/// # Returns
+///
+/// * `Vec<IoCattleManagementv3GlobalRoleRulesInner>` - The rules in canonical order
+pub fn normalize_rules(
+    rules: &[IoCattleManagementv3GlobalRoleRulesInner],
+) -> Vec<IoCattleManagementv3GlobalRoleRulesInner> {
+    let mut normalized: Vec<IoCattleManagementv3GlobalRoleRulesInner> = rules
+        .iter()
+        .cloned()
+        .map(|mut rule| {
+            if let Some(api_groups) = rule.api_groups.as_mut() {
+                api_groups.sort();
+            }
+            if let Some(non_resource_urls) = rule.non_resource_urls.as_mut() {
+                non_resource_urls.sort();
+            }
+            if let Some(resource_names) = rule.resource_names.as_mut() {
+                resource_names.sort();
+            }
+            if let Some(resources) = rule.resources.as_mut() {
+                resources.sort();
+            }
+            rule.verbs.sort();
+            rule
+        })
+        .collect();
+
+    normalized.sort_by_key(rule_sort_key);
+    normalized
+}
+
+/// The canonical sort key of a rule whose inner arrays are already sorted
+///
+/// Rules are ordered by `apiGroups`, then `resources`, then `verbs`. `resourceNames` and
+/// `nonResourceURLs` only break ties between rules that agree on those three.
+fn rule_sort_key(rule: &IoCattleManagementv3GlobalRoleRulesInner) -> [Vec<String>; 5] {
+    [
+        rule.api_groups.clone().unwrap_or_default(),
+        rule.resources.clone().unwrap_or_default(),
+        rule.verbs.clone(),
+        rule.resource_names.clone().unwrap_or_default(),
+        rule.non_resource_urls.clone().unwrap_or_default(),
+    ]
+}
+
+/// Sort inherited role template names, which Rancher treats as a set
+fn sorted_role_template_names(names: Option<&Vec<String>>) -> Option<Vec<String>> {
+    names.map(|names| {
+        let mut names = names.clone();
+        names.sort();
+        names
+    })
+}
+
+/// Serialize a role template in its API form with the server managed fields pruned
+fn api_value(template: &RoleTemplate) -> serde_json::Value {
+    let mut value = serde_json::to_value(api_form(template.clone())).unwrap_or_default();
+    prune_paths(&mut value, RT_EXCLUDE_PATHS);
+    value
+}
+
+/// Serialize a role template in its API form with canonical rules and the server managed fields pruned
+fn pruned_api_value(template: &RoleTemplate) -> serde_json::Value {
+    let mut template = template.clone();
+    template.rules = template.rules.as_deref().map(normalize_rules);
+    template.role_template_names = sorted_role_template_names(template.role_template_names.as_ref());
+    api_value(&template)
+}
+
+/// Compare a desired role template to the actual one, ignoring `RT_EXCLUDE_PATHS`
+///
+/// Rules and inherited role template names are compared in their canonical order, so reordering
+/// either is not a change.
+///
+/// # Arguments
+///
+/// * `desired` - The desired role template
+/// * `actual` - The role template as it exists on the server
+///
+/// # Returns
+///
+/// * `Option<DiffReport>` - The added, removed and changed fields, with API (camelCase, dotted) paths, or `None` when the role templates are equivalent
+pub fn diff(desired: &RoleTemplate, actual: &RoleTemplate) -> Option<DiffReport> {
+    let report = DiffReport::between(&pruned_api_value(actual), &pruned_api_value(desired));
+    if report.is_empty() {
+        None
+    } else {
+        Some(report)
+    }
+}
+
+/// Compute the RFC 6902 JSON patch turning the actual role template into the desired one
+///
+/// Both sides are compared in their pruned API form, so `RT_EXCLUDE_PATHS` never appear as operations.
+/// No operations are emitted when the role templates only differ in the order of their rules or
+/// inherited role template names. Otherwise the operations are computed against the actual rules
+/// in the order they are stored, so their indices address the object on the server. The result can
+/// be sent with `JSON_PATCH_CONTENT_TYPE`.
+///
+/// # Arguments
+///
+/// * `desired` - The desired role template
+/// * `actual` - The role template as it exists on the server
+///
+/// # Returns
+///
+/// * `serde_json::Value` - The patch as a JSON array of operations, empty when the role templates are equivalent
+pub fn to_json_patch(desired: &RoleTemplate, actual: &RoleTemplate) -> serde_json::Value {
+    let desired = pruned_api_value(desired);
+    if pruned_api_value(actual) == desired {
+        return serde_json::Value::Array(Vec::new());
+    }
+    json_patch_between(&api_value(actual), &desired)
+}
+
+/// Compute a stable hash of the content of a role template
+///
+/// The hash covers the pruned, canonically ordered API form, without `CONTENT_HASH_ANNOTATION`,
+/// so it only changes when the role template itself does.
+///
+/// # Arguments
+///
+/// * `rt` - The role template to hash
+///
+/// # Returns
+///
+/// * `u64` - The hash, format it with `metadata::format_content_hash`
+pub fn content_hash(rt: &RoleTemplate) -> u64 {
+    let mut value = pruned_api_value(rt);
+    strip_for_content_hash(&mut value);
+    hash_value(&value)
+}
+
+/// Return a copy of a role template with its content hash stored in `CONTENT_HASH_ANNOTATION`
+pub fn with_content_hash(rt: &RoleTemplate) -> RoleTemplate {
+    let mut hashed = rt.clone();
+    hashed
+        .annotations
+        .get_or_insert_with(Default::default)
+        .insert(CONTENT_HASH_ANNOTATION.to_string(), format_content_hash(content_hash(rt)));
+    hashed
+}
+
+/// Estimate the size of the request body needed to send a role template to Rancher
+///
+/// # Arguments
+///
+/// * `template` - The role template to estimate
+///
+/// # Returns
+///
+/// * `usize` - The size in bytes of the serialized `IoCattleManagementv3RoleTemplate`, measured
+///   even when the role template would not pass validation
+///
+/// # Errors
+///
+/// * `serde_json::Error` - The error that occurred while serializing
+pub fn estimate_request_size(template: &RoleTemplate) -> Result<usize, serde_json::Error> {
+    serde_json::to_vec(&api_form(template.clone())).map(|body| body.len())
+}
+
+/// Check that a role template fits within the Rancher request body limit before applying it
+///
+/// Very large role templates (thousands of rules) fail with an unhelpful 413 from Rancher, this
+/// gives a clear error up front instead.
+///
+/// # Arguments
+///
+/// * `template` - The role template to check
+/// * `max_size` - The maximum allowed size in bytes, defaults to `DEFAULT_MAX_REQUEST_SIZE`
+///
+/// # Returns
+///
+/// * `usize` - The estimated size in bytes when within the limit
+///
+/// # Errors
+///
+/// * `String` - A message naming the role template and its size when it exceeds the limit, or
+///   the error that occurred while serializing it
+pub fn check_request_size(template: &RoleTemplate, max_size: Option<usize>) -> Result<usize, String> {
+    let max_size = max_size.unwrap_or(DEFAULT_MAX_REQUEST_SIZE);
+    let size = estimate_request_size(template)
+        .map_err(|e| format!("Failed to serialize role template {}: {}", template.id, e))?;
+    if size > max_size {
+        return Err(format!(
+            "Role template {} is {} bytes, which exceeds the maximum request size of {} bytes",
+            template.id, size, max_size
+        ));
+    }
+    Ok(size)
+}
+
+/// The scope of the binding that references a role template
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BindingScope {
+    /// A cluster role template binding
+    Cluster,
+    /// A project role template binding
+    Project,
+}
+
+impl BindingScope {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BindingScope::Cluster => "cluster",
+            BindingScope::Project => "project",
+        }
+    }
+}
+
+/// Check that the context of a role template allows it to be bound at a scope
+///
+/// Project role templates may only be bound by project role template bindings and cluster role
+/// templates only by cluster role template bindings. A role template without a context is not
+/// checked.
+///
+/// # Arguments
+///
+/// * `rt` - The role template referenced by the binding
+/// * `scope` - The scope of the binding
+/// * `binding_id` - The ID of the binding, for the error message
+///
+/// # Errors
+///
+/// * `String` - A message naming the binding, the role template and its context on a mismatch
+pub fn check_context(rt: &RoleTemplate, scope: BindingScope, binding_id: &str) -> Result<(), String> {
+    let context = match (&rt.context, scope) {
+        (Some(Context::Cluster), BindingScope::Project) => BindingScope::Cluster,
+        (Some(Context::Project), BindingScope::Cluster) => BindingScope::Project,
+        _ => return Ok(()),
+    };
+    Err(format!(
+        "{} binding {} references role template {} whose context is {}",
+        scope.as_str(),
+        binding_id,
+        rt.id,
+        context.as_str()
+    ))
+}
+
+/// Find the inheritance cycles among role templates
+///
+/// A role template inherits the rules of those named in its `role_template_names`, a cycle makes
+/// the expansion of its permissions never end. Names of role templates outside `templates` are
+/// ignored, they cannot close a cycle within the set.
+///
+/// # Arguments
+///
+/// * `templates` - The role templates to check
+///
+/// # Returns
+///
+/// * `Vec<Vec<String>>` - The IDs of the role templates of each cycle, in inheritance order,
+///   e.g. `["a", "b"]` when `a` inherits `b` and `b` inherits `a`; empty when there is no cycle
+pub fn detect_cycles(templates: &[RoleTemplate]) -> Vec<Vec<String>> {
+    #[derive(Clone, Copy, PartialEq)]
+    enum State {
+        Visiting,
+        Done,
+    }
+
+    fn visit<'a>(
+        id: &'a str,
+        inherits: &HashMap<&'a str, Vec<&'a str>>,
+        states: &mut HashMap<&'a str, State>,
+        stack: &mut Vec<&'a str>,
+        cycles: &mut Vec<Vec<String>>,
+    ) {
+        states.insert(id, State::Visiting);
+        stack.push(id);
+        for &inherited in inherits.get(id).into_iter().flatten() {
+            match states.get(inherited) {
+                None if inherits.contains_key(inherited) => {
+                    visit(inherited, inherits, states, stack, cycles)
+                }
+                // back to a template on the current path, the path from it is a cycle
+                Some(State::Visiting) => {
+                    let start = stack.iter().position(|&n| n == inherited).unwrap_or_default();
+                    cycles.push(stack[start..].iter().map(|n| n.to_string()).collect());
+                }
+                _ => {}
+            }
+        }
+        stack.pop();
+        states.insert(id, State::Done);
+    }
+
+    let inherits: HashMap<&str, Vec<&str>> = templates
+        .iter()
+        .map(|rt| {
+            let names = rt.role_template_names.iter().flatten().map(String::as_str).collect();
+            (rt.id.as_str(), names)
+        })
+        .collect();
+    let mut ids: Vec<&str> = inherits.keys().copied().collect();
+    ids.sort();
+
+    let mut states = HashMap::new();
+    let mut cycles = Vec::new();
+    for id in ids {
+        if !states.contains_key(id) {
+            visit(id, &inherits, &mut states, &mut Vec::new(), &mut cycles);
+        }
+    }
+    cycles
+}
+
+/// How serious a finding of `validate_rules` is
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RuleSeverity {
+    /// Rancher or Kubernetes would reject or ignore the rule
+    Error,
+    /// The rule is valid but most likely grants more than intended
+    Warning,
+}
+
+/// A finding of `validate_rules` on a single rule
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RuleIssue {
+    /// The position of the rule in the checked slice
+    pub index: usize,
+    pub severity: RuleSeverity,
+    pub message: String,
+}
+
+impl std::fmt::Display for RuleIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "rule {}: {}", self.index, self.message)
+    }
+}
+
+/// Check the content of RBAC rules
+///
+/// A rule without verbs, or with neither resources nor non-resource URLs, is an error. The rule
+/// granting every verb on every resource of every API group (`*`/`*`/`*`) is a warning.
+///
+/// # Arguments
+///
+/// * `rules` - The rules to check
+///
+/// # Returns
+///
+/// * `Vec<RuleIssue>` - The findings, in rule order, empty when every rule is fine
+pub fn validate_rules(rules: &[IoCattleManagementv3GlobalRoleRulesInner]) -> Vec<RuleIssue> {
+    fn is_set(values: &Option<Vec<String>>) -> bool {
+        values.as_ref().is_some_and(|values| !values.is_empty())
+    }
+    fn has_wildcard(values: &Option<Vec<String>>) -> bool {
+        values.as_ref().is_some_and(|values| values.iter().any(|v| v == "*"))
+    }
+
+    let mut issues = Vec::new();
+    for (index, rule) in rules.iter().enumerate() {
+        let mut issue = |severity: RuleSeverity, message: &str| {
+            issues.push(RuleIssue {
+                index,
+                severity,
+                message: message.to_string(),
+            })
+        };
+        if rule.verbs.is_empty() {
+            issue(RuleSeverity::Error, "at least one verb is required");
+        }
+        if !is_set(&rule.resources) && !is_set(&rule.non_resource_urls) {
+            issue(
+                RuleSeverity::Error,
+                "at least one of resources or nonResourceURLs is required",
+            );
+        }
+        if has_wildcard(&rule.api_groups)
+            && has_wildcard(&rule.resources)
+            && rule.verbs.iter().any(|verb| verb == "*")
+        {
+            issue(
+                RuleSeverity::Warning,
+                "grants every verb on every resource of every API group",
+            );
+        }
+    }
+    issues
+}
+
+/// Check the rules of a role template before it is written
+///
+/// Only errors are returned, warnings are left to `lint_role_templates`.
+///
+/// # Errors
+///
+/// * `String` - A message naming the role template and every rule in error
+fn check_rules(template: &RoleTemplate) -> Result<(), String> {
+    let errors: Vec<RuleIssue> = validate_rules(template.rules.as_deref().unwrap_or_default())
+        .into_iter()
+        .filter(|issue| issue.severity == RuleSeverity::Error)
+        .collect();
+    if errors.is_empty() {
+        return Ok(());
+    }
+    let errors: Vec<String> = errors.iter().map(RuleIssue::to_string).collect();
+    Err(format!(
+        "Role template {} has invalid rules: {}",
+        template.id,
+        errors.join("; ")
+    ))
+}
+
+/// Lint the rules of role templates, e.g. those loaded from a directory before applying them
+///
+/// # Arguments
+///
+/// * `role_templates` - The role templates to lint
+///
+/// # Returns
+///
+/// * `Vec<(String, RuleIssue)>` - The findings with the ID of their role template, errors and warnings alike
+pub fn lint_role_templates(role_templates: &[RoleTemplate]) -> Vec<(String, RuleIssue)> {
+    role_templates
+        .iter()
+        .flat_map(|rt| {
+            validate_rules(rt.rules.as_deref().unwrap_or_default())
+                .into_iter()
+                .map(|issue| (rt.id.clone(), issue))
+        })
+        .collect()
+}
+
+impl PartialEq<RoleTemplate> for IoCattleManagementv3RoleTemplate {
+    fn eq(&self, other: &RoleTemplate) -> bool {
+        let lhs = self.metadata.as_ref().and_then(|m| m.name.clone());
+        let rhs = Some(other.id.clone());
+
+        let metadata = self.metadata.as_ref();
+
+        lhs == rhs
+            && user_metadata_eq(metadata.and_then(|m| m.annotations.as_ref()), other.annotations.as_ref())
+            && user_metadata_eq(metadata.and_then(|m| m.labels.as_ref()), other.labels.as_ref())
+            && self.administrative == other.administrative
+            && self.builtin == other.builtin
+            && self.cluster_creator_default == other.cluster_creator_default
+            && self.context == other.context
+            && self.description == other.description
+            && self.display_name == other.display_name
+            && self.external == other.external
+            && self.hidden == other.hidden
+            && self.locked == other.locked
+            && self.project_creator_default == other.project_creator_default
+            && sorted_role_template_names(self.role_template_names.as_ref())
+                == sorted_role_template_names(other.role_template_names.as_ref())
+            && self.rules.as_deref().map(normalize_rules)
+                == other.rules.as_deref().map(normalize_rules)
+    }
+}
+
+
+impl PartialEq<IoCattleManagementv3RoleTemplate> for RoleTemplate {
+    fn eq(&self, other: &IoCattleManagementv3RoleTemplate) -> bool {
+        // let lhs = Some(self.id.clone());
+        // let rhs = other.metadata.as_ref().and_then(|m| m.name.clone());
+
+        // self.administrative == other.administrative
+        //     && self.builtin == other.builtin
+        //     && self.cluster_creator_default == other.cluster_creator_default
+        //     && self.context == other.context
+        //     && self.description == other.description
+        //     && self.display_name == other.display_name
+        //     && self.external == other.external
+        //     && self.hidden == other.hidden
+        //     && self.locked == other.locked
+        //     && lhs == rhs
+        //     && self.project_creator_default == other.project_creator_default
+        //     && self.role_template_names == other.role_template_names
+        //     && self.rules == other.rules
+
+
+        other == self
+    }
+}
+
+
+
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::convert::TryFrom;
+
+    fn sample_metadata(name: &str) -> IoK8sApimachineryPkgApisMetaV1ObjectMeta {
+        IoK8sApimachineryPkgApisMetaV1ObjectMeta {
+            name: Some(name.to_string()),
+            ..Default::default()
+        }
+    }
+
+    fn sample_role_template() -> RoleTemplate {
+        RoleTemplate::new(
+            Some(true), // administrative
+            Some(std::collections::HashMap::new()), // annotations
+            Some(false), // builtin
+            Some(true), // cluster_creator_default
+            Some(Context::Cluster), // context
+            Some("A role template".to_string()), // description
+            Some("Admin".to_string()), // display_name
+            Some(false), // external
+            Some(false), // hidden
+            Some(std::collections::HashMap::new()), // labels
+            Some(false), // locked
+            "admin-template".to_string(), // id
+            Some(false), // project_creator_default
+            Some(vec!["base-template".to_string()]), // role_template_names
+            Some(vec![]), // rules
+        )
+    }
+
+    fn sample_iocattle_role_template() -> IoCattleManagementv3RoleTemplate {
+        IoCattleManagementv3RoleTemplate {
+            administrative: Some(true),
+            api_version: Some("management.cattle.io/v3".to_string()),
+            builtin: Some(false),
+            cluster_creator_default: Some(true),
+            context: Some(Context::Cluster),
+            description: Some("A role template".to_string()),
+            display_name: Some("Admin".to_string()),
+            external: Some(false),
+            hidden: Some(false),
+            kind: Some("RoleTemplate".to_string()),
+            locked: Some(false),
+            metadata: Some(sample_metadata("admin-template")),
+            project_creator_default: Some(false),
+            role_template_names: Some(vec!["base-template".to_string()]),
+            rules: Some(vec![]),
+        }
+    }
+
+    #[test]
+    fn test_iocattle_to_role_template_conversion_success() {
+        let io_rt = sample_iocattle_role_template();
+        let result = RoleTemplate::try_from(io_rt).unwrap();
+        assert_eq!(result.id, "admin-template");
+        assert_eq!(result.display_name.as_deref(), Some("Admin"));
+        assert_eq!(result.project_creator_default, Some(false));
+    }
+
+    #[test]
+    fn test_role_template_to_iocattle_conversion_success() {
+        let rt = sample_role_template();
+        let result = IoCattleManagementv3RoleTemplate::try_from(rt).unwrap();
+        assert_eq!(
+            result.metadata.as_ref().unwrap().name.as_deref(),
+            Some("admin-template")
+        );
+        assert_eq!(result.display_name.as_deref(), Some("Admin"));
+        assert_eq!(result.project_creator_default, Some(false));
+    }
+
+    #[test]
+    fn test_iocattle_to_role_template_missing_metadata() {
+        let mut io_rt = sample_iocattle_role_template();
+        io_rt.metadata = None;
+        let result = RoleTemplate::try_from(io_rt);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_iocattle_to_role_template_missing_metadata_name() {
+        let mut io_rt = sample_iocattle_role_template();
+        io_rt.metadata.as_mut().unwrap().name = None;
+        let result = RoleTemplate::try_from(io_rt);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_round_trip_conversion() {
+        let original = sample_role_template();
+        let back_and_forth = RoleTemplate::try_from(
+            IoCattleManagementv3RoleTemplate::try_from(original.clone()).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(original, back_and_forth);
+    }
+
+
+    #[test]
+    fn test_equality_both_directions() {
+        let rt = sample_role_template();
+        let iort = sample_iocattle_role_template();
+
+        // Forward comparison
+        assert_eq!(iort, rt);
+
+        // Reverse comparison
+        assert_eq!(rt, iort);
+    }
+
+    #[test]
+    fn test_inequality_on_field() {
+        let rt = sample_role_template();
+        let mut iort = sample_iocattle_role_template();
+
+        // Change display name
+        iort.display_name = Some("Changed".into());
+
+        assert_ne!(rt, iort);
+        assert_ne!(iort, rt);
+    }
+
+    #[test]
+    fn test_missing_metadata_name() {
+        let rt = sample_role_template();
+        let mut iort = sample_iocattle_role_template();
+
+        // Remove the name field
+        if let Some(metadata) = iort.metadata.as_mut() {
+            metadata.name = None;
+        }
+
+        assert_ne!(rt, iort);
+        assert_ne!(iort, rt);
+    }
+
+    fn sample_rule(resources: &[&str], verbs: &[&str]) -> IoCattleManagementv3GlobalRoleRulesInner {
+        IoCattleManagementv3GlobalRoleRulesInner {
+            api_groups: Some(vec!["".to_string()]),
+            resources: Some(resources.iter().map(|r| r.to_string()).collect()),
+            verbs: verbs.iter().map(|v| v.to_string()).collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_reordered_rules_are_equal() {
+        let mut rt = sample_role_template();
+        rt.rules = Some(vec![
+            sample_rule(&["pods", "services"], &["get", "list"]),
+            sample_rule(&["secrets"], &["get"]),
+        ]);
+
+        let mut iort = sample_iocattle_role_template();
+        iort.rules = Some(vec![
+            sample_rule(&["secrets"], &["get"]),
+            sample_rule(&["services", "pods"], &["list", "get"]),
+        ]);
+
+        assert_eq!(rt, iort);
+        assert_eq!(iort, rt);
+    }
+
+    #[test]
+    fn test_rules_sorted_by_api_groups_resources_and_verbs() {
+        let mut apps = sample_rule(&["deployments"], &["get"]);
+        apps.api_groups = Some(vec!["apps".to_string()]);
+        let rules = vec![
+            apps.clone(),
+            sample_rule(&["pods"], &["watch"]),
+            sample_rule(&["pods"], &["get"]),
+            sample_rule(&["configmaps"], &["list"]),
+        ];
+
+        let normalized = normalize_rules(&rules);
+
+        assert_eq!(
+            normalized,
+            vec![
+                sample_rule(&["configmaps"], &["list"]),
+                sample_rule(&["pods"], &["get"]),
+                sample_rule(&["pods"], &["watch"]),
+                apps,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_reordered_rules_across_api_groups_are_equal() {
+        let mut apps = sample_rule(&["deployments", "statefulsets"], &["get"]);
+        apps.api_groups = Some(vec!["apps".to_string()]);
+        let mut reordered_apps = sample_rule(&["statefulsets", "deployments"], &["get"]);
+        reordered_apps.api_groups = Some(vec!["apps".to_string()]);
+
+        let mut rt = sample_role_template();
+        rt.rules = Some(vec![apps, sample_rule(&["pods"], &["get", "list"])]);
+        let mut reordered = sample_role_template();
+        reordered.rules = Some(vec![sample_rule(&["pods"], &["list", "get"]), reordered_apps]);
+
+        let iort = IoCattleManagementv3RoleTemplate::try_from(reordered.clone()).unwrap();
+        assert_eq!(rt, iort);
+        assert_eq!(iort, rt);
+        assert!(diff(&rt, &reordered).is_none());
+    }
+
+    #[test]
+    fn test_changed_verb_is_not_equal() {
+        let mut rt = sample_role_template();
+        rt.rules = Some(vec![sample_rule(&["pods"], &["get", "list"])]);
+
+        let mut iort = sample_iocattle_role_template();
+        iort.rules = Some(vec![sample_rule(&["pods"], &["get", "watch"])]);
+
+        assert_ne!(rt, iort);
+        assert_ne!(iort, rt);
+    }
+
+    #[test]
+    fn test_small_role_template_passes_size_check() {
+        let rt = sample_role_template();
+        let size = check_request_size(&rt, None).unwrap();
+        assert!(size > 0);
+        assert_eq!(size, estimate_request_size(&rt).unwrap());
+    }
+
+    #[test]
+    fn test_size_of_invalid_role_template_is_measured() {
+        let mut rt = sample_role_template();
+        rt.id = "Admin_RT".to_string();
+
+        assert!(estimate_request_size(&rt).unwrap() > 0);
+    }
+
+    #[test]
+    fn test_oversized_role_template_is_flagged() {
+        let mut rt = sample_role_template();
+        rt.rules = Some(
+            (0..100)
+                .map(|i| sample_rule(&[&format!("resource-{}", i)], &["get", "list", "watch"]))
+                .collect(),
+        );
+
+        let result = check_request_size(&rt, Some(1024));
+        assert!(result.is_err());
+        assert!(result.unwrap_err().contains("admin-template"));
+    }
+
+    #[test]
+    fn test_large_name_set_uses_single_list() {
+        assert_eq!(fetch_strategy(BATCH_LIST_THRESHOLD + 1), FetchStrategy::SingleList);
+        assert_eq!(fetch_strategy(1), FetchStrategy::PerName);
+    }
+
+    #[test]
+    fn test_resolve_names_includes_none_for_missing() {
+        let names = vec!["admin-template".to_string(), "missing".to_string()];
+        let resolved = resolve_role_template_names(&names, vec![sample_iocattle_role_template()]);
+
+        assert_eq!(resolved.len(), 2);
+        assert_eq!(resolved["admin-template"].as_ref().unwrap().id, "admin-template");
+        assert!(resolved["missing"].is_none());
+    }
+
+    #[tokio::test]
+    async fn test_create_role_template() {
+        let rancher = crate::test_support::MockRancher::start().await;
+        rancher
+            .expect_create_role_template()
+            .with_body_partial(serde_json::json!({ "metadata": { "name": "admin-template" } }))
+            .expect_calls(1)
+            .returning_json(201, serde_json::to_value(sample_iocattle_role_template()).unwrap())
+            .await;
+
+        let created = create_role_template(&rancher.configuration(), &sample_role_template())
+            .await
+            .unwrap();
+
+        assert_eq!(created.id, "admin-template");
+    }
+
+    #[tokio::test]
+    async fn test_create_role_template_accepts_every_success_code() {
+        let created = serde_json::to_value(sample_iocattle_role_template()).unwrap();
+        let accepted = serde_json::json!({ "kind": "Status", "status": "Success", "code": 202 });
+        let cases = vec![
+            wiremock::ResponseTemplate::new(200).set_body_json(created.clone()),
+            wiremock::ResponseTemplate::new(201).set_body_json(created),
+            wiremock::ResponseTemplate::new(202).set_body_json(accepted),
+            wiremock::ResponseTemplate::new(202),
+        ];
+
+        for template in cases {
+            let rancher = crate::test_support::MockRancher::start().await;
+            rancher
+                .expect_create_role_template()
+                .expect_calls(1)
+                .returning_template(template)
+                .await;
+
+            let created = create_role_template(&rancher.configuration(), &sample_role_template())
+                .await
+                .unwrap();
+
+            assert_eq!(created.id, "admin-template");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_replace_role_template_accepts_every_success_code() {
+        let replaced = serde_json::to_value(sample_iocattle_role_template()).unwrap();
+        let cases = vec![
+            wiremock::ResponseTemplate::new(200).set_body_json(replaced.clone()),
+            wiremock::ResponseTemplate::new(201).set_body_json(replaced),
+            wiremock::ResponseTemplate::new(202),
+        ];
+
+        for template in cases {
+            let rancher = crate::test_support::MockRancher::start().await;
+            rancher
+                .expect("PUT", &crate::ResourceKind::RoleTemplate.api_path(None, "admin-template"))
+                .expect_calls(1)
+                .returning_template(template)
+                .await;
+
+            let replaced = replace_role_template(&rancher.configuration(), &sample_role_template(), Some("7"))
+                .await
+                .unwrap();
+
+            assert_eq!(replaced.id, "admin-template");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_delete_role_template_accepts_every_success_code() {
+        for status in [200, 201, 202] {
+            let rancher = crate::test_support::MockRancher::start().await;
+            rancher
+                .expect("DELETE", &crate::ResourceKind::RoleTemplate.api_path(None, "admin-template"))
+                .expect_calls(1)
+                .returning_json(status, serde_json::json!({ "kind": "Status", "status": "Success" }))
+                .await;
+
+            let result = delete_role_template(&rancher.configuration(), "admin-template").await;
+
+            assert!(result.is_ok(), "status {} should succeed, got {:?}", status, result);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_create_builtin_role_template_is_rejected() {
+        let rancher = crate::test_support::MockRancher::start().await;
+        rancher
+            .expect_create_role_template()
+            .expect_calls(0)
+            .returning_json(201, serde_json::json!({}))
+            .await;
+        let mut rt = sample_role_template();
+        rt.builtin = Some(true);
+
+        let result = create_role_template(&rancher.configuration(), &rt).await;
+
+        assert!(matches!(result.map_err(WithResource::into_error), Err(RancherError::Invalid(_))));
+    }
+
+    #[tokio::test]
+    async fn test_replace_role_template_sends_resource_version() {
+        let rancher = crate::test_support::MockRancher::start().await;
+        rancher
+            .expect("PUT", &crate::ResourceKind::RoleTemplate.api_path(None, "admin-template"))
+            .with_body_partial(serde_json::json!({ "metadata": { "resourceVersion": "7" } }))
+            .expect_calls(1)
+            .returning_json(200, serde_json::to_value(sample_iocattle_role_template()).unwrap())
+            .await;
+
+        let replaced = replace_role_template(&rancher.configuration(), &sample_role_template(), Some("7"))
+            .await
+            .unwrap();
+
+        assert_eq!(replaced.id, "admin-template");
+    }
+
+    #[tokio::test]
+    async fn test_replace_role_template_conflict() {
+        let rancher = crate::test_support::MockRancher::start().await;
+        rancher
+            .expect("PUT", &crate::ResourceKind::RoleTemplate.api_path(None, "admin-template"))
+            .returning_status(409, "Conflict", "the object has been modified")
+            .await;
+
+        let result = replace_role_template(&rancher.configuration(), &sample_role_template(), Some("6")).await;
+
+        match result.map_err(WithResource::into_error) {
+            Err(RancherError::Conflict { message, .. }) => assert_eq!(message, "the object has been modified"),
+            other => panic!("expected a conflict, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_replace_role_template_without_resource_version() {
+        let result = replace_role_template(&Configuration::new(), &sample_role_template(), None).await;
+
+        assert!(matches!(
+            result.map_err(WithResource::into_error),
+            Err(RancherError::Invalid(message)) if message.starts_with("missing resource version")
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_delete_missing_role_template_is_not_found() {
+        let rancher = crate::test_support::MockRancher::start().await;
+        rancher
+            .expect("DELETE", &crate::ResourceKind::RoleTemplate.api_path(None, "gone"))
+            .returning_status(404, "NotFound", "roletemplates \"gone\" not found")
+            .await;
+
+        let result = delete_role_template(&rancher.configuration(), "gone").await;
+
+        assert!(matches!(result.map_err(WithResource::into_error), Err(RancherError::NotFound)));
+    }
+
+    #[tokio::test]
+    async fn test_delete_builtin_role_template_is_forbidden() {
+        let rancher = crate::test_support::MockRancher::start().await;
+        rancher
+            .expect("DELETE", &crate::ResourceKind::RoleTemplate.api_path(None, "project-owner"))
+            .returning_status(403, "Forbidden", "cannot delete builtin role template")
+            .await;
+
+        let result = delete_role_template(&rancher.configuration(), "project-owner").await;
+
+        match result.map_err(WithResource::into_error) {
+            Err(RancherError::Forbidden(status)) => assert_eq!(status.message, "cannot delete builtin role template"),
+            other => panic!("expected forbidden, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_delete_error_names_the_role_template() {
+        let rancher = crate::test_support::MockRancher::start().await;
+        rancher
+            .expect("DELETE", &crate::ResourceKind::RoleTemplate.api_path(None, "project-owner"))
+            .returning_status(403, "Forbidden", "cannot delete builtin role template")
+            .await;
+
+        let err = delete_role_template(&rancher.configuration(), "project-owner")
+            .await
+            .unwrap_err();
+
+        assert_eq!(
+            err.to_string(),
+            "RoleTemplate project-owner: Forbidden: cannot delete builtin role template"
+        );
+        let source = std::error::Error::source(&err).unwrap();
+        assert_eq!(source.to_string(), err.error.to_string());
+        assert!(matches!(err.into_error(), RancherError::Forbidden(_)));
+    }
+
+    #[test]
+    fn test_role_template_field_patch() {
+        let patch = role_template_field_patch(RoleTemplateField::DisplayName, serde_json::json!("Viewer")).unwrap();
+        assert_eq!(patch, serde_json::json!({ "displayName": "Viewer" }));
+
+        let patch = role_template_field_patch(RoleTemplateField::Locked, serde_json::json!(true)).unwrap();
+        assert_eq!(patch, serde_json::json!({ "locked": true }));
+
+        assert!(role_template_field_patch(RoleTemplateField::Locked, serde_json::json!("yes")).is_err());
+        assert!(role_template_field_patch(RoleTemplateField::Description, serde_json::json!(["a"])).is_err());
+    }
+
+    #[test]
+    fn test_merge_patch_replaces_whole_rules_array() {
+        let mut target = serde_json::json!({
+            "description": "A role template",
+            "rules": [
+                { "resources": ["pods"], "verbs": ["get"] },
+                { "resources": ["secrets"], "verbs": ["list"] }
+            ]
+        });
+        let patch = serde_json::json!({ "rules": [{ "resources": ["pods"], "verbs": ["watch"] }] });
+
+        json_patch::merge(&mut target, &patch);
+
+        assert_eq!(
+            target["rules"],
+            serde_json::json!([{ "resources": ["pods"], "verbs": ["watch"] }])
+        );
+        assert_eq!(target["description"], "A role template");
+    }
+
+    #[tokio::test]
+    async fn test_patch_role_template_sends_merge_patch() {
+        let rancher = crate::test_support::MockRancher::start().await;
+        let mut patched = sample_iocattle_role_template();
+        patched.description = Some("Read only".to_string());
+        rancher
+            .expect("PATCH", &ResourceKind::RoleTemplate.api_path(None, "admin-template"))
+            .with_body_partial(serde_json::json!({ "description": "Read only" }))
+            .expect_calls(1)
+            .returning_json(200, serde_json::to_value(&patched).unwrap())
+            .await;
+
+        let patch = role_template_field_patch(RoleTemplateField::Description, serde_json::json!("Read only")).unwrap();
+        let rt = patch_role_template(&rancher.configuration(), "admin-template", patch)
+            .await
+            .unwrap();
+
+        assert_eq!(rt.description.as_deref(), Some("Read only"));
+    }
+
+    #[tokio::test]
+    async fn test_get_role_templates_by_names_rejects_invalid_name() {
+        let rancher = crate::test_support::MockRancher::start().await;
+        rancher
+            .expect_list_role_templates()
+            .expect_calls(0)
+            .returning(Vec::<serde_json::Value>::new())
+            .await;
+
+        let names = vec!["view".to_string(), "a,metadata.namespace=b".to_string()];
+        let result = get_role_templates_by_names(&rancher.configuration(), &names).await;
+
+        match result {
+            Err(RancherError::Invalid(message)) => assert!(message.starts_with("a,metadata.namespace=b: ")),
+            other => panic!("expected an invalid name error, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_patch_role_template_rejects_null_metadata() {
+        let configuration = Configuration::new();
+
+        let result = patch_role_template(&configuration, "admin-template", serde_json::json!({ "metadata": null })).await;
+
+        match result.map_err(WithResource::into_error) {
+            Err(RancherError::Invalid(message)) => {
+                assert_eq!(message, "field metadata.creationTimestamp cannot be changed")
+            }
+            other => panic!("expected an immutable field error, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_get_role_template_found_and_missing() {
+        let rancher = crate::test_support::MockRancher::start().await;
+        rancher
+            .expect_get_role_template("admin-template")
+            .returning_json(200, serde_json::to_value(sample_iocattle_role_template()).unwrap())
+            .await;
+        rancher
+            .expect_get_role_template("missing")
+            .returning_status(404, "NotFound", "roletemplates \"missing\" not found")
+            .await;
+
+        let found = get_role_template(&rancher.configuration(), "admin-template").await.unwrap();
+        let missing = get_role_template(&rancher.configuration(), "missing").await.unwrap();
+
+        assert_eq!(found.map(|rt| rt.id).as_deref(), Some("admin-template"));
+        assert!(missing.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_get_role_template_unexpected_status() {
+        let rancher = crate::test_support::MockRancher::start().await;
+        rancher
+            .expect_get_role_template("admin-template")
+            .returning_status(500, "InternalError", "boom")
+            .await;
+
+        let result = get_role_template(&rancher.configuration(), "admin-template").await;
+
+        assert!(matches!(
+            result,
+            Err(RancherError::Api(StatusCode::INTERNAL_SERVER_ERROR, status)) if status.message == "boom"
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_get_all_role_templates_follows_continue_token() {
+        let rancher = crate::test_support::MockRancher::start().await;
+        rancher
+            .expect_list_role_templates()
+            .without_query("continue")
+            .with_query("limit", "2")
+            .expect_calls(1)
+            .returning_page(
+                vec![
+                    crate::test_support::role_template_json("rt-1"),
+                    crate::test_support::role_template_json("rt-2"),
+                ],
+                Some("page-2"),
+            )
+            .await;
+        rancher
+            .expect_list_role_templates()
+            .with_query("continue", "page-2")
+            .without_query("resourceVersion")
+            .expect_calls(1)
+            .returning_page(vec![crate::test_support::role_template_json("rt-3")], None)
+            .await;
+
+        let role_templates = get_all_role_templates(
+            &rancher.configuration(),
+            None,
+            None,
+            Some(2),
+            &RequestOptions::default(),
+        )
+        .await
+            .unwrap();
+
+        let ids: Vec<&str> = role_templates.iter().map(|rt| rt.id.as_str()).collect();
+        assert_eq!(ids, vec!["rt-1", "rt-2", "rt-3"]);
+    }
+
+    #[tokio::test]
+    async fn test_get_all_role_templates_retries_rate_limited_page() {
+        let rancher = crate::test_support::MockRancher::start().await;
+        rancher
+            .expect_list_role_templates()
+            .times(2)
+            .expect_calls(2)
+            .returning_template(
+                wiremock::ResponseTemplate::new(429).insert_header("Retry-After", "0"),
+            )
+            .await;
+        rancher
+            .expect_list_role_templates()
+            .expect_calls(1)
+            .returning(vec![crate::test_support::role_template_json("rt-1")])
+            .await;
+
+        let role_templates =
+            get_all_role_templates(&rancher.configuration(), None, None, None, &RequestOptions::default())
+                .await
+                .unwrap();
+
+        assert_eq!(role_templates.len(), 1);
+        assert_eq!(role_templates[0].id, "rt-1");
+    }
+
+    #[tokio::test]
+    async fn test_get_all_role_templates_rejects_non_positive_limit() {
+        let rancher = crate::test_support::MockRancher::start().await;
+
+        for limit in [0, -1] {
+            let result = get_all_role_templates(
+                &rancher.configuration(),
+                None,
+                None,
+                Some(limit),
+                &RequestOptions::default(),
+            )
+            .await;
+
+            assert!(matches!(
+                result,
+                Err(RancherError::Invalid(message)) if message == format!("limit must be greater than 0, got {}", limit)
+            ));
+        }
     }
-}
 
+    #[tokio::test]
+    async fn test_get_all_role_templates_clamps_the_default_page_size() {
+        let rancher = crate::test_support::MockRancher::start().await;
+        rancher
+            .expect_list_role_templates()
+            .with_query("limit", "10")
+            .expect_calls(1)
+            .returning(vec![crate::test_support::role_template_json("rt-1")])
+            .await;
+        let options = RequestOptions {
+            max_page_size: 10,
+            ..RequestOptions::default()
+        };
 
-impl PartialEq<IoCattleManagementv3RoleTemplate> for RoleTemplate {
-    fn eq(&self, other: &IoCattleManagementv3RoleTemplate) -> bool {
-        // let lhs = Some(self.id.clone());
-        // let rhs = other.metadata.as_ref().and_then(|m| m.name.clone());
+        let role_templates = get_all_role_templates(&rancher.configuration(), None, None, None, &options)
+            .await
+            .unwrap();
 
-        // self.administrative == other.administrative
-        //     && self.builtin == other.builtin
-        //     && self.cluster_creator_default == other.cluster_creator_default
-        //     && self.context == other.context
-        //     && self.description == other.description
-        //     && self.display_name == other.display_name
-        //     && self.external == other.external
-        //     && self.hidden == other.hidden
-        //     && self.locked == other.locked
-        //     && lhs == rhs
-        //     && self.project_creator_default == other.project_creator_default
-        //     && self.role_template_names == other.role_template_names
-        //     && self.rules == other.rules
+        assert_eq!(role_templates.len(), 1);
+    }
 
+    #[tokio::test]
+    async fn test_stream_role_templates_across_pages() {
+        let rancher = crate::test_support::MockRancher::start().await;
+        rancher
+            .expect_list_role_templates()
+            .without_query("continue")
+            .expect_calls(1)
+            .returning_page(
+                vec![
+                    crate::test_support::role_template_json("rt-1"),
+                    crate::test_support::role_template_json("rt-2"),
+                ],
+                Some("page-2"),
+            )
+            .await;
+        rancher
+            .expect_list_role_templates()
+            .with_query("continue", "page-2")
+            .expect_calls(1)
+            .returning_page(vec![crate::test_support::role_template_json("rt-3")], None)
+            .await;
 
-        other == self
+        let configuration = rancher.configuration();
+        let options = RequestOptions::default();
+        let items: Vec<_> = stream_role_templates(&configuration, &options).collect().await;
+
+        assert_eq!(items.len(), 3);
+        assert!(items.iter().all(|item| item.is_ok()));
     }
-}
 
+    #[tokio::test]
+    async fn test_get_role_templates_from_bytes() {
+        let rancher = crate::test_support::MockRancher::start().await;
+        rancher
+            .expect_list_role_templates()
+            .with_query("limit", "2")
+            .with_query("labelSelector", "team=a")
+            .returning_page(
+                vec![
+                    crate::test_support::role_template_json("rt-1"),
+                    crate::test_support::role_template_json("rt-2"),
+                ],
+                Some("page-2"),
+            )
+            .await;
 
+        let list = get_role_templates_from_bytes(&rancher.configuration(), None, Some("team=a"), Some(2), None)
+            .await
+            .unwrap();
 
+        assert_eq!(list.items.len(), 2);
+        assert_eq!(continue_token(list.metadata.as_deref()).as_deref(), Some("page-2"));
+    }
 
+    #[tokio::test]
+    async fn test_get_role_templates_from_bytes_unknown_value() {
+        let rancher = crate::test_support::MockRancher::start().await;
+        rancher
+            .expect_list_role_templates()
+            .returning_status(403, "Forbidden", "denied")
+            .await;
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use std::convert::TryFrom;
+        let result = get_role_templates_from_bytes(&rancher.configuration(), None, None, None, None).await;
 
-    fn sample_metadata(name: &str) -> IoK8sApimachineryPkgApisMetaV1ObjectMeta {
-        IoK8sApimachineryPkgApisMetaV1ObjectMeta {
-            name: Some(name.to_string()),
-            ..Default::default()
+        match result {
+            Err(Error::ResponseError(response)) => {
+                assert_eq!(response.status, StatusCode::FORBIDDEN);
+                assert!(response.content.contains("denied"));
+            }
+            other => panic!("expected a response error, got {:?}", other),
         }
     }
 
-    fn sample_role_template() -> RoleTemplate {
-        RoleTemplate::new(
-            Some(true), // administrative
-            Some(std::collections::HashMap::new()), // annotations
-            Some(false), // builtin
-            Some(true), // cluster_creator_default
-            Some(Context::Cluster), // context
-            Some("A role template".to_string()), // description
-            Some("Admin".to_string()), // display_name
-            Some(false), // external
-            Some(false), // hidden
-            Some(std::collections::HashMap::new()), // labels
-            Some(false), // locked
-            "admin-template".to_string(), // id
-            Some(false), // project_creator_default
-            Some(vec!["base-template".to_string()]), // role_template_names
-            Some(vec![]), // rules
-        )
+    #[tokio::test]
+    async fn test_limit_above_max_is_clamped() {
+        let rancher = crate::test_support::MockRancher::start().await;
+        rancher
+            .expect_list_role_templates()
+            .with_query("limit", "1000")
+            .expect_calls(1)
+            .returning(vec![crate::test_support::role_template_json("rt-1")])
+            .await;
+
+        let list = get_role_templates(&rancher.configuration(), None, None, Some(50_000), None, None, None)
+            .await
+            .unwrap();
+
+        assert_eq!(list.items.len(), 1);
     }
 
-    fn sample_iocattle_role_template() -> IoCattleManagementv3RoleTemplate {
-        IoCattleManagementv3RoleTemplate {
-            administrative: Some(true),
-            api_version: Some("management.cattle.io/v3".to_string()),
-            builtin: Some(false),
-            cluster_creator_default: Some(true),
-            context: Some(Context::Cluster),
-            description: Some("A role template".to_string()),
-            display_name: Some("Admin".to_string()),
-            external: Some(false),
-            hidden: Some(false),
-            kind: Some("RoleTemplate".to_string()),
-            locked: Some(false),
-            metadata: Some(sample_metadata("admin-template")),
-            project_creator_default: Some(false),
-            role_template_names: Some(vec!["base-template".to_string()]),
-            rules: Some(vec![]),
+    #[tokio::test]
+    async fn test_non_positive_limit_is_rejected() {
+        let rancher = crate::test_support::MockRancher::start().await;
+        rancher
+            .expect_list_role_templates()
+            .expect_calls(0)
+            .returning(Vec::<serde_json::Value>::new())
+            .await;
+
+        for limit in [0, -5] {
+            let result = get_role_templates(&rancher.configuration(), None, None, Some(limit), None, None, None).await;
+            match result {
+                Err(Error::Io(e)) => assert_eq!(
+                    e.get_ref().and_then(|source| source.downcast_ref::<crate::InvalidLimit>()),
+                    Some(&crate::InvalidLimit(limit))
+                ),
+                other => panic!("expected an invalid limit error, got {:?}", other),
+            }
+
+            let result = get_role_templates_with_token(
+                &rancher.configuration(),
+                None,
+                None,
+                Some(limit),
+                None,
+                None,
+                None,
+                &RequestOptions::default(),
+            )
+            .await;
+            match result {
+                Err(RancherError::Invalid(message)) => {
+                    assert_eq!(message, format!("limit must be greater than 0, got {}", limit))
+                }
+                other => panic!("expected an invalid request error, got {:?}", other),
+            }
         }
     }
 
+    #[tokio::test]
+    async fn test_limit_is_clamped_to_the_max_page_size_of_the_options() {
+        let rancher = crate::test_support::MockRancher::start().await;
+        rancher
+            .expect_list_role_templates()
+            .with_query("limit", "10")
+            .expect_calls(1)
+            .returning(vec![crate::test_support::role_template_json("rt-1")])
+            .await;
+        let options = RequestOptions {
+            max_page_size: 10,
+            ..RequestOptions::default()
+        };
+
+        let (list, _) =
+            get_role_templates_with_token(&rancher.configuration(), None, None, Some(50), None, None, None, &options)
+                .await
+                .unwrap();
+
+        assert_eq!(list.items.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_get_role_templates_returns_continue_token() {
+        let rancher = crate::test_support::MockRancher::start().await;
+        rancher
+            .expect_list_role_templates()
+            .without_query("continue")
+            .returning_page(vec![crate::test_support::role_template_json("rt-1")], Some("page-2"))
+            .await;
+        rancher
+            .expect_list_role_templates()
+            .with_query("continue", "page-2")
+            .returning_page(vec![crate::test_support::role_template_json("rt-2")], None)
+            .await;
+        let configuration = rancher.configuration();
+        let options = RequestOptions::default();
+
+        let (_, token) = get_role_templates_with_token(&configuration, None, None, None, None, None, None, &options)
+            .await
+            .unwrap();
+        assert_eq!(token.as_deref(), Some("page-2"));
+
+        let (list, token) =
+            get_role_templates_with_token(&configuration, None, None, None, None, None, token.as_ref(), &options)
+                .await
+                .unwrap();
+        assert_eq!(list.items.len(), 1);
+        assert!(token.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_get_role_templates_page_surfaces_remaining_item_count() {
+        let rancher = crate::test_support::MockRancher::start().await;
+        rancher
+            .expect_list_role_templates()
+            .returning_json(
+                200,
+                serde_json::json!({
+                    "apiVersion": "management.cattle.io/v3",
+                    "items": [crate::test_support::role_template_json("rt-1")],
+                    "metadata": { "continue": "page-2", "remainingItemCount": 41 },
+                }),
+            )
+            .await;
+
+        let page = get_role_templates_page(&rancher.configuration(), None, None, Some(1), None)
+            .await
+            .unwrap();
+
+        assert_eq!(page.items.len(), 1);
+        assert_eq!(page.continue_token.as_deref(), Some("page-2"));
+        assert_eq!(page.remaining_item_count, Some(41));
+    }
+
     #[test]
-    fn test_iocattle_to_role_template_conversion_success() {
-        let io_rt = sample_iocattle_role_template();
-        let result = RoleTemplate::try_from(io_rt).unwrap();
-        assert_eq!(result.id, "admin-template");
-        assert_eq!(result.display_name.as_deref(), Some("Admin"));
-        assert_eq!(result.project_creator_default, Some(false));
+    fn test_diff_ignores_resource_version() {
+        let mut current = sample_iocattle_role_template();
+        current.metadata.as_mut().unwrap().resource_version = Some("1".to_string());
+        let mut latest = sample_iocattle_role_template();
+        latest.metadata.as_mut().unwrap().resource_version = Some("2".to_string());
+
+        let desired = RoleTemplate::try_from(current).unwrap();
+        let actual = RoleTemplate::try_from(latest).unwrap();
+
+        assert!(diff(&desired, &actual).is_none());
     }
 
     #[test]
-    fn test_role_template_to_iocattle_conversion_success() {
-        let rt = sample_role_template();
-        let result = IoCattleManagementv3RoleTemplate::try_from(rt).unwrap();
+    fn test_json_patch_single_changed_field() {
+        let actual = sample_role_template();
+        let mut desired = sample_role_template();
+        desired.description = Some("Updated".to_string());
+
+        let patch = to_json_patch(&desired, &actual);
+
         assert_eq!(
-            result.metadata.as_ref().unwrap().name.as_deref(),
-            Some("admin-template")
+            patch,
+            serde_json::json!([{ "op": "replace", "path": "/description", "value": "Updated" }])
         );
-        assert_eq!(result.display_name.as_deref(), Some("Admin"));
-        assert_eq!(result.project_creator_default, Some(false));
     }
 
     #[test]
-    fn test_iocattle_to_role_template_missing_metadata() {
-        let mut io_rt = sample_iocattle_role_template();
-        io_rt.metadata = None;
-        let result = RoleTemplate::try_from(io_rt);
-        assert!(result.is_err());
+    fn test_json_patch_never_touches_excluded_paths() {
+        let mut current = sample_iocattle_role_template();
+        current.metadata.as_mut().unwrap().resource_version = Some("1".to_string());
+        current.metadata.as_mut().unwrap().uid = Some("uid-1".to_string());
+        let mut latest = sample_iocattle_role_template();
+        latest.metadata.as_mut().unwrap().resource_version = Some("2".to_string());
+
+        let desired = RoleTemplate::try_from(current).unwrap();
+        let actual = RoleTemplate::try_from(latest).unwrap();
+
+        assert_eq!(to_json_patch(&desired, &actual), serde_json::json!([]));
     }
 
     #[test]
-    fn test_iocattle_to_role_template_missing_metadata_name() {
-        let mut io_rt = sample_iocattle_role_template();
-        io_rt.metadata.as_mut().unwrap().name = None;
-        let result = RoleTemplate::try_from(io_rt);
-        assert!(result.is_err());
+    fn test_json_patch_ignores_reordered_rules() {
+        let mut actual = sample_role_template();
+        actual.rules = Some(vec![sample_rule(&["secrets"], &["list"]), sample_rule(&["pods"], &["get"])]);
+        let mut desired = actual.clone();
+        desired.rules = Some(vec![sample_rule(&["pods"], &["get"]), sample_rule(&["secrets"], &["list"])]);
+
+        assert_eq!(to_json_patch(&desired, &actual), serde_json::json!([]));
     }
 
     #[test]
-    fn test_round_trip_conversion() {
-        let original = sample_role_template();
-        let back_and_forth = RoleTemplate::try_from(
-            IoCattleManagementv3RoleTemplate::try_from(original.clone()).unwrap(),
-        )
-        .unwrap();
-        assert_eq!(original, back_and_forth);
+    fn test_json_patch_applies_to_unsorted_actual() {
+        let mut actual = sample_role_template();
+        actual.rules = Some(vec![sample_rule(&["secrets"], &["list"]), sample_rule(&["pods"], &["get"])]);
+        let mut desired = actual.clone();
+        desired.rules = Some(vec![
+            sample_rule(&["pods"], &["get"]),
+            sample_rule(&["secrets"], &["list", "watch"]),
+        ]);
+
+        let patch: json_patch::Patch = serde_json::from_value(to_json_patch(&desired, &actual)).unwrap();
+        let mut served = api_value(&actual);
+        json_patch::patch(&mut served, &patch.0).unwrap();
+
+        let served: IoCattleManagementv3RoleTemplate = serde_json::from_value(served).unwrap();
+        let served = RoleTemplate::try_from(served).unwrap();
+        assert!(diff(&desired, &served).is_none());
     }
 
+    #[test]
+    fn test_content_hash_is_stable() {
+        let mut rt = sample_role_template();
+        rt.rules = Some(vec![sample_rule(&["pods"], &["get"]), sample_rule(&["secrets"], &["list"])]);
+        let mut reordered = rt.clone();
+        reordered.rules = Some(vec![sample_rule(&["secrets"], &["list"]), sample_rule(&["pods"], &["get"])]);
+        let mut changed = rt.clone();
+        changed.description = Some("Changed".to_string());
+
+        assert_eq!(content_hash(&rt), content_hash(&reordered));
+        assert_eq!(content_hash(&rt), content_hash(&with_content_hash(&rt)));
+        assert_ne!(content_hash(&rt), content_hash(&changed));
+    }
 
     #[test]
-    fn test_equality_both_directions() {
-        let rt = sample_role_template();
-        let iort = sample_iocattle_role_template();
+    fn test_diff_ignores_rule_order() {
+        let mut actual = sample_role_template();
+        actual.rules = Some(vec![sample_rule(&["pods"], &["get"]), sample_rule(&["secrets"], &["list"])]);
+        let mut desired = sample_role_template();
+        desired.rules = Some(vec![sample_rule(&["secrets"], &["list"]), sample_rule(&["pods"], &["get"])]);
 
-        // Forward comparison
-        assert_eq!(iort, rt);
+        assert!(diff(&desired, &actual).is_none());
+    }
 
-        // Reverse comparison
+    #[test]
+    fn test_reordered_role_template_names_are_equal() {
+        let mut rt = sample_role_template();
+        rt.role_template_names = Some(vec!["base-template".to_string(), "view".to_string()]);
+        let mut reordered = sample_role_template();
+        reordered.role_template_names = Some(vec!["view".to_string(), "base-template".to_string()]);
+
+        let iort = IoCattleManagementv3RoleTemplate::try_from(reordered.clone()).unwrap();
         assert_eq!(rt, iort);
+        assert_eq!(iort, rt);
+        assert!(diff(&rt, &reordered).is_none());
     }
 
     #[test]
-    fn test_inequality_on_field() {
+    fn test_diff_reports_rules_and_inherited_templates() {
+        let mut actual = sample_role_template();
+        actual.rules = Some(vec![sample_rule(&["pods"], &["get"])]);
+        let mut desired = sample_role_template();
+        desired.rules = Some(vec![sample_rule(&["pods"], &["watch"])]);
+        desired.role_template_names = Some(vec!["base-template".to_string(), "view".to_string()]);
+
+        let report = diff(&desired, &actual).unwrap();
+
+        assert_eq!(report.paths(), vec!["roleTemplateNames[1]", "rules[0].verbs[0]"]);
+        assert_eq!(report.added[0].path, "roleTemplateNames[1]");
+        assert_eq!(report.changed[0].path, "rules[0].verbs[0]");
+    }
+
+    #[test]
+    fn test_label_only_difference_is_unequal() {
         let rt = sample_role_template();
-        let mut iort = sample_iocattle_role_template();
+        let mut io_rt = IoCattleManagementv3RoleTemplate::try_from(rt.clone()).unwrap();
+        assert_eq!(io_rt, rt);
 
-        // Change display name
-        iort.display_name = Some("Changed".into());
+        io_rt.metadata.as_mut().unwrap().labels =
+            Some([("team".to_string(), "a".to_string())].into_iter().collect());
 
-        assert_ne!(rt, iort);
-        assert_ne!(iort, rt);
+        assert_ne!(io_rt, rt);
+        assert_ne!(rt, io_rt);
     }
 
     #[test]
-    fn test_missing_metadata_name() {
+    fn test_annotation_only_difference_is_unequal() {
+        let mut rt = sample_role_template();
+        let io_rt = IoCattleManagementv3RoleTemplate::try_from(rt.clone()).unwrap();
+        rt.annotations = Some([("owner".to_string(), "me".to_string())].into_iter().collect());
+
+        assert_ne!(io_rt, rt);
+        assert_ne!(rt, io_rt);
+    }
+
+    #[test]
+    fn test_server_managed_metadata_is_ignored() {
         let rt = sample_role_template();
-        let mut iort = sample_iocattle_role_template();
+        let mut io_rt = IoCattleManagementv3RoleTemplate::try_from(rt.clone()).unwrap();
+        io_rt.metadata.as_mut().unwrap().annotations = Some(
+            [("lifecycle.cattle.io/create.mgmt-auth-roletemplate-lifecycle".to_string(), "true".to_string())]
+                .into_iter()
+                .collect(),
+        );
+        io_rt.metadata.as_mut().unwrap().labels = None;
 
-        // Remove the name field
-        if let Some(metadata) = iort.metadata.as_mut() {
-            metadata.name = None;
-        }
+        assert_eq!(io_rt, rt);
+        assert_eq!(rt, io_rt);
+    }
 
-        assert_ne!(rt, iort);
-        assert_ne!(iort, rt);
+    fn exported_role_template() -> RoleTemplate {
+        let mut rt = sample_role_template();
+        rt.annotations = Some(HashMap::from([("owner".to_string(), "team-a".to_string())]));
+        rt.labels = Some(HashMap::from([("tier".to_string(), "gold".to_string())]));
+        rt.rules = Some(vec![sample_rule(&["pods"], &["get", "list"])]);
+        rt
+    }
+
+    #[test]
+    fn test_yaml_round_trip() {
+        let rt = exported_role_template();
+        let yaml = rt.to_yaml().unwrap();
+        assert_eq!(RoleTemplate::from_yaml(&yaml).unwrap(), rt);
+    }
+
+    #[test]
+    fn test_to_yaml_drops_server_managed_metadata() {
+        let mut rt = exported_role_template();
+        let mut annotations = rt.annotations.clone().unwrap();
+        annotations.insert(
+            "lifecycle.cattle.io/create.mgmt-auth-roletemplate-lifecycle".to_string(),
+            "true".to_string(),
+        );
+        annotations.insert(CONTENT_HASH_ANNOTATION.to_string(), "0123456789abcdef".to_string());
+        rt.annotations = Some(annotations);
+
+        let yaml = rt.to_yaml().unwrap();
+
+        assert!(!yaml.contains("lifecycle.cattle.io"));
+        assert!(!yaml.contains(CONTENT_HASH_ANNOTATION));
+        assert_eq!(RoleTemplate::from_yaml(&yaml).unwrap(), exported_role_template());
+    }
+
+    #[test]
+    fn test_to_yaml_is_byte_stable() {
+        let keys = ["zone", "app", "owner", "tier", "cost-center", "env"];
+        let mut first = exported_role_template();
+        first.labels = Some(keys.iter().map(|k| (k.to_string(), "x".to_string())).collect());
+        let mut second = first.clone();
+        second.labels = Some(keys.iter().rev().map(|k| (k.to_string(), "x".to_string())).collect());
+
+        assert_eq!(first.to_yaml().unwrap(), first.to_yaml().unwrap());
+        assert_eq!(first.to_yaml().unwrap(), second.to_yaml().unwrap());
+    }
+
+    #[test]
+    fn test_to_yaml_sorts_metadata_keys() {
+        let mut rt = exported_role_template();
+        rt.labels = Some(HashMap::from([
+            ("zone".to_string(), "a".to_string()),
+            ("app".to_string(), "b".to_string()),
+            ("env".to_string(), "c".to_string()),
+        ]));
+
+        let yaml = rt.to_yaml().unwrap();
+        let positions: Vec<usize> = ["app:", "env:", "zone:"]
+            .iter()
+            .map(|key| yaml.find(key).unwrap())
+            .collect();
+
+        assert!(positions.windows(2).all(|pair| pair[0] < pair[1]));
+    }
+
+    #[test]
+    fn test_manifest_yaml_has_kubectl_shape() {
+        let rt = RoleTemplate::from_yaml("id: rt-1\ncontext: project\ndisplay_name: Role One\n").unwrap();
+
+        let manifest: serde_json::Value =
+            serde_yaml::from_str(&rt.to_manifest_yaml().unwrap()).unwrap();
+
+        assert_eq!(
+            manifest,
+            serde_json::json!({
+                "apiVersion": "management.cattle.io/v3",
+                "kind": "RoleTemplate",
+                "metadata": { "name": "rt-1" },
+                "context": "project",
+                "displayName": "Role One",
+            })
+        );
     }
 
+    #[test]
+    fn test_builder_accumulates_rules() {
+        let rt = RoleTemplate::builder()
+            .id("read-workloads")
+            .context(Context::Project)
+            .rule(&[""], &["pods"], &["get", "list"])
+            .rule(&["apps"], &["deployments"], &["get"])
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            rt.rules,
+            Some(vec![
+                sample_rule(&["pods"], &["get", "list"]),
+                IoCattleManagementv3GlobalRoleRulesInner {
+                    api_groups: Some(vec!["apps".to_string()]),
+                    resources: Some(vec!["deployments".to_string()]),
+                    verbs: vec!["get".to_string()],
+                    ..Default::default()
+                },
+            ])
+        );
+        assert_eq!(rt.context, Some(Context::Project));
+    }
+
+    #[test]
+    fn test_builder_defaults() {
+        let rt = RoleTemplate::builder().id("empty").build().unwrap();
+
+        assert_eq!(rt.builtin, Some(false));
+        assert_eq!(rt.context, None);
+        assert_eq!(rt.rules, None);
+        assert_eq!(rt.labels, None);
+        assert_eq!(rt.display_name, None);
+    }
+
+    #[test]
+    fn test_builder_requires_id() {
+        let result = RoleTemplate::builder().rule(&[""], &["pods"], &["get"]).build();
+
+        assert_eq!(result.unwrap_err(), "id is required");
+    }
+
+    #[test]
+    fn test_default_serializes_cleanly() {
+        let rt = RoleTemplate {
+            id: "read-only".to_string(),
+            ..Default::default()
+        };
+
+        assert_eq!(serde_json::to_value(&rt).unwrap(), serde_json::json!({ "id": "read-only" }));
+        assert_eq!(RoleTemplate::default().id, "");
+    }
+
+    #[test]
+    fn test_key_ignores_fields_other_than_id() {
+        let role_template = sample_role_template();
+        let other = RoleTemplate {
+            display_name: Some("Other".to_string()),
+            rules: None,
+            ..sample_role_template()
+        };
+        let keys: std::collections::HashSet<ResourceKey> =
+            [role_template.key(), other.key()].into_iter().collect();
+
+        assert_eq!(keys.len(), 1);
+        assert_eq!(role_template.key().to_string(), role_template.id);
+    }
+
+    #[test]
+    fn test_key_orders_by_id() {
+        let role_template = |id: &str| RoleTemplate {
+            id: id.to_string(),
+            ..sample_role_template()
+        };
+        let mut role_templates = vec![
+            role_template("rt-c"),
+            role_template("rt-a"),
+            role_template("rt-b"),
+        ];
+
+        role_templates.sort_by_key(RoleTemplate::key);
+
+        let ids: Vec<&str> = role_templates.iter().map(|rt| rt.id.as_str()).collect();
+        assert_eq!(ids, vec!["rt-a", "rt-b", "rt-c"]);
+    }
+
+    #[test]
+    fn test_validate_rules_rejects_rule_without_verbs() {
+        let rules = vec![sample_rule(&["pods"], &["get"]), sample_rule(&["secrets"], &[])];
 
+        let issues = validate_rules(&rules);
 
+        assert_eq!(
+            issues,
+            vec![RuleIssue {
+                index: 1,
+                severity: RuleSeverity::Error,
+                message: "at least one verb is required".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_validate_rules_warns_on_wildcard_rule() {
+        let mut wildcard = sample_rule(&["*"], &["*"]);
+        wildcard.api_groups = Some(vec!["*".to_string()]);
+
+        let issues = validate_rules(&[wildcard]);
+
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].severity, RuleSeverity::Warning);
+
+        let mut rt = sample_role_template();
+        rt.rules = Some(vec![sample_rule(&[], &["get"])]);
+        let lints = lint_role_templates(&[sample_role_template(), rt]);
+        assert_eq!(lints.len(), 1);
+        assert_eq!(lints[0].0, "admin-template");
+        assert_eq!(
+            lints[0].1.to_string(),
+            "rule 0: at least one of resources or nonResourceURLs is required"
+        );
+    }
+
+    #[test]
+    fn test_detect_cycles_self_reference() {
+        let mut rt = sample_role_template();
+        rt.role_template_names = Some(vec!["admin-template".to_string(), "view".to_string()]);
+
+        assert_eq!(detect_cycles(&[rt]), vec![vec!["admin-template".to_string()]]);
+    }
+
+    #[test]
+    fn test_detect_cycles_two_templates() {
+        let inheriting = |id: &str, names: &[&str]| RoleTemplate {
+            id: id.to_string(),
+            role_template_names: Some(names.iter().map(|n| n.to_string()).collect()),
+            ..sample_role_template()
+        };
+        let templates = vec![
+            inheriting("rt-b", &["rt-a"]),
+            inheriting("rt-a", &["rt-b"]),
+            inheriting("rt-c", &["rt-a"]),
+        ];
+
+        assert_eq!(
+            detect_cycles(&templates),
+            vec![vec!["rt-a".to_string(), "rt-b".to_string()]]
+        );
+        assert!(detect_cycles(&[inheriting("rt-c", &["rt-a"]), inheriting("rt-a", &[])]).is_empty());
+    }
+
+    #[test]
+    fn test_check_context_rejects_mismatched_scope() {
+        let cluster_rt = sample_role_template();
+
+        assert_eq!(check_context(&cluster_rt, BindingScope::Cluster, "crtb-1"), Ok(()));
+        assert_eq!(
+            check_context(&cluster_rt, BindingScope::Project, "prtb-1"),
+            Err("project binding prtb-1 references role template admin-template whose context is cluster".to_string())
+        );
+
+        let project_rt = RoleTemplate {
+            context: Some(Context::Project),
+            ..sample_role_template()
+        };
+        assert!(check_context(&project_rt, BindingScope::Cluster, "crtb-1").is_err());
+        assert_eq!(check_context(&project_rt, BindingScope::Project, "prtb-1"), Ok(()));
+
+        let no_context = RoleTemplate {
+            context: None,
+            ..sample_role_template()
+        };
+        assert_eq!(check_context(&no_context, BindingScope::Project, "prtb-1"), Ok(()));
+    }
 }
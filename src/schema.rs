@@ -0,0 +1,83 @@
+use schemars::schema::RootSchema;
+use schemars::{schema_for, JsonSchema};
+
+use crate::prtb::ProjectRoleTemplateBinding;
+use crate::rt::RoleTemplate;
+
+/// The schema of `rancher_client`'s role template `Context`, which does not implement `JsonSchema`
+#[derive(JsonSchema)]
+#[serde(rename_all = "lowercase")]
+#[allow(dead_code)]
+pub(crate) enum ContextSchema {
+    Cluster,
+    Project,
+}
+
+/// The schema of `IoCattleManagementv3GlobalRoleRulesInner`, which does not implement `JsonSchema`
+#[derive(JsonSchema)]
+#[allow(dead_code)]
+pub(crate) struct PolicyRuleSchema {
+    #[serde(rename = "apiGroups")]
+    api_groups: Option<Vec<String>>,
+    #[serde(rename = "nonResourceURLs")]
+    non_resource_urls: Option<Vec<String>>,
+    #[serde(rename = "resourceNames")]
+    resource_names: Option<Vec<String>>,
+    resources: Option<Vec<String>>,
+    verbs: Vec<String>,
+}
+
+/// The JSON Schema of a `RoleTemplate` file, as read by `RoleTemplate::from_yaml`
+///
+/// # Returns
+///
+/// * `RootSchema` - The schema, serializable with `serde_json`
+pub fn role_template_schema() -> RootSchema {
+    schema_for!(RoleTemplate)
+}
+
+/// The JSON Schema of a `ProjectRoleTemplateBinding` file, as read by `ProjectRoleTemplateBinding::from_yaml`
+///
+/// # Returns
+///
+/// * `RootSchema` - The schema, serializable with `serde_json`
+pub fn project_role_template_binding_schema() -> RootSchema {
+    schema_for!(ProjectRoleTemplateBinding)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::Value;
+
+    fn required(schema: &Value) -> Vec<&str> {
+        schema["required"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|field| field.as_str().unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_role_template_schema() {
+        let schema = serde_json::to_value(role_template_schema()).unwrap();
+
+        assert_eq!(required(&schema), vec!["id"]);
+        for field in ["context", "display_name", "rules", "role_template_names", "labels"] {
+            assert!(schema["properties"].get(field).is_some(), "missing {}", field);
+        }
+    }
+
+    #[test]
+    fn test_project_role_template_binding_schema() {
+        let schema = serde_json::to_value(project_role_template_binding_schema()).unwrap();
+
+        let required = required(&schema);
+        assert!(required.contains(&"id"));
+        assert!(!required.contains(&"user_name"));
+        for field in ["user_name", "group_name", "service_account", "annotations"] {
+            assert!(schema["properties"].get(field).is_some(), "missing {}", field);
+        }
+    }
+}
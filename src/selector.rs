@@ -0,0 +1,245 @@
+use crate::metadata::{check_key, check_label_value};
+
+/// A Kubernetes field selector, rendered to the `fieldSelector` query parameter of list requests
+///
+/// Values are escaped, so a value holding `,`, `=` or `\` cannot add requirements of its own.
+///
+/// ```ignore
+/// let selector = FieldSelector::new()
+///     .eq("metadata.namespace", "c-123")
+///     .ne("metadata.name", "default");
+/// let list = get_projects(&configuration, "c-123", Some(&selector.to_string()), ...).await?;
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FieldSelector {
+    requirements: Vec<String>,
+}
+
+impl FieldSelector {
+    pub fn new() -> Self {
+        FieldSelector::default()
+    }
+
+    /// Require `field` to equal `value`
+    pub fn eq(self, field: &str, value: &str) -> Self {
+        self.with(field, "=", value)
+    }
+
+    /// Require `field` to differ from `value`
+    pub fn ne(self, field: &str, value: &str) -> Self {
+        self.with(field, "!=", value)
+    }
+
+    /// Whether the selector has no requirement, and so selects every object
+    pub fn is_empty(&self) -> bool {
+        self.requirements.is_empty()
+    }
+
+    fn with(mut self, field: &str, operator: &str, value: &str) -> Self {
+        self.requirements
+            .push(format!("{}{}{}", field, operator, escape_value(value)));
+        self
+    }
+}
+
+/// Escape the characters that separate requirements and operators, as `fields.EscapeValue` does
+fn escape_value(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        if matches!(c, '\\' | ',' | '=') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+impl std::fmt::Display for FieldSelector {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.requirements.join(","))
+    }
+}
+
+/// A Kubernetes label selector, rendered to the `labelSelector` query parameter of list requests
+///
+/// Keys and values are checked like the labels of an object, so a value holding `,` or `)` cannot
+/// add requirements of its own. Every builder method fails on an invalid key or value.
+///
+/// ```ignore
+/// let selector = LabelSelector::new()
+///     .eq("team", "platform")?
+///     .in_set("tier", &["gold", "silver"])?
+///     .not_exists("deprecated")?;
+/// let bindings = get_all_project_role_template_bindings(
+///     &configuration,
+///     None,
+///     Some(&selector.to_string()),
+///     &RequestOptions::default(),
+/// )
+/// .await?;
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LabelSelector {
+    requirements: Vec<String>,
+}
+
+impl LabelSelector {
+    pub fn new() -> Self {
+        LabelSelector::default()
+    }
+
+    /// Require the label `key` to equal `value`
+    ///
+    /// # Errors
+    ///
+    /// * `String` - What makes `key` or `value` invalid
+    pub fn eq(self, key: &str, value: &str) -> Result<Self, String> {
+        check_label_value(value)?;
+        self.with(key, format!("{}={}", key, value))
+    }
+
+    /// Require the label `key` to be missing or to differ from `value`
+    ///
+    /// # Errors
+    ///
+    /// * `String` - What makes `key` or `value` invalid
+    pub fn ne(self, key: &str, value: &str) -> Result<Self, String> {
+        check_label_value(value)?;
+        self.with(key, format!("{}!={}", key, value))
+    }
+
+    /// Require the label `key` to be one of `values`
+    ///
+    /// # Errors
+    ///
+    /// * `String` - What makes `key` or one of `values` invalid, or that `values` is empty
+    pub fn in_set(self, key: &str, values: &[&str]) -> Result<Self, String> {
+        let values = set_values(values)?;
+        self.with(key, format!("{} in ({})", key, values))
+    }
+
+    /// Require the label `key` to be missing or none of `values`
+    ///
+    /// # Errors
+    ///
+    /// * `String` - What makes `key` or one of `values` invalid, or that `values` is empty
+    pub fn not_in(self, key: &str, values: &[&str]) -> Result<Self, String> {
+        let values = set_values(values)?;
+        self.with(key, format!("{} notin ({})", key, values))
+    }
+
+    /// Require the label `key` to be set, whatever its value
+    ///
+    /// # Errors
+    ///
+    /// * `String` - What makes `key` invalid
+    pub fn exists(self, key: &str) -> Result<Self, String> {
+        self.with(key, key.to_string())
+    }
+
+    /// Require the label `key` to be missing
+    ///
+    /// # Errors
+    ///
+    /// * `String` - What makes `key` invalid
+    pub fn not_exists(self, key: &str) -> Result<Self, String> {
+        self.with(key, format!("!{}", key))
+    }
+
+    /// Whether the selector has no requirement, and so selects every object
+    pub fn is_empty(&self) -> bool {
+        self.requirements.is_empty()
+    }
+
+    fn with(mut self, key: &str, requirement: String) -> Result<Self, String> {
+        check_key(key)?;
+        self.requirements.push(requirement);
+        Ok(self)
+    }
+}
+
+/// Check the values of a set requirement and join them, the API server rejects an empty set
+fn set_values(values: &[&str]) -> Result<String, String> {
+    if values.is_empty() {
+        return Err("a set requirement needs at least one value".to_string());
+    }
+    for value in values {
+        check_label_value(value)?;
+    }
+    Ok(values.join(","))
+}
+
+impl std::fmt::Display for LabelSelector {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.requirements.join(","))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_renders_comma_joined_requirements() {
+        let selector = FieldSelector::new()
+            .eq("metadata.namespace", "c-123")
+            .ne("metadata.name", "p-456");
+
+        assert_eq!(selector.to_string(), "metadata.namespace=c-123,metadata.name!=p-456");
+        assert!(FieldSelector::new().is_empty());
+        assert_eq!(FieldSelector::new().to_string(), "");
+    }
+
+    #[test]
+    fn test_escapes_values() {
+        let selector = FieldSelector::new().eq("metadata.name", "a,metadata.namespace=b\\");
+
+        assert_eq!(selector.to_string(), "metadata.name=a\\,metadata.namespace\\=b\\\\");
+    }
+
+    #[test]
+    fn test_label_selector_operators() {
+        assert_eq!(LabelSelector::new().eq("team", "platform").unwrap().to_string(), "team=platform");
+        assert_eq!(LabelSelector::new().ne("team", "platform").unwrap().to_string(), "team!=platform");
+        assert_eq!(
+            LabelSelector::new().in_set("tier", &["gold", "silver"]).unwrap().to_string(),
+            "tier in (gold,silver)"
+        );
+        assert_eq!(
+            LabelSelector::new().not_in("tier", &["bronze"]).unwrap().to_string(),
+            "tier notin (bronze)"
+        );
+        assert_eq!(LabelSelector::new().exists("managed-by").unwrap().to_string(), "managed-by");
+        assert_eq!(LabelSelector::new().not_exists("deprecated").unwrap().to_string(), "!deprecated");
+    }
+
+    #[test]
+    fn test_label_selector_joins_requirements() {
+        let selector = LabelSelector::new()
+            .eq("team", "platform")
+            .and_then(|s| s.in_set("tier", &["gold", "silver"]))
+            .and_then(|s| s.not_exists("deprecated"))
+            .unwrap();
+
+        assert_eq!(selector.to_string(), "team=platform,tier in (gold,silver),!deprecated");
+        assert!(LabelSelector::new().is_empty());
+    }
+
+    #[test]
+    fn test_label_selector_rejects_injected_requirement() {
+        assert!(LabelSelector::new().eq("team", "a,b").is_err());
+        assert!(LabelSelector::new().in_set("tier", &["gold)", "x"]).is_err());
+        assert!(LabelSelector::new().exists("team,tier").is_err());
+    }
+
+    #[test]
+    fn test_label_selector_rejects_empty_set() {
+        assert!(LabelSelector::new().in_set("tier", &[]).is_err());
+        assert!(LabelSelector::new().not_in("tier", &[]).is_err());
+    }
+
+    #[test]
+    fn test_label_selector_allows_empty_value() {
+        assert_eq!(LabelSelector::new().eq("team", "").unwrap().to_string(), "team=");
+    }
+}
@@ -0,0 +1,331 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use rancher_client::apis::configuration::Configuration;
+
+use rancher_client::{
+    apis::management_cattle_io_v3_api::{
+        read_management_cattle_io_v3_setting, ReadManagementCattleIoV3SettingError,
+    },
+    models::{
+        IoCattleManagementv3Setting, IoCattleManagementv3SettingList,
+        IoK8sApimachineryPkgApisMetaV1ObjectMeta,
+    },
+};
+
+use crate::client::RequestOptions;
+use crate::metadata::validate_name;
+use crate::paginate::ContinueToken;
+use crate::request::{
+    get_list, list_query, optional_object, parse_response, send_request, MERGE_PATCH_CONTENT_TYPE,
+};
+use crate::retry::RetryPolicy;
+use crate::{checked_limit, continue_token, RancherError};
+
+/// The API path of the setting list
+const SETTINGS_PATH: &str = "/apis/management.cattle.io/v3/settings";
+
+pub const SETTING_EXCLUDE_PATHS: &[&str] = &[
+    "customized",
+    "default",
+    "metadata.creationTimestamp",
+    "metadata.finalizers",
+    "metadata.generateName",
+    "metadata.generation",
+    "metadata.managedFields",
+    "metadata.resourceVersion",
+    "metadata.selfLink",
+    "metadata.uid",
+    "source",
+];
+
+/// Get a page of settings
+///
+/// A page rate limited by the server is retried with the default `RetryPolicy`, and the request
+/// times out after the timeout of `options`.
+///
+/// # Arguments
+///
+/// * `configuration` - The configuration to use for the request
+/// * `field_selector` - A selector to restrict the settings by their fields
+/// * `label_selector` - A selector to restrict the settings by their labels
+/// * `limit` - The largest number of settings to return, clamped to the largest page size of `options`
+/// * `continue_` - The token returned with the previous page, `None` for the first page
+/// * `options` - The timeout and largest page size of the request
+///
+/// # Returns
+///
+/// * `IoCattleManagementv3SettingList` - The page of settings
+/// * `Option<ContinueToken>` - The token to fetch the next page, `None` on the last page
+///
+/// # Errors
+///
+/// * `RancherError` - The error that occurred while trying to get the settings
+///
+#[async_backtrace::framed]
+pub async fn get_settings(
+    configuration: &Configuration,
+    field_selector: Option<&str>,
+    label_selector: Option<&str>,
+    limit: Option<i32>,
+    continue_: Option<&ContinueToken>,
+    options: &RequestOptions,
+) -> Result<(IoCattleManagementv3SettingList, Option<ContinueToken>), RancherError> {
+    let limit = checked_limit(limit, options)?;
+    let query = list_query(field_selector, label_selector, limit, continue_.map(ContinueToken::as_str));
+    let list: IoCattleManagementv3SettingList = get_list(
+        configuration,
+        SETTINGS_PATH,
+        &query,
+        &RetryPolicy::default(),
+        options,
+    )
+    .await?;
+    let token = continue_token(list.metadata.as_deref()).map(ContinueToken::new);
+    Ok((list, token))
+}
+
+/// Get a setting by name
+///
+/// # Arguments
+///
+/// * `configuration` - The configuration to use for the request
+/// * `name` - The name of the setting, e.g. `server-url`
+///
+/// # Returns
+///
+/// * `Option<Setting>` - The setting, or `None` when it does not exist
+///
+/// # Errors
+///
+/// * `RancherError` - The error that occurred while reading or converting the setting
+///
+#[async_backtrace::framed]
+pub async fn get_setting(
+    configuration: &Configuration,
+    name: &str,
+) -> Result<Option<Setting>, RancherError> {
+    let result = read_management_cattle_io_v3_setting(configuration, name, None, None).await;
+    optional_object::<IoCattleManagementv3Setting, _>(
+        result,
+        ReadManagementCattleIoV3SettingError::UnknownValue,
+    )?
+    .map(Setting::try_from)
+    .transpose()
+    .map_err(RancherError::conversion)
+}
+
+/// Build the merge patch setting the value of a setting
+///
+/// Only `value` is written, the `default` shipped by Rancher is left untouched.
+///
+/// # Arguments
+///
+/// * `value` - The new value of the setting
+///
+/// # Returns
+///
+/// * `serde_json::Value` - The merge patch, e.g. `{"value": "https://rancher.example.com"}`
+pub fn setting_value_patch(value: &str) -> serde_json::Value {
+    serde_json::json!({ "value": value })
+}
+
+/// Set the value of a setting with a JSON merge patch (RFC 7386)
+///
+/// # Arguments
+///
+/// * `configuration` - The configuration to use for the request
+/// * `name` - The name of the setting, e.g. `auth-user-session-ttl-minutes`
+/// * `value` - The new value of the setting
+///
+/// # Returns
+///
+/// * `Setting` - The setting as patched by the server
+///
+/// # Errors
+///
+/// * `RancherError` - The error that occurred while patching the setting
+///
+#[async_backtrace::framed]
+pub async fn update_setting(
+    configuration: &Configuration,
+    name: &str,
+    value: &str,
+) -> Result<Setting, RancherError> {
+    let path = format!("{}/{}", SETTINGS_PATH, name);
+    let response_content = send_request(
+        configuration,
+        reqwest::Method::PATCH,
+        &path,
+        MERGE_PATCH_CONTENT_TYPE,
+        Some(&setting_value_patch(value)),
+    )
+    .await?;
+    let patched: IoCattleManagementv3Setting =
+        serde_json::from_value(parse_response(response_content)?).map_err(RancherError::Serde)?;
+    Setting::try_from(patched).map_err(RancherError::conversion)
+}
+
+/// A Rancher global setting
+///
+/// Rancher ships every setting with a `default`, the `value` overrides it when it is not empty.
+/// Only `value` is managed, the other fields are read-only and reported for reference.
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq)]
+pub struct Setting {
+    /// Whether the value was changed from the default. Read-only.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub customized: Option<bool>,
+    /// The value Rancher uses when `value` is empty. Read-only.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default: Option<String>,
+    /// The name of the setting, e.g. `server-url`
+    pub id: String,
+    /// Where the effective value comes from, e.g. `env` when set by an environment variable. Read-only.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+    pub value: String,
+}
+
+impl Setting {
+    /// The value Rancher applies: `value` when set, otherwise `default`
+    pub fn effective_value(&self) -> &str {
+        if self.value.is_empty() {
+            self.default.as_deref().unwrap_or_default()
+        } else {
+            &self.value
+        }
+    }
+}
+
+impl TryFrom<IoCattleManagementv3Setting> for Setting {
+    type Error = &'static str;
+
+    fn try_from(value: IoCattleManagementv3Setting) -> Result<Self, Self::Error> {
+        let metadata: IoK8sApimachineryPkgApisMetaV1ObjectMeta = value.metadata.ok_or("missing metadata")?;
+
+        Ok(Setting {
+            customized: value.customized,
+            default: value.default,
+            id: metadata.name.ok_or("missing metadata.name")?,
+            source: value.source,
+            value: value.value,
+        })
+    }
+}
+
+impl TryFrom<Setting> for IoCattleManagementv3Setting {
+    type Error = &'static str;
+
+    fn try_from(value: Setting) -> Result<Self, Self::Error> {
+        validate_name(&value.id)?;
+        let metadata = IoK8sApimachineryPkgApisMetaV1ObjectMeta {
+            name: Some(value.id),
+            ..Default::default()
+        };
+
+        Ok(IoCattleManagementv3Setting {
+            api_version: Some("management.cattle.io/v3".to_string()),
+            customized: value.customized,
+            default: value.default,
+            kind: Some("Setting".to_string()),
+            metadata: Some(metadata),
+            source: value.source,
+            value: value.value,
+        })
+    }
+}
+
+impl PartialEq<Setting> for IoCattleManagementv3Setting {
+    /// Settings are compared on their name and value, the read-only fields are ignored
+    fn eq(&self, other: &Setting) -> bool {
+        self.metadata.as_ref().and_then(|m| m.name.as_deref()) == Some(other.id.as_str())
+            && self.value == other.value
+    }
+}
+
+impl PartialEq<IoCattleManagementv3Setting> for Setting {
+    fn eq(&self, other: &IoCattleManagementv3Setting) -> bool {
+        other == self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_setting() -> Setting {
+        Setting {
+            customized: Some(true),
+            default: Some("960".to_string()),
+            id: "auth-user-session-ttl-minutes".to_string(),
+            source: None,
+            value: "480".to_string(),
+        }
+    }
+
+    fn sample_iocattle_setting() -> IoCattleManagementv3Setting {
+        IoCattleManagementv3Setting {
+            api_version: Some("management.cattle.io/v3".to_string()),
+            customized: Some(true),
+            default: Some("960".to_string()),
+            kind: Some("Setting".to_string()),
+            metadata: Some(IoK8sApimachineryPkgApisMetaV1ObjectMeta {
+                name: Some("auth-user-session-ttl-minutes".to_string()),
+                ..Default::default()
+            }),
+            source: None,
+            value: "480".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_iocattle_to_setting_conversion_success() {
+        let setting = Setting::try_from(sample_iocattle_setting()).unwrap();
+
+        assert_eq!(setting, sample_setting());
+        assert_eq!(setting.default.as_deref(), Some("960"));
+    }
+
+    #[test]
+    fn test_equality_ignores_default() {
+        let mut iosetting = sample_iocattle_setting();
+        iosetting.default = Some("1440".to_string());
+
+        assert_eq!(sample_setting(), iosetting);
+
+        iosetting.value = "960".to_string();
+        assert_ne!(sample_setting(), iosetting);
+    }
+
+    #[test]
+    fn test_effective_value_falls_back_to_default() {
+        let mut setting = sample_setting();
+        assert_eq!(setting.effective_value(), "480");
+
+        setting.value = String::new();
+        assert_eq!(setting.effective_value(), "960");
+    }
+
+    #[test]
+    fn test_value_patch_only_sets_value() {
+        assert_eq!(setting_value_patch("480"), serde_json::json!({ "value": "480" }));
+    }
+
+    #[tokio::test]
+    async fn test_update_setting_sends_value_merge_patch() {
+        let rancher = crate::test_support::MockRancher::start().await;
+        rancher
+            .expect("PATCH", "/apis/management.cattle.io/v3/settings/auth-user-session-ttl-minutes")
+            .with_header("content-type", MERGE_PATCH_CONTENT_TYPE)
+            .with_body_partial(serde_json::json!({ "value": "480" }))
+            .expect_calls(1)
+            .returning_json(200, serde_json::to_value(sample_iocattle_setting()).unwrap())
+            .await;
+
+        let setting = update_setting(&rancher.configuration(), "auth-user-session-ttl-minutes", "480")
+            .await
+            .unwrap();
+
+        assert_eq!(setting, sample_setting());
+    }
+}
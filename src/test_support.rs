@@ -0,0 +1,311 @@
+// Shared helpers for tests that talk to a mocked Rancher API
+use std::path::PathBuf;
+
+use serde::Serialize;
+use serde_json::{json, Value};
+
+use wiremock::matchers::{
+    body_partial_json, header, method, path, query_param, query_param_is_missing,
+};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+use rancher_client::apis::configuration::Configuration;
+
+use crate::client::RancherClient;
+use crate::rancher_config_init;
+
+const ROLE_TEMPLATES_PATH: &str = "/apis/management.cattle.io/v3/roletemplates";
+const PRTB_ALL_NAMESPACES_PATH: &str = "/apis/management.cattle.io/v3/projectroletemplatebindings";
+
+/// A mock Rancher server backed by wiremock
+pub struct MockRancher {
+    server: MockServer,
+}
+
+impl MockRancher {
+    pub async fn start() -> Self {
+        MockRancher {
+            server: MockServer::start().await,
+        }
+    }
+
+    /// A configuration pointing at the mock server
+    pub fn configuration(&self) -> Configuration {
+        rancher_config_init(&self.server.uri(), "token-test:secret")
+    }
+
+    /// A client sending its requests to the mock server
+    pub fn client(&self) -> RancherClient {
+        RancherClient::new(self.configuration())
+    }
+
+    /// Expect a request with the given method on the given path
+    pub fn expect(&self, http_method: &str, api_path: &str) -> MockEndpoint<'_> {
+        MockEndpoint {
+            server: &self.server,
+            method: http_method.to_string(),
+            path: api_path.to_string(),
+            query: Vec::new(),
+            missing_query: Vec::new(),
+            headers: Vec::new(),
+            body: None,
+            times: None,
+            calls: None,
+        }
+    }
+
+    pub fn expect_list_role_templates(&self) -> MockEndpoint<'_> {
+        self.expect("GET", ROLE_TEMPLATES_PATH)
+    }
+
+    pub fn expect_get_role_template(&self, name: &str) -> MockEndpoint<'_> {
+        self.expect("GET", &format!("{}/{}", ROLE_TEMPLATES_PATH, name))
+    }
+
+    pub fn expect_create_role_template(&self) -> MockEndpoint<'_> {
+        self.expect("POST", ROLE_TEMPLATES_PATH)
+    }
+
+    pub fn expect_list_project_role_template_bindings(&self) -> MockEndpoint<'_> {
+        self.expect("GET", PRTB_ALL_NAMESPACES_PATH)
+    }
+
+    pub fn expect_list_namespaced_project_role_template_bindings(
+        &self,
+        namespace: &str,
+    ) -> MockEndpoint<'_> {
+        self.expect(
+            "GET",
+            &format!(
+                "/apis/management.cattle.io/v3/namespaces/{}/projectroletemplatebindings",
+                namespace
+            ),
+        )
+    }
+
+    pub fn expect_create_namespaced_project_role_template_binding(
+        &self,
+        namespace: &str,
+    ) -> MockEndpoint<'_> {
+        self.expect(
+            "POST",
+            &format!(
+                "/apis/management.cattle.io/v3/namespaces/{}/projectroletemplatebindings",
+                namespace
+            ),
+        )
+    }
+}
+
+/// A single expected request, mounted on the server by one of the `returning*` methods
+pub struct MockEndpoint<'a> {
+    server: &'a MockServer,
+    method: String,
+    path: String,
+    query: Vec<(String, String)>,
+    missing_query: Vec<String>,
+    headers: Vec<(String, String)>,
+    body: Option<Value>,
+    times: Option<u64>,
+    calls: Option<u64>,
+}
+
+impl MockEndpoint<'_> {
+    /// Only match requests carrying this query parameter
+    pub fn with_query(mut self, key: &str, value: &str) -> Self {
+        self.query.push((key.to_string(), value.to_string()));
+        self
+    }
+
+    /// Only match requests without this query parameter
+    pub fn without_query(mut self, key: &str) -> Self {
+        self.missing_query.push(key.to_string());
+        self
+    }
+
+    /// Only match requests carrying this header value
+    pub fn with_header(mut self, key: &str, value: &str) -> Self {
+        self.headers.push((key.to_string(), value.to_string()));
+        self
+    }
+
+    /// Only match requests whose JSON body contains `body`
+    pub fn with_body_partial(mut self, body: Value) -> Self {
+        self.body = Some(body);
+        self
+    }
+
+    /// Only respond to the first `n` matching requests, later requests fall through to other mocks
+    pub fn times(mut self, n: u64) -> Self {
+        self.times = Some(n);
+        self
+    }
+
+    /// Verify the endpoint is called exactly `n` times when the server is dropped
+    pub fn expect_calls(mut self, n: u64) -> Self {
+        self.calls = Some(n);
+        self
+    }
+
+    /// Respond with a list containing `items` and no continue token
+    pub async fn returning<T: Serialize>(self, items: Vec<T>) {
+        self.returning_page(items, None).await
+    }
+
+    /// Respond with a single page of a list, with an optional continue token
+    pub async fn returning_page<T: Serialize>(self, items: Vec<T>, continue_token: Option<&str>) {
+        let body = json!({
+            "apiVersion": "management.cattle.io/v3",
+            "items": items,
+            "metadata": { "continue": continue_token.unwrap_or_default() },
+        });
+        self.returning_json(200, body).await
+    }
+
+    /// Respond with a Kubernetes `Status` failure body
+    pub async fn returning_status(self, status: u16, reason: &str, message: &str) {
+        let body = json!({
+            "apiVersion": "v1",
+            "kind": "Status",
+            "status": "Failure",
+            "message": message,
+            "reason": reason,
+            "code": status,
+        });
+        self.returning_json(status, body).await
+    }
+
+    /// Respond with an arbitrary JSON body
+    pub async fn returning_json(self, status: u16, body: Value) {
+        self.returning_template(ResponseTemplate::new(status).set_body_json(body))
+            .await
+    }
+
+    /// Respond with a fully custom response
+    pub async fn returning_template(self, template: ResponseTemplate) {
+        let mut builder = Mock::given(method(self.method.as_str())).and(path(self.path.as_str()));
+        for (key, value) in &self.query {
+            builder = builder.and(query_param(key.as_str(), value.as_str()));
+        }
+        for key in &self.missing_query {
+            builder = builder.and(query_param_is_missing(key.as_str()));
+        }
+        for (key, value) in &self.headers {
+            builder = builder.and(header(key.as_str(), value.as_str()));
+        }
+        if let Some(body) = self.body {
+            builder = builder.and(body_partial_json(body));
+        }
+
+        let mut mock = builder.respond_with(template);
+        if let Some(times) = self.times {
+            mock = mock.up_to_n_times(times);
+        }
+        if let Some(calls) = self.calls {
+            mock = mock.expect(calls);
+        }
+        mock.mount(self.server).await;
+    }
+}
+
+/// A minimal raw role template as Rancher would return it
+pub fn role_template_json(name: &str) -> Value {
+    json!({
+        "apiVersion": "management.cattle.io/v3",
+        "kind": "RoleTemplate",
+        "metadata": { "name": name, "resourceVersion": "1" },
+        "context": "project",
+        "displayName": name,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rt::{get_role_templates, get_role_templates_by_names, BATCH_LIST_THRESHOLD};
+    use reqwest::StatusCode;
+    use rancher_client::apis::Error;
+
+    #[tokio::test]
+    async fn test_list_role_templates() {
+        let rancher = MockRancher::start().await;
+        rancher
+            .expect_list_role_templates()
+            .returning(vec![role_template_json("rt-a"), role_template_json("rt-b")])
+            .await;
+
+        let list = get_role_templates(&rancher.configuration(), None, None, None, None, None, None)
+            .await
+            .unwrap();
+        assert_eq!(list.items.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_rate_limited_then_success() {
+        let rancher = MockRancher::start().await;
+        rancher
+            .expect_list_role_templates()
+            .times(1)
+            .returning_status(429, "TooManyRequests", "slow down")
+            .await;
+        rancher
+            .expect_list_role_templates()
+            .returning(vec![role_template_json("rt-a")])
+            .await;
+
+        let configuration = rancher.configuration();
+        let mut attempts = 0;
+        let list = loop {
+            attempts += 1;
+            match get_role_templates(&configuration, None, None, None, None, None, None).await {
+                Err(Error::ResponseError(response)) if response.status == StatusCode::TOO_MANY_REQUESTS => continue,
+                other => break other.unwrap(),
+            }
+        };
+        assert_eq!(attempts, 2);
+        assert_eq!(list.items.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_large_name_set_uses_one_list_call() {
+        let rancher = MockRancher::start().await;
+        rancher
+            .expect_list_role_templates()
+            .expect_calls(1)
+            .returning(vec![role_template_json("rt-0")])
+            .await;
+
+        let names: Vec<String> = (0..=BATCH_LIST_THRESHOLD).map(|i| format!("rt-{}", i)).collect();
+        let resolved = get_role_templates_by_names(&rancher.configuration(), &names)
+            .await
+            .unwrap();
+
+        assert_eq!(resolved.len(), names.len());
+        assert!(resolved["rt-0"].is_some());
+        assert!(resolved["rt-1"].is_none());
+    }
+}
+
+/// A fresh directory under the system temp directory, removed when dropped
+pub struct TestDir(pub PathBuf);
+
+impl TestDir {
+    pub fn new(name: &str) -> Self {
+        let path = std::env::temp_dir().join(format!("rancher-cac-test-{}-{}", std::process::id(), name));
+        let _ = std::fs::remove_dir_all(&path);
+        std::fs::create_dir_all(&path).unwrap();
+        TestDir(path)
+    }
+
+    pub fn write(&self, relative: &str, content: &str) {
+        let path = self.0.join(relative);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, content).unwrap();
+    }
+}
+
+impl Drop for TestDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
@@ -0,0 +1,358 @@
+use std::time::Duration;
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use rancher_client::apis::{configuration::Configuration, Error, ResponseContent};
+use reqwest::StatusCode;
+
+use rancher_client::{
+    apis::management_cattle_io_v3_api::{
+        create_management_cattle_io_v3_token, delete_management_cattle_io_v3_token,
+        CreateManagementCattleIoV3TokenError, DeleteManagementCattleIoV3TokenError,
+    },
+    models::{
+        IoCattleManagementv3Token, IoCattleManagementv3TokenList,
+        IoK8sApimachineryPkgApisMetaV1ObjectMeta,
+    },
+};
+
+use crate::client::RequestOptions;
+use crate::paginate::Paginator;
+use crate::request::{get_list, is_write_success, list_query};
+use crate::retry::RetryPolicy;
+use crate::{checked_limit, continue_token, ApiStatus, RancherError, DEFAULT_PAGE_SIZE};
+
+/// The API path of the token list
+const TOKENS_PATH: &str = "/apis/management.cattle.io/v3/tokens";
+
+/// The prefix of the name the server generates for a created token
+const TOKEN_GENERATE_NAME: &str = "token-";
+
+/// Get every API token, following the continue token
+///
+/// The secret of a token is only returned when it is created, listed tokens never carry it. Pages
+/// are `DEFAULT_PAGE_SIZE` items clamped to the largest page size of `options`.
+///
+/// # Arguments
+///
+/// * `configuration` - The configuration to use for the requests
+/// * `label_selector` - A selector to restrict the tokens by their labels
+/// * `options` - The timeout and largest page size of the requests
+///
+/// # Returns
+///
+/// * `Vec<Token>` - The tokens of every page
+///
+/// # Errors
+///
+/// * `RancherError` - The first error that occurred while fetching a page or converting a token
+///
+#[async_backtrace::framed]
+pub async fn get_tokens(
+    configuration: &Configuration,
+    label_selector: Option<&str>,
+    options: &RequestOptions,
+) -> Result<Vec<Token>, RancherError> {
+    let limit = checked_limit(Some(DEFAULT_PAGE_SIZE), options)?;
+    let items = Paginator::new(move |continue_: Option<String>| async move {
+        let query = list_query(None, label_selector, limit, continue_.as_deref());
+        let list: IoCattleManagementv3TokenList =
+            get_list(configuration, TOKENS_PATH, &query, &RetryPolicy::default(), options).await?;
+        Ok::<_, RancherError>((list.items, continue_token(list.metadata.as_deref())))
+    })
+    .collect_all()
+    .await?;
+
+    items
+        .into_iter()
+        .map(|item| Token::try_from(item).map_err(|e| RancherError::Invalid(e.to_string())))
+        .collect()
+}
+
+/// Create an API token for a user
+///
+/// The server returns the secret of the token in this response only, it is handed back as a
+/// `TokenSecret` that is never printed and can be read once.
+///
+/// # Arguments
+///
+/// * `configuration` - The configuration to use for the request
+/// * `user_id` - The ID of the user the token authenticates as, e.g. `u-abc12`
+/// * `description` - What the token is used for, e.g. `CI pipeline of team-a`
+/// * `ttl` - How long the token is valid, `None` for the server default
+///
+/// # Returns
+///
+/// * `CreatedToken` - The token and its secret
+///
+/// # Errors
+///
+/// * `RancherError` - `Invalid` when the response holds no secret, or the error that occurred while creating the token
+///
+#[async_backtrace::framed]
+pub async fn create_token(
+    configuration: &Configuration,
+    user_id: &str,
+    description: &str,
+    ttl: Option<Duration>,
+) -> Result<CreatedToken, RancherError> {
+    let body = IoCattleManagementv3Token {
+        api_version: Some("management.cattle.io/v3".to_string()),
+        description: Some(description.to_string()),
+        kind: Some("Token".to_string()),
+        metadata: Some(IoK8sApimachineryPkgApisMetaV1ObjectMeta {
+            generate_name: Some(TOKEN_GENERATE_NAME.to_string()),
+            ..Default::default()
+        }),
+        ttl: ttl.map(|ttl| i64::try_from(ttl.as_millis()).unwrap_or(i64::MAX)),
+        user_id: Some(user_id.to_string()),
+        ..Default::default()
+    };
+
+    let result = create_management_cattle_io_v3_token(configuration, body, None, None, None, None).await;
+    match result {
+        Err(e) => Err(e.into()),
+        Ok(response_content) => {
+            // Match on the status code and deserialize accordingly
+            match response_content.status {
+                status if is_write_success(status) => {
+                    // Deserialize the created IoCattleManagementv3Token, the only response holding the secret
+                    let mut data: IoCattleManagementv3Token =
+                        serde_json::from_str(&response_content.content).map_err(RancherError::Serde)?;
+                    let secret = data.token.take().filter(|secret| !secret.is_empty()).ok_or_else(|| {
+                        RancherError::Invalid("the created token holds no secret".to_string())
+                    })?;
+                    let token = Token::try_from(data).map_err(RancherError::conversion)?;
+                    let secret = TokenSecret(format!("{}:{}", token.id, secret));
+                    Ok(CreatedToken { token, secret })
+                }
+                _ => {
+                    // If not a success, treat as UnknownValue
+                    match serde_json::from_str::<serde_json::Value>(&response_content.content) {
+                        Ok(unknown_data) => {
+                            // Handle the unknown response
+                            Err(Error::ResponseError(ResponseContent {
+                                status: response_content.status,
+                                content: response_content.content,
+                                entity: Some(CreateManagementCattleIoV3TokenError::UnknownValue(
+                                    unknown_data,
+                                )),
+                            })
+                            .into())
+                        }
+                        Err(deserialize_err) => Err(RancherError::Serde(deserialize_err)),
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Delete an API token, revoking it
+///
+/// # Arguments
+///
+/// * `configuration` - The configuration to use for the request
+/// * `name` - The name of the token to delete
+///
+/// # Errors
+///
+/// * `RancherError` - `NotFound` when the token does not exist, so callers can treat it as already revoked, `Forbidden` when the server refuses the deletion, or the error that occurred while deleting the token
+///
+#[async_backtrace::framed]
+pub async fn delete_token(
+    configuration: &Configuration,
+    name: &str,
+) -> Result<(), RancherError> {
+    let result = delete_management_cattle_io_v3_token(
+        configuration,
+        name,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+    )
+    .await;
+    match result {
+        Err(e) => Err(e.into()),
+        Ok(response_content) => {
+            // Match on the status code
+            match response_content.status {
+                status if is_write_success(status) => Ok(()),
+                StatusCode::NOT_FOUND => Err(RancherError::NotFound),
+                StatusCode::FORBIDDEN => Err(RancherError::Forbidden(ApiStatus::parse(&response_content.content))),
+                _ => {
+                    // Otherwise treat as UnknownValue
+                    match serde_json::from_str::<serde_json::Value>(&response_content.content) {
+                        Ok(unknown_data) => {
+                            // Handle the unknown response
+                            Err(Error::ResponseError(ResponseContent {
+                                status: response_content.status,
+                                content: response_content.content,
+                                entity: Some(DeleteManagementCattleIoV3TokenError::UnknownValue(
+                                    unknown_data,
+                                )),
+                            })
+                            .into())
+                        }
+                        Err(deserialize_err) => Err(RancherError::Serde(deserialize_err)),
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// An API token, without its secret
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq)]
+pub struct Token {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Whether the token is past its expiry. Read-only.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expired: Option<bool>,
+    /// When the token expires. Read-only.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<String>,
+    /// The name of the token, the part of the bearer token before the colon
+    pub id: String,
+    /// How long the token is valid after its creation, in milliseconds, zero for no expiry
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ttl: Option<i64>,
+    /// The ID of the user the token authenticates as
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user_id: Option<String>,
+}
+
+impl TryFrom<IoCattleManagementv3Token> for Token {
+    type Error = &'static str;
+
+    /// The secret of the token, when present, is dropped
+    fn try_from(value: IoCattleManagementv3Token) -> Result<Self, Self::Error> {
+        let metadata: IoK8sApimachineryPkgApisMetaV1ObjectMeta = value.metadata.ok_or("missing metadata")?;
+
+        Ok(Token {
+            description: value.description,
+            expired: value.expired,
+            expires_at: value.expires_at,
+            id: metadata.name.ok_or("missing metadata.name")?,
+            ttl: value.ttl,
+            user_id: value.user_id,
+        })
+    }
+}
+
+/// The bearer token (`<name>:<secret>`) of a newly created API token
+///
+/// It is neither `Clone` nor `Serialize`, and its `Debug` output is redacted, so it only leaves
+/// this crate through `expose`.
+pub struct TokenSecret(String);
+
+impl TokenSecret {
+    /// Take the bearer token, to hand it to the system that will use it
+    pub fn expose(self) -> String {
+        self.0
+    }
+}
+
+impl std::fmt::Debug for TokenSecret {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("TokenSecret(<redacted>)")
+    }
+}
+
+/// A token returned by `create_token`, along with its secret
+#[derive(Debug)]
+pub struct CreatedToken {
+    pub token: Token,
+    pub secret: TokenSecret,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SECRET: &str = "x8kq2v7mzt4ws9lfp6n3rbc5hjd";
+
+    fn sample_iocattle_token(name: &str, secret: Option<&str>) -> IoCattleManagementv3Token {
+        IoCattleManagementv3Token {
+            api_version: Some("management.cattle.io/v3".to_string()),
+            description: Some("CI pipeline".to_string()),
+            kind: Some("Token".to_string()),
+            metadata: Some(IoK8sApimachineryPkgApisMetaV1ObjectMeta {
+                name: Some(name.to_string()),
+                ..Default::default()
+            }),
+            token: secret.map(str::to_string),
+            ttl: Some(3_600_000),
+            user_id: Some("u-ci123".to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_create_token_surfaces_the_secret_once() {
+        let rancher = crate::test_support::MockRancher::start().await;
+        rancher
+            .expect("POST", TOKENS_PATH)
+            .with_body_partial(serde_json::json!({
+                "description": "CI pipeline",
+                "ttl": 3_600_000,
+                "userId": "u-ci123",
+            }))
+            .expect_calls(1)
+            .returning_json(
+                201,
+                serde_json::to_value(sample_iocattle_token("token-abc12", Some(SECRET))).unwrap(),
+            )
+            .await;
+
+        let created = create_token(
+            &rancher.configuration(),
+            "u-ci123",
+            "CI pipeline",
+            Some(Duration::from_secs(3600)),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(created.token.id, "token-abc12");
+        assert!(!format!("{:?}", created).contains(SECRET));
+        assert!(!serde_json::to_string(&created.token).unwrap().contains(SECRET));
+        assert_eq!(created.secret.expose(), format!("token-abc12:{}", SECRET));
+    }
+
+    #[tokio::test]
+    async fn test_create_token_without_secret_fails() {
+        let rancher = crate::test_support::MockRancher::start().await;
+        rancher
+            .expect("POST", TOKENS_PATH)
+            .returning_json(
+                201,
+                serde_json::to_value(sample_iocattle_token("token-abc12", None)).unwrap(),
+            )
+            .await;
+
+        let result = create_token(&rancher.configuration(), "u-ci123", "CI pipeline", None).await;
+
+        assert!(matches!(result, Err(RancherError::Invalid(_))));
+    }
+
+    #[tokio::test]
+    async fn test_listed_tokens_never_expose_the_secret() {
+        let rancher = crate::test_support::MockRancher::start().await;
+        rancher
+            .expect("GET", TOKENS_PATH)
+            .returning(vec![sample_iocattle_token("token-abc12", Some(SECRET))])
+            .await;
+
+        let tokens = get_tokens(&rancher.configuration(), None, &RequestOptions::default()).await.unwrap();
+
+        assert_eq!(tokens.len(), 1);
+        assert!(!format!("{:?}", tokens).contains(SECRET));
+        assert!(!serde_json::to_string(&tokens).unwrap().contains(SECRET));
+    }
+}
@@ -0,0 +1,296 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use rancher_client::apis::configuration::Configuration;
+
+use rancher_client::{
+    apis::management_cattle_io_v3_api::{
+        read_management_cattle_io_v3_user, ReadManagementCattleIoV3UserError,
+    },
+    models::{
+        IoCattleManagementv3User, IoCattleManagementv3UserList,
+        IoK8sApimachineryPkgApisMetaV1ObjectMeta,
+    },
+};
+
+use crate::metadata::validate_name;
+use crate::client::RequestOptions;
+use crate::paginate::{ContinueToken, Paginator};
+use crate::request::{get_list, list_query, optional_object};
+use crate::retry::RetryPolicy;
+use crate::{
+    checked_limit, continue_token, RancherError, DEFAULT_PAGE_SIZE,
+};
+
+/// The API path of the user list
+const USERS_PATH: &str = "/apis/management.cattle.io/v3/users";
+
+/// Get a page of users
+///
+/// A page rate limited by the server is retried with the default `RetryPolicy`, and the request
+/// times out after the timeout of `options`.
+///
+/// # Arguments
+///
+/// * `configuration` - The configuration to use for the request
+/// * `field_selector` - A selector to restrict the users by their fields
+/// * `label_selector` - A selector to restrict the users by their labels
+/// * `limit` - The largest number of users to return, clamped to the largest page size of `options`
+/// * `continue_` - The token returned with the previous page, `None` for the first page
+/// * `options` - The timeout and largest page size of the request
+///
+/// # Returns
+///
+/// * `IoCattleManagementv3UserList` - The page of users
+/// * `Option<ContinueToken>` - The token to fetch the next page, `None` on the last page
+///
+/// # Errors
+///
+/// * `RancherError` - The error that occurred while trying to get the users
+///
+#[async_backtrace::framed]
+pub async fn get_users(
+    configuration: &Configuration,
+    field_selector: Option<&str>,
+    label_selector: Option<&str>,
+    limit: Option<i32>,
+    continue_: Option<&ContinueToken>,
+    options: &RequestOptions,
+) -> Result<(IoCattleManagementv3UserList, Option<ContinueToken>), RancherError> {
+    let limit = checked_limit(limit, options)?;
+    let query = list_query(field_selector, label_selector, limit, continue_.map(ContinueToken::as_str));
+    let list: IoCattleManagementv3UserList = get_list(
+        configuration,
+        USERS_PATH,
+        &query,
+        &RetryPolicy::default(),
+        options,
+    )
+    .await?;
+    let token = continue_token(list.metadata.as_deref()).map(ContinueToken::new);
+    Ok((list, token))
+}
+
+/// Get every user, following the continue token
+///
+/// A page rate limited by the server is retried with the default `RetryPolicy`, and every
+/// request times out after the timeout of `options`. Pages are `DEFAULT_PAGE_SIZE` items clamped
+/// to the largest page size of `options`.
+///
+/// # Arguments
+///
+/// * `configuration` - The configuration to use for the requests
+/// * `field_selector` - A selector to restrict the users by their fields
+/// * `label_selector` - A selector to restrict the users by their labels
+/// * `options` - The timeout and largest page size of the requests
+///
+/// # Returns
+///
+/// * `Vec<User>` - The users of every page
+///
+/// # Errors
+///
+/// * `RancherError` - The first error that occurred while fetching a page or converting a user
+///
+#[async_backtrace::framed]
+pub async fn get_all_users(
+    configuration: &Configuration,
+    field_selector: Option<&str>,
+    label_selector: Option<&str>,
+    options: &RequestOptions,
+) -> Result<Vec<User>, RancherError> {
+    let items = Paginator::new(move |continue_: Option<String>| async move {
+        let continue_ = continue_.map(ContinueToken::new);
+        let (list, next) = get_users(
+            configuration,
+            field_selector,
+            label_selector,
+            Some(DEFAULT_PAGE_SIZE),
+            continue_.as_ref(),
+            options,
+        )
+        .await?;
+        Ok::<_, RancherError>((list.items, next.map(ContinueToken::into_inner)))
+    })
+    .collect_all()
+    .await?;
+
+    items
+        .into_iter()
+        .map(|item| User::try_from(item).map_err(|e| RancherError::Invalid(e.to_string())))
+        .collect()
+}
+
+/// Get a user by its ID
+///
+/// # Arguments
+///
+/// * `configuration` - The configuration to use for the request
+/// * `name` - The ID of the user, e.g. `u-abc12`
+///
+/// # Returns
+///
+/// * `Option<User>` - The user, or `None` when it does not exist
+///
+/// # Errors
+///
+/// * `RancherError` - The error that occurred while reading or converting the user
+///
+#[async_backtrace::framed]
+pub async fn get_user(
+    configuration: &Configuration,
+    name: &str,
+) -> Result<Option<User>, RancherError> {
+    let result = read_management_cattle_io_v3_user(configuration, name, None, None).await;
+    optional_object::<IoCattleManagementv3User, _>(result, ReadManagementCattleIoV3UserError::UnknownValue)?
+        .map(User::try_from)
+        .transpose()
+        .map_err(RancherError::conversion)
+}
+
+/// Find a user by its username
+///
+/// The users API cannot select on `username`, so every user is listed and filtered client-side.
+///
+/// # Arguments
+///
+/// * `configuration` - The configuration to use for the requests
+/// * `username` - The username the user logs in with, e.g. `alice`
+///
+/// # Returns
+///
+/// * `Option<User>` - The user, or `None` when no user has this username
+///
+/// # Errors
+///
+/// * `RancherError` - The error that occurred while listing the users
+///
+#[async_backtrace::framed]
+pub async fn get_user_by_username(
+    configuration: &Configuration,
+    username: &str,
+) -> Result<Option<User>, RancherError> {
+    let users = get_all_users(configuration, None, None, &RequestOptions::default()).await?;
+    Ok(users
+        .into_iter()
+        .find(|user| user.username.as_deref() == Some(username)))
+}
+
+/// A Rancher user, local or from an external auth provider
+///
+/// The password is neither read nor written by this crate.
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq)]
+pub struct User {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub display_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub enabled: Option<bool>,
+    /// The ID of the user (`u-xxxxx`), used as `user_name` by the role template bindings
+    pub id: String,
+    /// The principals of the user, e.g. `local://u-xxxxx` or `github_user://1234`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub principal_ids: Option<Vec<String>>,
+    /// The name the user logs in with, only set for local users
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub username: Option<String>,
+}
+
+impl TryFrom<IoCattleManagementv3User> for User {
+    type Error = &'static str;
+
+    fn try_from(value: IoCattleManagementv3User) -> Result<Self, Self::Error> {
+        let metadata: IoK8sApimachineryPkgApisMetaV1ObjectMeta = value.metadata.ok_or("missing metadata")?;
+
+        Ok(User {
+            description: value.description,
+            display_name: value.display_name,
+            enabled: value.enabled,
+            id: metadata.name.ok_or("missing metadata.name")?,
+            principal_ids: value.principal_ids,
+            username: value.username,
+        })
+    }
+}
+
+impl TryFrom<User> for IoCattleManagementv3User {
+    type Error = &'static str;
+
+    fn try_from(value: User) -> Result<Self, Self::Error> {
+        validate_name(&value.id)?;
+        let metadata = IoK8sApimachineryPkgApisMetaV1ObjectMeta {
+            name: Some(value.id),
+            ..Default::default()
+        };
+
+        Ok(IoCattleManagementv3User {
+            api_version: Some("management.cattle.io/v3".to_string()),
+            description: value.description,
+            display_name: value.display_name,
+            enabled: value.enabled,
+            kind: Some("User".to_string()),
+            metadata: Some(metadata),
+            principal_ids: value.principal_ids,
+            username: value.username,
+            ..Default::default()
+        })
+    }
+}
+
+impl PartialEq<User> for IoCattleManagementv3User {
+    fn eq(&self, other: &User) -> bool {
+        self.metadata.as_ref().and_then(|m| m.name.as_deref()) == Some(other.id.as_str())
+            && self.description == other.description
+            && self.display_name == other.display_name
+            && self.enabled == other.enabled
+            && self.principal_ids == other.principal_ids
+            && self.username == other.username
+    }
+}
+
+impl PartialEq<IoCattleManagementv3User> for User {
+    fn eq(&self, other: &IoCattleManagementv3User) -> bool {
+        other == self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_iocattle_user(id: &str, username: &str) -> IoCattleManagementv3User {
+        IoCattleManagementv3User {
+            api_version: Some("management.cattle.io/v3".to_string()),
+            display_name: Some(username.to_uppercase()),
+            enabled: Some(true),
+            kind: Some("User".to_string()),
+            metadata: Some(IoK8sApimachineryPkgApisMetaV1ObjectMeta {
+                name: Some(id.to_string()),
+                ..Default::default()
+            }),
+            principal_ids: Some(vec![format!("local://{}", id)]),
+            username: Some(username.to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_get_user_by_username() {
+        let rancher = crate::test_support::MockRancher::start().await;
+        rancher
+            .expect("GET", USERS_PATH)
+            .returning(vec![
+                sample_iocattle_user("u-alice1", "alice"),
+                sample_iocattle_user("u-bob22", "bob"),
+            ])
+            .await;
+        let configuration = rancher.configuration();
+
+        let bob = get_user_by_username(&configuration, "bob").await.unwrap().unwrap();
+        assert_eq!(bob.id, "u-bob22");
+        assert_eq!(bob.principal_ids, Some(vec!["local://u-bob22".to_string()]));
+
+        assert!(get_user_by_username(&configuration, "carol").await.unwrap().is_none());
+    }
+}